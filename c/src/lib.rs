@@ -954,6 +954,7 @@ impl PFRendererOptions {
                 },
                 dest: *Box::from_raw(self.dest as *mut DestFramebuffer<D>),
                 show_debug_ui,
+                coverage_gamma: 1.0,
             }
         }
     }
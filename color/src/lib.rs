@@ -165,6 +165,20 @@ impl ColorF {
         ColorU { r: color[0] as u8, g: color[1] as u8, b: color[2] as u8, a: color[3] as u8 }
     }
 
+    /// Converts the red, green, and blue channels from sRGB encoding to linear light. Alpha is
+    /// left as is.
+    #[inline]
+    pub fn srgb_to_linear(&self) -> ColorF {
+        fn convert(value: f32) -> f32 {
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        }
+        ColorF::new(convert(self.r()), convert(self.g()), convert(self.b()), self.a())
+    }
+
     #[inline]
     pub fn lerp(&self, other: ColorF, t: f32) -> ColorF {
         ColorF(self.0 + (other.0 - self.0) * F32x4::splat(t))
@@ -30,7 +30,7 @@ use std::mem;
 ///
 /// The names "outline" and "contour" come from the TrueType specification. They were chosen to
 /// avoid conflicting with the Rust use of "path" for filesystem paths.
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct Outline {
     pub(crate) contours: Vec<Contour>,
     pub(crate) bounds: RectF,
@@ -39,7 +39,7 @@ pub struct Outline {
 /// An individual subpath, consisting of a series of endpoints and/or control points. Contours can
 /// be either open (first and last points disconnected) or closed (first point implicitly joined to
 /// last point with a line).
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct Contour {
     pub(crate) points: Vec<Vector2F>,
     pub(crate) flags: Vec<PointFlags>,
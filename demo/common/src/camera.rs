@@ -13,19 +13,34 @@
 // TODO(#140, pcwalton): Move some of this out of the demo and into the library
 // proper.
 
+use crate::{Fit, Options};
+use crate::presets::CameraPreset;
 use crate::window::{OcularTransform, View};
 use pathfinder_geometry::vector::{Vector2I, Vector4F};
 use pathfinder_geometry::rect::RectF;
 use pathfinder_geometry::transform2d::Transform2F;
 use pathfinder_geometry::transform3d::{Perspective, Transform4F};
+use pathfinder_renderer::options::RenderTransform;
 use std::f32::consts::FRAC_PI_4;
+use std::mem;
+use std::time::Instant;
 
-const NEAR_CLIP_PLANE: f32 = 0.01;
-const FAR_CLIP_PLANE: f32 = 10.0;
+// The ratio of the default near clip plane distance to the default far one. Keeping this fixed
+// preserves depth buffer precision regardless of the size of the scene.
+const DEFAULT_NEAR_FAR_RATIO: f32 = 0.001;
 
 // Half of the eye separation distance.
 const DEFAULT_EYE_OFFSET: f32 = 0.025;
 
+// How much each key press changes the separation of the eyes in VR, and how far the separation
+// can be adjusted either way, as offsets from the half separation supplied by the window.
+const EYE_OFFSET_ADJUSTMENT_STEP: f32 = 0.0025;
+const MIN_EYE_OFFSET_ADJUSTMENT: f32 = -DEFAULT_EYE_OFFSET;
+const MAX_EYE_OFFSET_ADJUSTMENT: f32 = DEFAULT_EYE_OFFSET * 2.0;
+
+// How long it takes to animate between 2D and 3D modes, in seconds.
+const MODE_TRANSITION_DURATION: f32 = 0.3;
+
 pub enum Camera {
     TwoD(Transform2F),
     ThreeD {
@@ -40,32 +55,73 @@ pub enum Camera {
         modelview_transform: CameraTransform3D,
         // The camera's velocity (in world coordinates)
         velocity: Vector4F,
+        // A user adjustment to half of the eye separation, applied on top of the eye transforms.
+        eye_offset_adjustment: f32,
     },
+    // An animation between two cameras. Once the animation finishes, this is replaced with the
+    // destination camera.
+    Transition(Box<CameraTransition>),
+}
+
+pub struct CameraTransition {
+    from: Camera,
+    to: Camera,
+    viewport_size: Vector2I,
+    start_time: Instant,
 }
 
 impl Camera {
-    pub fn new(mode: Mode, view_box: RectF, viewport_size: Vector2I) -> Camera {
+    pub fn new(mode: Mode, view_box: RectF, viewport_size: Vector2I, options: &Options)
+               -> Camera {
         if mode == Mode::TwoD {
-            Camera::new_2d(view_box, viewport_size)
+            Camera::new_2d(view_box, viewport_size, options)
         } else {
-            Camera::new_3d(mode, view_box, viewport_size)
+            Camera::new_3d(mode, view_box, viewport_size, options)
         }
     }
 
-    fn new_2d(view_box: RectF, viewport_size: Vector2I) -> Camera {
-        let scale = i32::min(viewport_size.x(), viewport_size.y()) as f32 *
-            scale_factor_for_view_box(view_box);
-        let origin = viewport_size.to_f32() * 0.5 - view_box.size() * (scale * 0.5);
-        Camera::TwoD(Transform2F::from_scale(scale).translate(origin))
+    // Frames the view box in the viewport, unless fitting was turned off, in which case one scene
+    // unit is one device pixel.
+    fn new_2d(view_box: RectF, viewport_size: Vector2I, options: &Options) -> Camera {
+        if options.no_fit {
+            return Camera::TwoD(Transform2F::default());
+        }
+
+        let viewport_size_f = viewport_size.to_f32();
+        let width_scale = viewport_size_f.x() / view_box.width();
+        let height_scale = viewport_size_f.y() / view_box.height();
+        let scale = match options.fit {
+            Fit::Auto => f32::min(width_scale, height_scale),
+            Fit::Width => width_scale,
+            Fit::Height => height_scale,
+        } * fit_scale_for_padding(options.fit_padding);
+        let origin = viewport_size_f * 0.5 - view_box.size() * (scale * 0.5);
+        Camera::TwoD(Transform2F::from_translation(-view_box.origin()).scale(scale)
+                                                                       .translate(origin))
     }
 
-    fn new_3d(mode: Mode, view_box: RectF, viewport_size: Vector2I) -> Camera {
+    fn new_3d(mode: Mode, view_box: RectF, viewport_size: Vector2I, options: &Options)
+              -> Camera {
         let viewport_count = mode.viewport_count();
 
         let fov_y = FRAC_PI_4;
         let aspect = viewport_size.x() as f32 / viewport_size.y() as f32;
-        let projection =
-            Transform4F::from_perspective(fov_y, aspect, NEAR_CLIP_PLANE, FAR_CLIP_PLANE);
+        let (near_clip_plane, far_clip_plane) = clip_planes(view_box, options);
+        let projection = if options.orthographic && mode != Mode::VR {
+            // Show as much of the scene as the perspective projection would at the distance the
+            // camera starts at.
+            let camera_distance = initial_camera_distance(options.fit_padding);
+            let half_height = camera_distance * f32::tan(fov_y * 0.5);
+            let half_width = half_height * aspect;
+            Transform4F::from_ortho(-half_width,
+                                    half_width,
+                                    -half_height,
+                                    half_height,
+                                    near_clip_plane,
+                                    far_clip_plane)
+        } else {
+            Transform4F::from_perspective(fov_y, aspect, near_clip_plane, far_clip_plane)
+        };
         let perspective = Perspective::new(&projection, viewport_size);
 
         // Create a scene transform by moving the camera back from the center of the eyes so that
@@ -96,18 +152,55 @@ impl Camera {
         Camera::ThreeD {
             scene_transform,
             eye_transforms,
-            modelview_transform: CameraTransform3D::new(view_box),
+            modelview_transform: CameraTransform3D::new(view_box, options.fit_padding),
             velocity: Vector4F::default(),
+            eye_offset_adjustment: 0.0,
         }
     }
 
+    // Widens (`steps` > 0) or narrows (`steps` < 0) the separation of the eyes. Returns true if
+    // the separation changed.
+    pub fn adjust_eye_offset(&mut self, steps: i32) -> bool {
+        match *self {
+            Camera::ThreeD { ref mut eye_offset_adjustment, ref eye_transforms, .. }
+                    if eye_transforms.len() > 1 => {
+                let new_adjustment = *eye_offset_adjustment +
+                    steps as f32 * EYE_OFFSET_ADJUSTMENT_STEP;
+                let new_adjustment = f32::max(f32::min(new_adjustment, MAX_EYE_OFFSET_ADJUSTMENT),
+                                              MIN_EYE_OFFSET_ADJUSTMENT);
+                let changed = new_adjustment != *eye_offset_adjustment;
+                *eye_offset_adjustment = new_adjustment;
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    pub fn new_transition(from: Camera, to: Camera, viewport_size: Vector2I) -> Camera {
+        Camera::Transition(Box::new(CameraTransition {
+            from,
+            to,
+            viewport_size,
+            start_time: Instant::now(),
+        }))
+    }
+
     pub fn is_3d(&self) -> bool {
         match *self {
             Camera::ThreeD { .. } => true,
             Camera::TwoD { .. } => false,
+            Camera::Transition(ref transition) => transition.from.is_3d(),
+        }
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        match *self {
+            Camera::Transition(_) => true,
+            Camera::TwoD { .. } | Camera::ThreeD { .. } => false,
         }
     }
 
+    // While a transition is in progress, this reports the mode being transitioned *from*.
     pub fn mode(&self) -> Mode {
         match *self {
             Camera::ThreeD {
@@ -115,6 +208,92 @@ impl Camera {
             } if eye_transforms.len() >= 2 => Mode::VR,
             Camera::ThreeD { .. } => Mode::ThreeD,
             Camera::TwoD { .. } => Mode::TwoD,
+            Camera::Transition(ref transition) => transition.from.mode(),
+        }
+    }
+
+    // The mode that this camera will be in once any transition finishes.
+    pub fn target_mode(&self) -> Mode {
+        match *self {
+            Camera::Transition(ref transition) => transition.to.target_mode(),
+            _ => self.mode(),
+        }
+    }
+
+    // Advances any in-progress transition, replacing it with the destination camera once it
+    // finishes. Returns true if the camera was animating and the scene needs to be redrawn.
+    pub fn update_transition(&mut self) -> bool {
+        let finished = match *self {
+            Camera::Transition(ref transition) => transition.progress() >= 1.0,
+            Camera::TwoD { .. } | Camera::ThreeD { .. } => return false,
+        };
+        if finished {
+            if let Camera::Transition(transition) =
+                    mem::replace(self, Camera::TwoD(Transform2F::default())) {
+                *self = transition.to;
+            }
+        }
+        true
+    }
+
+    // Makes the 3D camera render the scene with exactly the given transform, from scene
+    // coordinates to normalized device coordinates. Moving the camera afterward moves it from
+    // there.
+    pub fn set_transform_3d(&mut self, transform: Transform4F) {
+        if let Camera::ThreeD { ref mut scene_transform, ref modelview_transform, .. } = *self {
+            let camera_transform = scene_transform.modelview_to_eye *
+                modelview_transform.to_transform();
+            scene_transform.perspective.transform = transform * camera_transform.inverse();
+        }
+    }
+
+    pub fn render_transform(&self) -> RenderTransform {
+        match *self {
+            Camera::TwoD(transform) => RenderTransform::Transform2D(transform),
+            Camera::ThreeD { ref scene_transform, ref modelview_transform, .. } => {
+                let perspective = scene_transform.perspective *
+                    scene_transform.modelview_to_eye *
+                    modelview_transform.to_transform();
+                RenderTransform::Perspective(perspective)
+            }
+            Camera::Transition(ref transition) => {
+                let viewport_size = transition.viewport_size;
+                let from = to_perspective(transition.from.render_transform(), viewport_size);
+                let to = to_perspective(transition.to.render_transform(), viewport_size);
+
+                // Ease in and out (smoothstep).
+                let t = transition.progress();
+                let t = t * t * (3.0 - 2.0 * t);
+
+                let transform = to.transform.lerp(t, &from.transform);
+                RenderTransform::Perspective(Perspective::new(&transform, viewport_size))
+            }
+        }
+    }
+}
+
+impl CameraTransition {
+    // Returns the fraction of the transition that has elapsed, from 0.0 to 1.0.
+    fn progress(&self) -> f32 {
+        let elapsed = self.start_time.elapsed().as_secs_f32();
+        f32::min(elapsed / MODE_TRANSITION_DURATION, 1.0)
+    }
+}
+
+// Converts a render transform to a perspective transform so that 2D and 3D cameras can be
+// interpolated.
+fn to_perspective(transform: RenderTransform, viewport_size: Vector2I) -> Perspective {
+    match transform {
+        RenderTransform::Perspective(perspective) => perspective,
+        RenderTransform::Transform2D(transform) => {
+            // Map device pixels to normalized device coordinates.
+            let viewport_size_f = viewport_size.to_f32();
+            let to_ndc = Transform4F::from_translation(Vector4F::new(-1.0, 1.0, 0.0, 1.0)) *
+                Transform4F::from_scale(Vector4F::new(2.0 / viewport_size_f.x(),
+                                                      -2.0 / viewport_size_f.y(),
+                                                      1.0,
+                                                      1.0));
+            Perspective::new(&(to_ndc * transform.to_3d()), viewport_size)
         }
     }
 }
@@ -124,21 +303,24 @@ pub struct CameraTransform3D {
     position: Vector4F,
     pub yaw: f32,
     pub pitch: f32,
+    // Rotation about the line of sight, in radians.
+    pub roll: f32,
     scale: f32,
 }
 
 impl CameraTransform3D {
-    fn new(view_box: RectF) -> CameraTransform3D {
+    fn new(view_box: RectF, fit_padding: f32) -> CameraTransform3D {
         let scale = scale_factor_for_view_box(view_box);
         CameraTransform3D {
             position: Vector4F::new(
                 0.5 * view_box.max_x(),
                 -0.5 * view_box.max_y(),
-                1.5 / scale,
+                1.5 / (scale * fit_scale_for_padding(fit_padding)),
                 1.0,
             ),
             yaw: 0.0,
             pitch: 0.0,
+            roll: 0.0,
             scale,
         }
     }
@@ -146,17 +328,34 @@ impl CameraTransform3D {
     pub fn offset(&mut self, vector: Vector4F) -> bool {
         let update = !vector.is_zero();
         if update {
-            let rotation = Transform4F::from_rotation(-self.yaw, -self.pitch, 0.0);
+            let rotation = Transform4F::from_rotation(-self.yaw, -self.pitch, -self.roll);
             self.position = self.position + rotation * vector;
         }
         update
     }
 
+    pub fn to_preset(&self) -> CameraPreset {
+        CameraPreset {
+            position: [self.position.x(), self.position.y(), self.position.z()],
+            yaw: self.yaw,
+            pitch: self.pitch,
+            roll: self.roll,
+        }
+    }
+
+    pub fn apply_preset(&mut self, preset: &CameraPreset) {
+        let position = preset.position;
+        self.position = Vector4F::new(position[0], position[1], position[2], 1.0);
+        self.yaw = preset.yaw;
+        self.pitch = preset.pitch;
+        self.roll = preset.roll;
+    }
+
     pub fn to_transform(&self) -> Transform4F {
         let flip = Vector4F::new(1.0, -1.0, 1.0, 1.0);
         Transform4F::from_scale(flip).translate(-self.position)
                                      .uniform_scale(2.0 * self.scale)
-                                     .rotate(self.yaw, self.pitch, 0.0)
+                                     .rotate(self.yaw, self.pitch, self.roll)
     }
 }
 
@@ -186,3 +385,53 @@ impl Mode {
 pub fn scale_factor_for_view_box(view_box: RectF) -> f32 {
     1.0 / f32::min(view_box.size().x(), view_box.size().y())
 }
+
+// Returns the distance, in world units, from the scene at which the 3D camera starts. (See
+// `CameraTransform3D::new()`.)
+fn initial_camera_distance(fit_padding: f32) -> f32 {
+    3.0 / fit_scale_for_padding(fit_padding)
+}
+
+// Returns the near and far clip plane distances, in world units. Unless overridden by the user,
+// the far plane is placed so that the whole scene stays visible while the camera moves around it
+// at roughly its starting distance.
+pub fn clip_planes(view_box: RectF, options: &Options) -> (f32, f32) {
+    // World units are scaled such that the shorter side of the view box is 2 units long.
+    let scene_extent = 2.0 * f32::max(view_box.width(), view_box.height()) *
+        scale_factor_for_view_box(view_box);
+    let default_far_clip_plane =
+        2.0 * (initial_camera_distance(options.fit_padding) + scene_extent);
+
+    let far_clip_plane = options.far_clip_plane.unwrap_or(default_far_clip_plane);
+    let near_clip_plane =
+        options.near_clip_plane.unwrap_or(far_clip_plane * DEFAULT_NEAR_FAR_RATIO);
+    (near_clip_plane, far_clip_plane)
+}
+
+// Returns the factor by which to shrink the scene so that `fit_padding` of the viewport is left
+// empty on each side.
+fn fit_scale_for_padding(fit_padding: f32) -> f32 {
+    f32::max(1.0 - 2.0 * fit_padding, 0.0)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Options;
+    use pathfinder_geometry::rect::RectF;
+    use pathfinder_geometry::vector::{Vector2F, vec2f, vec2i};
+    use super::Camera;
+
+    #[test]
+    fn test_2d_camera_frames_offset_view_box() {
+        let options = Options { fit_padding: 0.0, ..Options::default() };
+        let view_box = RectF::new(vec2f(100.0, 50.0), vec2f(200.0, 100.0));
+        let transform = match Camera::new_2d(view_box, vec2i(400, 200), &options) {
+            Camera::TwoD(transform) => transform,
+            _ => unreachable!(),
+        };
+
+        let framed = transform * view_box;
+        assert!((framed.origin() - Vector2F::zero()).length() < 0.001);
+        assert!((framed.lower_right() - vec2f(400.0, 200.0)).length() < 0.001);
+    }
+}
@@ -12,34 +12,48 @@
 
 use pathfinder_renderer::concurrent::executor::{Executor, SequentialExecutor};
 use pathfinder_renderer::concurrent::rayon::RayonExecutor;
-use rayon::ThreadPoolBuilder;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 
 pub struct DemoExecutor {
-    sequential_mode: bool,
+    // Each executor has its own pool, rather than using the global one, so that the thread count
+    // can be changed by replacing the executor. This is `None` in sequential mode.
+    thread_pool: Option<ThreadPool>,
 }
 
 impl DemoExecutor {
+    // A thread count of 0 or 1 selects sequential mode, in which all tasks run on the calling
+    // thread in a deterministic order.
     pub fn new(thread_count: Option<usize>) -> DemoExecutor {
-        let sequential_mode = thread_count == Some(1);
-        if !sequential_mode {
-            let mut thread_pool_builder = ThreadPoolBuilder::new();
-            if let Some(thread_count) = thread_count {
-                thread_pool_builder = thread_pool_builder.num_threads(thread_count);
+        let thread_pool = match thread_count {
+            Some(0) | Some(1) => None,
+            _ => {
+                let mut thread_pool_builder = ThreadPoolBuilder::new();
+                if let Some(thread_count) = thread_count {
+                    thread_pool_builder = thread_pool_builder.num_threads(thread_count);
+                }
+                Some(thread_pool_builder.build().unwrap())
             }
-            thread_pool_builder.build_global().unwrap();
-        }
+        };
 
-        DemoExecutor { sequential_mode }
+        DemoExecutor { thread_pool }
+    }
+
+    pub fn thread_count(&self) -> usize {
+        match self.thread_pool {
+            None => 1,
+            Some(ref thread_pool) => thread_pool.current_num_threads(),
+        }
     }
 }
 
 impl Executor for DemoExecutor {
     fn build_vector<T, F>(&self, length: usize, builder: F) -> Vec<T>
                           where T: Send, F: Fn(usize) -> T + Send + Sync {
-        if self.sequential_mode {
-            SequentialExecutor.build_vector(length, builder)
-        } else {
-            RayonExecutor.build_vector(length, builder)
+        match self.thread_pool {
+            None => SequentialExecutor.build_vector(length, builder),
+            Some(ref thread_pool) => {
+                thread_pool.install(|| RayonExecutor.build_vector(length, builder))
+            }
         }
     }
 }
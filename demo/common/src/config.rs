@@ -0,0 +1,96 @@
+// pathfinder/demo/common/src/config.rs
+//
+// Copyright © 2020 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Options read from a TOML file, so that a setup can be shared and reproduced.
+
+use crate::camera::Mode;
+use crate::{BackgroundColor, Options, UIVisibility};
+use std::fs;
+use std::path::Path;
+use toml::Value;
+
+// The file looks like:
+//
+//     jobs = 4
+//     mode = "3d"
+//     background = "dark"
+//     ui = "stats"
+//     ground = false
+//     antialiasing = true
+//     stem_darkening = false
+//     subpixel_aa = false
+//     srgb = true
+//
+// Every key is optional. Unknown keys are errors, so that misspellings don't go unnoticed.
+pub fn apply_config_file(options: &mut Options, path: &Path) -> Result<(), String> {
+    let data = fs::read_to_string(path).map_err(|error| {
+        format!("couldn't read the config file \"{}\": {}", path.display(), error)
+    })?;
+    let table = match data.parse() {
+        Ok(Value::Table(table)) => table,
+        Ok(_) => return Err(format!("\"{}\" isn't a TOML table", path.display())),
+        Err(error) => return Err(format!("couldn't parse \"{}\": {}", path.display(), error)),
+    };
+
+    for (key, value) in &table {
+        apply_config_value(options, key, value).map_err(|error| {
+            format!("\"{}\": `{}` {}", path.display(), key, error)
+        })?;
+    }
+    Ok(())
+}
+
+fn apply_config_value(options: &mut Options, key: &str, value: &Value) -> Result<(), String> {
+    match key {
+        "jobs" => {
+            let jobs = value.as_integer().filter(|&jobs| jobs >= 0);
+            options.jobs = Some(jobs.ok_or("must be a nonnegative integer")? as usize);
+        }
+        "mode" => {
+            options.mode = match as_str(value)? {
+                "2d" => Mode::TwoD,
+                "3d" => Mode::ThreeD,
+                "vr" => Mode::VR,
+                _ => return Err("must be \"2d\", \"3d\", or \"vr\"".to_owned()),
+            };
+        }
+        "background" => {
+            options.background_color = match as_str(value)? {
+                "light" => BackgroundColor::Light,
+                "dark" => BackgroundColor::Dark,
+                "transparent" => BackgroundColor::Transparent,
+                _ => return Err("must be \"light\", \"dark\", or \"transparent\"".to_owned()),
+            };
+        }
+        "ui" => {
+            options.ui = match as_str(value)? {
+                "none" => UIVisibility::None,
+                "stats" => UIVisibility::Stats,
+                "all" => UIVisibility::All,
+                _ => return Err("must be \"none\", \"stats\", or \"all\"".to_owned()),
+            };
+        }
+        "ground" => options.show_ground = as_bool(value)?,
+        "antialiasing" => options.no_aa = !as_bool(value)?,
+        "stem_darkening" => options.no_stem_darkening = !as_bool(value)?,
+        "subpixel_aa" => options.no_subpixel_aa = !as_bool(value)?,
+        "srgb" => options.srgb = as_bool(value)?,
+        _ => return Err("isn't a known option".to_owned()),
+    }
+    Ok(())
+}
+
+fn as_str(value: &Value) -> Result<&str, String> {
+    value.as_str().ok_or_else(|| "must be a string".to_owned())
+}
+
+fn as_bool(value: &Value) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| "must be true or false".to_owned())
+}
@@ -22,6 +22,9 @@ where
     pub gridline_count_uniform: D::Uniform,
     pub ground_color_uniform: D::Uniform,
     pub gridline_color_uniform: D::Uniform,
+    pub horizon_color_uniform: D::Uniform,
+    pub fade_uniform: D::Uniform,
+    pub fade_distance_uniform: D::Uniform,
 }
 
 impl<D> GroundProgram<D>
@@ -34,12 +37,18 @@ where
         let gridline_count_uniform = device.get_uniform(&program, "GridlineCount");
         let ground_color_uniform = device.get_uniform(&program, "GroundColor");
         let gridline_color_uniform = device.get_uniform(&program, "GridlineColor");
+        let horizon_color_uniform = device.get_uniform(&program, "HorizonColor");
+        let fade_uniform = device.get_uniform(&program, "Fade");
+        let fade_distance_uniform = device.get_uniform(&program, "FadeDistance");
         GroundProgram {
             program,
             transform_uniform,
             gridline_count_uniform,
             ground_color_uniform,
             gridline_color_uniform,
+            horizon_color_uniform,
+            fade_uniform,
+            fade_distance_uniform,
         }
     }
 }
@@ -80,3 +89,128 @@ where
         GroundVertexArray { vertex_array }
     }
 }
+
+pub struct DistortionProgram<D>
+where
+    D: Device,
+{
+    pub program: D::Program,
+    pub source_texture: D::TextureParameter,
+    pub coefficients_uniform: D::Uniform,
+    pub aspect_ratio_uniform: D::Uniform,
+}
+
+impl<D> DistortionProgram<D>
+where
+    D: Device,
+{
+    pub fn new(device: &D, resources: &dyn ResourceLoader) -> DistortionProgram<D> {
+        let program = device.create_raster_program(resources, "demo_distortion");
+        let source_texture = device.get_texture_parameter(&program, "Source");
+        let coefficients_uniform = device.get_uniform(&program, "Coefficients");
+        let aspect_ratio_uniform = device.get_uniform(&program, "AspectRatio");
+        DistortionProgram {
+            program,
+            source_texture,
+            coefficients_uniform,
+            aspect_ratio_uniform,
+        }
+    }
+}
+
+pub struct DistortionVertexArray<D>
+where
+    D: Device,
+{
+    pub vertex_array: D::VertexArray,
+}
+
+impl<D> DistortionVertexArray<D>
+where
+    D: Device,
+{
+    pub fn new(
+        device: &D,
+        distortion_program: &DistortionProgram<D>,
+        quad_vertex_positions_buffer: &D::Buffer,
+        quad_vertex_indices_buffer: &D::Buffer,
+    ) -> DistortionVertexArray<D> {
+        let vertex_array = device.create_vertex_array();
+
+        let position_attr =
+            device.get_vertex_attr(&distortion_program.program, "Position").unwrap();
+
+        device.bind_buffer(&vertex_array, quad_vertex_positions_buffer, BufferTarget::Vertex);
+        device.configure_vertex_attr(&vertex_array, &position_attr, &VertexAttrDescriptor {
+            size: 2,
+            class: VertexAttrClass::Int,
+            attr_type: VertexAttrType::I16,
+            stride: 4,
+            offset: 0,
+            divisor: 0,
+            buffer_index: 0,
+        });
+        device.bind_buffer(&vertex_array, quad_vertex_indices_buffer, BufferTarget::Index);
+
+        DistortionVertexArray { vertex_array }
+    }
+}
+
+pub struct ColorMatrixProgram<D>
+where
+    D: Device,
+{
+    pub program: D::Program,
+    pub source_texture: D::TextureParameter,
+    pub color_matrix_uniform: D::Uniform,
+}
+
+impl<D> ColorMatrixProgram<D>
+where
+    D: Device,
+{
+    pub fn new(device: &D, resources: &dyn ResourceLoader) -> ColorMatrixProgram<D> {
+        let program = device.create_raster_program(resources, "demo_color_matrix");
+        let source_texture = device.get_texture_parameter(&program, "Source");
+        let color_matrix_uniform = device.get_uniform(&program, "ColorMatrix");
+        ColorMatrixProgram { program, source_texture, color_matrix_uniform }
+    }
+}
+
+pub struct ColorMatrixVertexArray<D>
+where
+    D: Device,
+{
+    pub vertex_array: D::VertexArray,
+}
+
+impl<D> ColorMatrixVertexArray<D>
+where
+    D: Device,
+{
+    pub fn new(
+        device: &D,
+        color_matrix_program: &ColorMatrixProgram<D>,
+        quad_vertex_positions_buffer: &D::Buffer,
+        quad_vertex_indices_buffer: &D::Buffer,
+    ) -> ColorMatrixVertexArray<D> {
+        let vertex_array = device.create_vertex_array();
+
+        let position_attr =
+            device.get_vertex_attr(&color_matrix_program.program, "Position").unwrap();
+
+        device.bind_buffer(&vertex_array, quad_vertex_positions_buffer, BufferTarget::Vertex);
+        device.configure_vertex_attr(&vertex_array, &position_attr, &VertexAttrDescriptor {
+            size: 2,
+            class: VertexAttrClass::Int,
+            attr_type: VertexAttrType::I16,
+            stride: 4,
+            offset: 0,
+            divisor: 0,
+            buffer_index: 0,
+        });
+        device.bind_buffer(&vertex_array, quad_vertex_indices_buffer, BufferTarget::Index);
+
+        ColorMatrixVertexArray { vertex_array }
+    }
+}
@@ -0,0 +1,117 @@
+// pathfinder/demo/common/src/fonts.rs
+//
+// Copyright © 2020 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reporting of the font families that SVG files refer to.
+
+use font_kit::source::SystemSource;
+
+// usvg maps these to its own default fonts rather than looking them up by name.
+const GENERIC_FONT_FAMILIES: [&str; 5] = ["serif", "sans-serif", "monospace", "cursive", "fantasy"];
+
+// Prints each font family that the SVG source names, in order of first appearance, and whether
+// it's installed. usvg silently substitutes its default font for families that aren't.
+//
+// usvg converts text to paths while parsing and doesn't report which fonts it picked, so this
+// works from the source instead.
+pub fn print_fonts_used(svg_data: &[u8]) {
+    let families = referenced_font_families(&String::from_utf8_lossy(svg_data));
+    if families.is_empty() {
+        println!("fonts: none");
+        return;
+    }
+
+    let source = SystemSource::new();
+    for family in families {
+        let status = if GENERIC_FONT_FAMILIES.contains(&&*family.to_lowercase()) {
+            "generic"
+        } else if source.select_family_by_name(&family).is_ok() {
+            "found"
+        } else {
+            "MISSING (falls back to the default font)"
+        };
+        println!("font \"{}\": {}", family, status);
+    }
+}
+
+// Finds the values of `font-family` presentation attributes and CSS properties. This is a textual
+// scan rather than a full parse, so it also picks up families that are commented out.
+fn referenced_font_families(source: &str) -> Vec<String> {
+    let mut families: Vec<String> = vec![];
+    let mut rest = source;
+    while let Some(index) = rest.find("font-family") {
+        rest = rest[index + "font-family".len()..].trim_start();
+        let value = if rest.starts_with('=') {
+            // A presentation attribute, whose value is quoted.
+            let quoted = rest[1..].trim_start();
+            let quote = match quoted.chars().next() {
+                Some(quote) if quote == '"' || quote == '\'' => quote,
+                _ => continue,
+            };
+            match quoted[1..].find(quote) {
+                Some(end) => &quoted[1..(end + 1)],
+                None => break,
+            }
+        } else if rest.starts_with(':') {
+            // A CSS property, in a `style` attribute or element.
+            &rest[1..]
+        } else {
+            continue;
+        };
+
+        for family in parse_font_family_list(value) {
+            if !families.contains(&family) {
+                families.push(family);
+            }
+        }
+    }
+    families
+}
+
+// Parses a comma-separated list of quoted or unquoted family names, stopping at the first
+// character that can't continue the list.
+fn parse_font_family_list(value: &str) -> Vec<String> {
+    let mut families = vec![];
+    let mut chars = value.chars().peekable();
+    loop {
+        while chars.peek().map_or(false, |c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let mut family = String::new();
+        match chars.peek().cloned() {
+            Some(quote) if quote == '"' || quote == '\'' => {
+                chars.next();
+                family.extend(chars.by_ref().take_while(|&c| c != quote));
+            }
+            _ => {
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '-' || c == '_' || c == ' ') {
+                        break;
+                    }
+                    family.push(c);
+                    chars.next();
+                }
+            }
+        }
+
+        let family = family.trim();
+        if !family.is_empty() {
+            families.push(family.to_owned());
+        }
+
+        while chars.peek().map_or(false, |c| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek() != Some(&',') {
+            return families;
+        }
+        chars.next();
+    }
+}
@@ -18,11 +18,18 @@ pub use crate::camera::Mode;
 
 use crate::camera::Camera;
 use crate::concurrent::DemoExecutor;
-use crate::device::{GroundProgram, GroundVertexArray};
+use crate::device::{ColorMatrixProgram, ColorMatrixVertexArray, DistortionProgram};
+use crate::device::{DistortionVertexArray, GroundProgram, GroundVertexArray};
+use crate::presets::CameraPresets;
+use crate::renderer::{SceneCacheKey, Watermark, create_background_gradient_texture};
+use crate::renderer::create_background_image_texture;
+use crate::stats::{Benchmark, StatsRecorder};
 use crate::ui::{DemoUIModel, DemoUIPresenter, ScreenshotInfo, ScreenshotType, UIAction};
-use crate::window::{Event, Keycode, DataPath, Window, WindowSize};
+use crate::window::{Event, Keycode, DataPath, MessageTimer, Window, WindowSize};
 use clap::{App, Arg};
-use pathfinder_content::effects::DEFRINGING_KERNEL_CORE_GRAPHICS;
+use pathfinder_color::ColorU;
+use pathfinder_color::matrix::ColorMatrix;
+use pathfinder_content::effects::{DEFRINGING_KERNEL_CORE_GRAPHICS, DEFRINGING_KERNEL_FREETYPE};
 use pathfinder_content::effects::PatternFilter;
 use pathfinder_content::effects::STEM_DARKENING_FACTORS;
 use pathfinder_content::outline::Outline;
@@ -31,28 +38,38 @@ use pathfinder_content::render_target::RenderTargetId;
 use pathfinder_export::{Export, FileFormat};
 use pathfinder_geometry::rect::{RectF, RectI};
 use pathfinder_geometry::transform2d::Transform2F;
-use pathfinder_geometry::transform3d::Transform4F;
+use pathfinder_geometry::transform3d::{Perspective, Transform4F};
 use pathfinder_geometry::vector::{Vector2F, Vector2I, Vector4F, vec2f, vec2i};
-use pathfinder_gpu::Device;
+use pathfinder_gl::GLVersion;
+use pathfinder_gpu::{DepthFunc, Device, TextureFormat};
 use pathfinder_renderer::concurrent::scene_proxy::SceneProxy;
 use pathfinder_renderer::gpu::options::{DestFramebuffer, RendererLevel};
 use pathfinder_renderer::gpu::options::{RendererMode, RendererOptions};
+use pathfinder_renderer::gpu::perf::RenderStats;
 use pathfinder_renderer::gpu::renderer::{DebugUIPresenterInfo, Renderer};
-use pathfinder_renderer::options::{BuildOptions, RenderTransform};
+use pathfinder_renderer::options::{AntialiasingQuality, BuildOptions, RenderTransform};
 use pathfinder_renderer::paint::Paint;
-use pathfinder_renderer::scene::{DrawPath, RenderTarget, Scene};
+use pathfinder_renderer::scene::{DrawPath, DrawPathId, RenderTarget, Scene};
+use pathfinder_renderer::{TILE_HEIGHT, TILE_WIDTH};
 use pathfinder_resources::ResourceLoader;
-use pathfinder_svg::SVGScene;
+use pathfinder_svg::{BuildResultFlags, SVGScene};
 use pathfinder_ui::{MousePosition, UIEvent};
+use std::collections::VecDeque;
+use std::f32::consts::PI;
 use std::fs::File;
-use std::io::BufWriter;
-use std::path::PathBuf;
+use std::io::{self, BufWriter, Read, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use usvg::{Options as UsvgOptions, Tree as SvgTree};
 use pdf::file::File as PdfFile;
 use pdf_render::Cache as PdfRenderCache;
 
+#[cfg(feature = "svgz")]
+use flate2::read::GzDecoder;
+
 #[cfg(any(not(target_os = "macos"), feature = "pf-gl"))]
 use pathfinder_gl::GLDevice as DeviceImpl;
 #[cfg(all(target_os = "macos", not(feature = "pf-gl")))]
@@ -60,27 +77,89 @@ use pathfinder_metal::MetalDevice as DeviceImpl;
 
 static DEFAULT_SVG_VIRTUAL_PATH: &'static str = "svg/Ghostscript_Tiger.svg";
 
+// The first two bytes of gzip-compressed data, such as an SVGZ file.
+#[cfg(feature = "svgz")]
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+// The directory that sample SVGs are loaded from, and the samples to use if the resource loader
+// can't enumerate it.
+static SAMPLE_SVG_VIRTUAL_DIRECTORY: &'static str = "svg";
+static FALLBACK_SAMPLE_SVG_VIRTUAL_PATHS: [&'static str; 4] = [
+    "svg/Ghostscript_Tiger.svg",
+    "svg/julius-caesar-with-bg.svg",
+    "svg/nba-notext.svg",
+    "svg/paper.svg",
+];
+
 const MOUSELOOK_ROTATION_SPEED: f32 = 0.007;
-const CAMERA_VELOCITY: f32 = 0.02;
+// How fast the 3D camera moves, in world units per second.
+const CAMERA_VELOCITY: f32 = 1.2;
+
+// How much the scene is scaled when a scale gesture is performed, unless overridden with
+// `--scale-speed`.
+const DEFAULT_CAMERA_SCALE_SPEED_2D: f32 = 6.0;
+// How much the scene is scaled when a zoom button is clicked, unless overridden with
+// `--zoom-amount`.
+const DEFAULT_CAMERA_ZOOM_AMOUNT_2D: f32 = 0.1;
 
-// How much the scene is scaled when a scale gesture is performed.
-const CAMERA_SCALE_SPEED_2D: f32 = 6.0;
-// How much the scene is scaled when a zoom button is clicked.
-const CAMERA_ZOOM_AMOUNT_2D: f32 = 0.1;
+// How far, in binary orders of magnitude, the 2D scale may be from a power of two and still be
+// considered to be exactly that power of two.
+const POWER_OF_TWO_ZOOM_EPSILON: f32 = 0.001;
+// The most characters that may be typed for a zoom percentage.
+const MAX_ZOOM_INPUT_LENGTH: usize = 8;
+// The default bounds on the 2D camera's scale factor.
+const DEFAULT_MIN_SCALE_2D: f32 = 0.0001;
+const DEFAULT_MAX_SCALE_2D: f32 = 10000.0;
+// How much the scene is rotated, in radians, when a rotate key is pressed.
+const CAMERA_ROTATION_AMOUNT_2D: f32 = PI / 36.0;
+// How much the 3D camera rolls, in radians, when a roll key is pressed.
+const CAMERA_ROLL_AMOUNT_3D: f32 = PI / 36.0;
+// How far, in device pixels, an arrow key pans the 2D scene, and how far it does with Shift held.
+const PAN_NUDGE_STEP: f32 = 1.0;
+const PAN_NUDGE_STEP_LARGE: f32 = 10.0;
+// Rubber bands narrower or shorter than this, in device pixels, are taken to be clicks and don't
+// zoom.
+const MIN_RUBBER_BAND_SIZE: f32 = 4.0;
+// How long an arrow key has to be held before it starts repeating, and the time between repeats.
+// Held keys repeat at this rate regardless of the window system's key repeat settings.
+const PAN_NUDGE_REPEAT_DELAY: Duration = Duration::from_millis(400);
+const PAN_NUDGE_REPEAT_INTERVAL: Duration = Duration::from_millis(50);
+
+// How far the animation clock advances when a single frame is stepped while paused.
+const FRAME_STEP_DURATION: Duration = Duration::from_micros(16_667);
+// How fast the scene spins, in degrees per second, when auto-rotate is toggled on without an
+// explicit `--auto-rotate` speed.
+const DEFAULT_AUTO_ROTATE_SPEED: f32 = 30.0;
 
 // Half of the eye separation distance.
 const DEFAULT_EYE_OFFSET: f32 = 0.025;
 
 const APPROX_FONT_SIZE: f32 = 16.0;
 
-const MESSAGE_TIMEOUT_SECS: u64 = 5;
+const FALLBACK_SCENE_COLOR: ColorU = ColorU { r: 64, g: 128, b: 192, a: 255 };
+
+const DEFAULT_MESSAGE_TIMEOUT_SECS: u64 = 5;
+const DEFAULT_SLIDESHOW_INTERVAL_SECS: u64 = 5;
+
+// The number of recent frames that `current_fps()` averages over, and the number it needs before
+// it reports anything.
+const FPS_SAMPLE_COUNT: usize = 60;
+const MIN_FPS_SAMPLE_COUNT: usize = 10;
+
+static DEFAULT_CAMERA_PRESETS_PATH: &'static str = "camera-presets.toml";
+
+static WINDOW_TITLE: &'static str = "Pathfinder Demo";
 
 pub mod window;
 
 mod camera;
 mod concurrent;
+mod config;
 mod device;
+mod fonts;
+mod presets;
 mod renderer;
+mod stats;
 mod ui;
 
 enum Content {
@@ -89,7 +168,11 @@ enum Content {
         file: PdfFile<Vec<u8>>,
         cache: PdfRenderCache,
         page_nr: u32
-    }
+    },
+    // A placeholder, shown when nothing could be loaded.
+    Fallback,
+    // A scene that an embedder built itself and passed to `DemoApp::set_scene()`.
+    Scene(Scene),
 }
 
 pub struct DemoApp<W> where W: Window {
@@ -98,21 +181,82 @@ pub struct DemoApp<W> where W: Window {
     pub options: Options,
 
     window_size: WindowSize,
+    // The backing scale factor that the window system reports. Mouse positions are in its logical
+    // pixels, even when `--dpr` overrides the one that the scene and UI are drawn with.
+    window_backing_scale_factor: f32,
+    // The title that the window was last given, so that it's only set when it changes.
+    window_title: String,
 
     content: Content,
+    data_path: DataPath,
+    // Files dropped onto the window together, if there were several, or the files of a
+    // `--slideshow` directory.
+    playlist: Vec<DataPath>,
+    playlist_index: usize,
     scene_metadata: SceneMetadata,
+    // The rectangle that the scene was last clipped to, so that it's only updated when it moves.
+    applied_scene_clip_rect: RectF,
     render_transform: Option<RenderTransform>,
 
     camera: Camera,
     frame_counter: u32,
+    frame_timestamps: VecDeque<Instant>,
     pending_screenshot_info: Option<ScreenshotInfo>,
     mouselook_enabled: bool,
-    pub dirty: bool,
+    ruler_visible: bool,
+    // Whether the colors of a monochrome scene are shown in the corner of the window.
+    color_legend_visible: bool,
+    bounds_outline_visible: bool,
+    // The outlines of the scene's paths while the wireframe is showing.
+    wireframe_outlines: Option<Vec<Outline>>,
+    checkerboard_enabled: bool,
+    // Whether the 2D translation is rounded to whole device pixels before building.
+    snap_to_pixel: bool,
+    // While set, the scene is built with these options, whatever the camera does, so that the
+    // tiling computed for one view can be seen from others.
+    pinned_build_options: Option<BuildOptions>,
+    auto_rotate_enabled: bool,
+    last_auto_rotate_time: Option<Instant>,
+    last_camera_move_time: Option<Instant>,
+    animation_clock: AnimationClock,
+    // Whether something changed that affects the rendered scene, such as the camera, the effects,
+    // or the scene itself, so that another frame needs to be rendered.
+    pub scene_dirty: bool,
+    // Whether only the demo UI changed, such as a message or an overlay. The scene can be reused
+    // as is, but the frame still has to be presented again.
+    pub ui_dirty: bool,
+    window_focused: bool,
+    // The number of threads that scenes are built on.
+    thread_count: usize,
+    // The largest texture that the scene may be rendered to: the GPU's limit, or
+    // `--max-texture-size` if that's smaller.
+    max_texture_size: i32,
+    // The size that the scene was last scaled down to because its viewport didn't fit in a
+    // texture, so that the warning is only shown when it changes.
+    downscaled_scene_size: Option<Vector2I>,
     expire_message_event_id: u32,
+    slideshow_event_id: u32,
     message_epoch: u32,
+    message_timer: Box<dyn MessageTimer>,
+    injected_events: Vec<Event>,
     last_mouse_position: Vector2I,
+    shift_down: bool,
+    // The rectangle being dragged out with Shift held in 2D, which the camera zooms to fit.
+    rubber_band: Option<RubberBand>,
+    // The arrow keys that are held down, and when they next nudge the 2D pan.
+    held_arrow_keys: Vec<Keycode>,
+    next_pan_nudge_time: Option<Instant>,
+    camera_presets: CameraPresets,
 
     current_frame: Option<Frame>,
+    dpi_sweep: Option<DpiSweep>,
+    stats_recorder: Option<StatsRecorder>,
+    benchmark: Option<Benchmark>,
+    frame_stats_callback: Option<Box<dyn FnMut(&RenderStats, Duration)>>,
+    // The statistics of every rendering of the scene this frame, added together, or `None` if it
+    // wasn't rendered, as when it was reused from the last frame. Scaled screenshots render the
+    // scene once per tile on top of the frame's own rendering.
+    frame_render_stats: Option<RenderStats>,
 
     ui_model: DemoUIModel,
     ui_presenter: DemoUIPresenter<DeviceImpl>,
@@ -121,14 +265,36 @@ pub struct DemoApp<W> where W: Window {
     renderer: Renderer<DeviceImpl>,
 
     scene_framebuffer: Option<<DeviceImpl as Device>::Framebuffer>,
+    scene_cache_key: Option<SceneCacheKey>,
+    msaa_framebuffer: Option<<DeviceImpl as Device>::Framebuffer>,
+    offscreen_framebuffer: Option<<DeviceImpl as Device>::Framebuffer>,
+    // Each eye is drawn here first when the window asks for lens distortion correction.
+    distortion_framebuffer: Option<<DeviceImpl as Device>::Framebuffer>,
+    // The frame is drawn here first when a color vision deficiency is being simulated.
+    color_blindness_framebuffer: Option<<DeviceImpl as Device>::Framebuffer>,
+    // Bound by draws that only clear their framebuffer, which can't sample itself.
+    placeholder_texture: <DeviceImpl as Device>::Texture,
+    background_texture: Option<<DeviceImpl as Device>::Texture>,
+    watermark: Option<Watermark>,
 
     ground_program: GroundProgram<DeviceImpl>,
     ground_vertex_array: GroundVertexArray<DeviceImpl>,
+    distortion_program: DistortionProgram<DeviceImpl>,
+    distortion_vertex_array: DistortionVertexArray<DeviceImpl>,
+    color_matrix_program: ColorMatrixProgram<DeviceImpl>,
+    color_matrix_vertex_array: ColorMatrixVertexArray<DeviceImpl>,
 }
 
 impl<W> DemoApp<W> where W: Window {
-    pub fn new(window: W, window_size: WindowSize, options: Options) -> DemoApp<W> {
+    pub fn new(window: W, window_size: WindowSize, mut options: Options) -> DemoApp<W> {
+        let window_backing_scale_factor = window_size.backing_scale_factor;
+        let window_size = match options.dpr {
+            None => window_size,
+            Some(dpr) => emulate_backing_scale_factor(window_size, dpr),
+        };
+
         let expire_message_event_id = window.create_user_event_id();
+        let slideshow_event_id = window.create_user_event_id();
 
         let device;
         #[cfg(all(target_os = "macos", not(feature = "pf-gl")))]
@@ -142,8 +308,24 @@ impl<W> DemoApp<W> where W: Window {
 
         let resources = window.resource_loader();
 
+        if let Some(ref output_frames_dir) = options.output_frames {
+            if let Err(error) = std::fs::create_dir_all(output_frames_dir) {
+                eprintln!("error: couldn't create the --output-frames directory {}: {}",
+                          output_frames_dir.display(),
+                          error);
+                process::exit(1);
+            }
+        }
+
+        // Benchmarks time the scene alone, so hide the UI.
+        let benchmark = options.benchmark_frames.map(Benchmark::new);
+        if benchmark.is_some() {
+            options.ui = UIVisibility::None;
+        }
+
         // Set up the executor.
         let executor = DemoExecutor::new(options.jobs);
+        let thread_count = executor.thread_count();
 
         let mut ui_model = DemoUIModel::new(&options);
 
@@ -151,6 +333,11 @@ impl<W> DemoApp<W> where W: Window {
             Some(level) => level,
             None => RendererLevel::default_for_device(&device),
         };
+        if level == RendererLevel::D3D11 &&
+                options.antialiasing_quality != AntialiasingQuality::High {
+            eprintln!("warning: `--aa-quality` has no effect at the D3D11 level, which flattens \
+                       curves on the GPU; pass `--level 9` to use it");
+        }
         let viewport = window.viewport(options.mode.view(0));
         let dest_framebuffer = DestFramebuffer::Default {
             viewport,
@@ -161,20 +348,130 @@ impl<W> DemoApp<W> where W: Window {
             dest: dest_framebuffer,
             background_color: None,
             show_debug_ui: true,
+            coverage_gamma: options.antialias_gamma,
         };
 
-        let filter = build_filter(&ui_model);
+        let filters = build_filters(&ui_model);
 
-        let viewport = window.viewport(options.mode.view(0));
-        let mut content = load_scene(resources, &options.input_path);
+        // A slideshow starts from the first file in its directory.
+        let mut playlist = vec![];
+        let mut slideshow_error = None;
+        if let Some(ref slideshow_dir) = options.slideshow {
+            match list_svg_files(slideshow_dir) {
+                Ok(ref paths) if paths.is_empty() => {
+                    slideshow_error = Some(format!("No SVG files in \"{}\".",
+                                                   slideshow_dir.display()));
+                }
+                Ok(paths) => {
+                    options.input_path = paths[0].clone();
+                    playlist = paths;
+                }
+                Err(error) => {
+                    slideshow_error = Some(format!("Couldn't read \"{}\": {}.",
+                                                   slideshow_dir.display(),
+                                                   error));
+                }
+            }
+        }
+        if playlist.len() > 1 {
+            start_slideshow_timer::<W>(slideshow_event_id, options.slideshow_interval);
+        }
+
+        let viewport_size = scene_viewport_size(&window, options.mode);
+        let (mut content, load_error) = match load_scene(resources,
+                                                         &options.input_path,
+                                                         &options) {
+            Ok(content) => (content, None),
+            Err(load_error) => (Content::Fallback, Some(load_error)),
+        };
+
+        let (mut scene, result_flags) = content.render(viewport_size, filters);
+
+        if let Some(ref warnings_json_path) = options.warnings_json {
+            if let Err(error) = write_warnings_json(warnings_json_path, result_flags) {
+                eprintln!("error: couldn't write \"{}\": {}", warnings_json_path.display(), error);
+                process::exit(1);
+            }
+        }
+        if options.strict {
+            // The fallback scene shown in place of a file that failed to load has no warnings.
+            if let Some(ref load_error) = load_error {
+                eprintln!("error: {}", load_error);
+                process::exit(1);
+            }
+            if !result_flags.is_empty() {
+                eprintln!("error: unsupported SVG features: {}", result_flags);
+                process::exit(1);
+            }
+        }
 
-        let (mut scene, message) = content.render(viewport.size(), filter);
+        let mut error = slideshow_error.or(load_error);
 
         let renderer = Renderer::new(device, resources, render_mode, render_options);
+        if options.srgb && !renderer.device().supports_skipping_srgb_decode() {
+            eprintln!("warning: the {} backend can't skip sRGB decoding when compositing, so \
+                       `--srgb` renders too dark",
+                      renderer.device().backend_name());
+        }
+        let max_texture_size = renderer.device().max_texture_size();
+        let max_texture_size = options.max_texture_size.map_or(max_texture_size, |size| {
+            i32::min(size, max_texture_size)
+        });
+
+        // An image background takes precedence over a gradient one.
+        let mut background_texture = None;
+        if let Some(ref background_image_path) = options.background_image {
+            match create_background_image_texture(renderer.device(),
+                                                  background_image_path,
+                                                  max_texture_size) {
+                Ok(texture) => background_texture = Some(texture),
+                Err(background_error) => error = Some(background_error),
+            }
+        }
+        if background_texture.is_none() {
+            background_texture = options.background_gradient.map(|(top, bottom)| {
+                create_background_gradient_texture(renderer.device(), top, bottom)
+            });
+        }
+
+        let mut watermark = None;
+        if let Some(ref watermark_path) = options.watermark {
+            match load_watermark(resources, watermark_path, viewport_size, &options, level) {
+                Ok(loaded_watermark) => watermark = Some(loaded_watermark),
+                Err(watermark_error) => error = Some(watermark_error),
+            }
+        }
+
+        let scene_metadata =
+            SceneMetadata::new_clipping_view_box(&mut scene, viewport_size, &options);
+        let mut camera = Camera::new(options.mode,
+                                     scene_metadata.view_box,
+                                     viewport_size,
+                                     &options);
+
+        if let Some(ref transform_string) = options.initial_transform {
+            if let Camera::TwoD(ref mut transform) = camera {
+                match parse_transform_2d(transform_string) {
+                    Ok(initial_transform) => *transform = initial_transform,
+                    Err(transform_error) => error = Some(transform_error),
+                }
+            }
+        }
+        if let Some(ref transform_path) = options.transform_file {
+            if let Err(transform_error) = apply_transform_file(&mut camera, transform_path) {
+                error = Some(transform_error);
+            }
+        }
 
-        let scene_metadata = SceneMetadata::new_clipping_view_box(&mut scene,
-                                                                  viewport.size());
-        let camera = Camera::new(options.mode, scene_metadata.view_box, viewport.size());
+        // In quiet mode, errors still go to stderr, but unsupported feature warnings are dropped.
+        let message = if options.quiet {
+            if let Some(ref error) = error {
+                eprintln!("error: {}", error);
+            }
+            String::new()
+        } else {
+            error.unwrap_or_else(|| get_svg_building_message(result_flags))
+        };
 
         let scene_proxy = SceneProxy::from_scene(scene, level, executor);
 
@@ -183,38 +480,107 @@ impl<W> DemoApp<W> where W: Window {
                                                          &ground_program,
                                                          &renderer.quad_vertex_positions_buffer(),
                                                          &renderer.quad_vertex_indices_buffer());
+        let distortion_program = DistortionProgram::new(renderer.device(), resources);
+        let distortion_vertex_array =
+            DistortionVertexArray::new(renderer.device(),
+                                       &distortion_program,
+                                       &renderer.quad_vertex_positions_buffer(),
+                                       &renderer.quad_vertex_indices_buffer());
+        let color_matrix_program = ColorMatrixProgram::new(renderer.device(), resources);
+        let color_matrix_vertex_array =
+            ColorMatrixVertexArray::new(renderer.device(),
+                                        &color_matrix_program,
+                                        &renderer.quad_vertex_positions_buffer(),
+                                        &renderer.quad_vertex_indices_buffer());
+        let placeholder_texture =
+            renderer.device().create_texture(TextureFormat::RGBA8, Vector2I::splat(1));
 
         let mut message_epoch = 0;
-        emit_message::<W>(
+        let message_timer = window.create_message_timer(expire_message_event_id,
+                                                        options.message_timeout);
+        emit_message(
             &mut ui_model,
             &mut message_epoch,
-            expire_message_event_id,
+            &*message_timer,
             message,
         );
 
         let ui_presenter = DemoUIPresenter::new(renderer.device(), resources);
+        let animation_clock = AnimationClock::new(animation_frame_duration(&options));
+
+        let dpi_sweep = if options.dpi_sweep.is_empty() {
+            None
+        } else {
+            Some(DpiSweep { index: 0, original_window_size: window_size })
+        };
+
+        let data_path = options.input_path.clone();
+
+        let stats_recorder = match options.stats_json {
+            None => None,
+            Some(_) => Some(StatsRecorder::new()),
+        };
 
-        DemoApp {
+        let camera_presets = CameraPresets::load(&options.camera_presets);
+        let auto_rotate_enabled = options.auto_rotate.is_some() || options.capture_frame.is_some();
+
+        let mut demo_app = DemoApp {
             window,
             should_exit: false,
             options,
 
             window_size,
+            window_backing_scale_factor,
+            window_title: WINDOW_TITLE.to_owned(),
 
             content,
+            data_path,
+            playlist,
+            playlist_index: 0,
             scene_metadata,
+            applied_scene_clip_rect: RectF::default(),
             render_transform: None,
 
             camera,
             frame_counter: 0,
+            frame_timestamps: VecDeque::with_capacity(FPS_SAMPLE_COUNT),
             pending_screenshot_info: None,
             mouselook_enabled: false,
-            dirty: true,
+            ruler_visible: false,
+            color_legend_visible: false,
+            bounds_outline_visible: false,
+            wireframe_outlines: None,
+            checkerboard_enabled: false,
+            snap_to_pixel: options.snap_to_pixel,
+            pinned_build_options: None,
+            auto_rotate_enabled,
+            last_auto_rotate_time: None,
+            last_camera_move_time: None,
+            animation_clock,
+            scene_dirty: true,
+            ui_dirty: true,
+            window_focused: true,
+            thread_count,
+            max_texture_size,
+            downscaled_scene_size: None,
             expire_message_event_id,
+            slideshow_event_id,
             message_epoch,
+            message_timer,
+            injected_events: vec![],
             last_mouse_position: Vector2I::default(),
+            shift_down: false,
+            rubber_band: None,
+            held_arrow_keys: vec![],
+            next_pan_nudge_time: None,
+            camera_presets,
 
             current_frame: None,
+            dpi_sweep,
+            stats_recorder,
+            benchmark,
+            frame_stats_callback: None,
+            frame_render_stats: None,
 
             ui_presenter,
             ui_model,
@@ -223,19 +589,121 @@ impl<W> DemoApp<W> where W: Window {
             renderer,
 
             scene_framebuffer: None,
+            scene_cache_key: None,
+            msaa_framebuffer: None,
+            offscreen_framebuffer: None,
+            distortion_framebuffer: None,
+            color_blindness_framebuffer: None,
+            placeholder_texture,
+            background_texture,
+            watermark,
 
             ground_program,
             ground_vertex_array,
+            distortion_program,
+            distortion_vertex_array,
+            color_matrix_program,
+            color_matrix_vertex_array,
+        };
+
+        demo_app.update_scene_clip_rect();
+
+        if let Some(&backing_scale_factor) = demo_app.options.dpi_sweep.first() {
+            demo_app.set_dpi_sweep_scale_factor(backing_scale_factor);
         }
+
+        demo_app
+    }
+
+    // Replaces the scene with one that the embedder built, without going through SVG or PDF. As
+    // when a file is opened, the camera is reset to frame the new scene. The text and color
+    // effects are applied while converting SVG, so they don't affect a scene set this way.
+    pub fn set_scene(&mut self, scene: Scene) {
+        self.content = Content::Scene(scene);
+
+        let viewport_size = scene_viewport_size(&self.window, self.ui_model.mode);
+        let (mut scene, _) = self.content.render(viewport_size, vec![]);
+        self.ui_model.message = String::new();
+
+        self.scene_metadata =
+            SceneMetadata::new_clipping_view_box(&mut scene, viewport_size, &self.options);
+        self.camera = Camera::new(self.ui_model.mode,
+                                  self.scene_metadata.view_box,
+                                  viewport_size,
+                                  &self.options);
+
+        self.update_wireframe(&scene);
+        self.scene_proxy.replace_scene(scene);
+        self.update_scene_clip_rect();
+
+        self.scene_dirty = true;
+    }
+
+    // Registers a function to be called at the end of every frame in which the scene was
+    // rendered, with that frame's statistics and the CPU time spent building the scene, summed
+    // over every time it was rendered. This lets an embedder collect metrics without patching the
+    // demo.
+    pub fn with_frame_stats_callback<F>(mut self, callback: F) -> DemoApp<W>
+                                        where F: FnMut(&RenderStats, Duration) + 'static {
+        self.frame_stats_callback = Some(Box::new(callback));
+        self
+    }
+
+    // Returns true if anything changed since the last frame, so that the next frame shouldn't wait
+    // for an event before being drawn.
+    pub fn dirty(&self) -> bool {
+        self.scene_dirty || self.ui_dirty
+    }
+
+    // Returns the number of frames presented per second, averaged over the last few frames, or
+    // `None` if too few frames have been presented yet. The demo only draws when something
+    // changes, so this is the rate at which frames are actually presented, which can be far below
+    // what the renderer could sustain while the scene is idle.
+    pub fn current_fps(&self) -> Option<f32> {
+        if self.frame_timestamps.len() < MIN_FPS_SAMPLE_COUNT {
+            return None;
+        }
+        let first = *self.frame_timestamps.front().unwrap();
+        let last = *self.frame_timestamps.back().unwrap();
+        let elapsed = (last - first).as_secs_f32();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        Some((self.frame_timestamps.len() - 1) as f32 / elapsed)
+    }
+
+    // Queues synthetic events to be handled in the next frame, after those from the window. This
+    // lets an external driver, such as an integration test, script the app deterministically.
+    pub fn inject_events(&mut self, events: Vec<Event>) {
+        self.injected_events.extend(events);
+        // Make sure the next frame doesn't block waiting on the window.
+        self.ui_dirty = true;
     }
 
-    pub fn prepare_frame(&mut self, events: Vec<Event>) -> u32 {
-        // Clear dirty flag.
-        self.dirty = false;
+    pub fn prepare_frame(&mut self, mut events: Vec<Event>) -> u32 {
+        if let Some(ref mut benchmark) = self.benchmark {
+            benchmark.begin_frame();
+        }
+
+        self.wait_for_max_fps();
+        self.animation_clock.next_frame();
+
+        // Clear dirty flags.
+        self.scene_dirty = false;
+        self.ui_dirty = false;
 
         // Handle events.
+        events.extend(self.injected_events.drain(..));
         let ui_events = self.handle_events(events);
 
+        // Keep handling events in the background, but don't build or draw anything until the
+        // window is focused again.
+        if self.rendering_paused() {
+            self.scene_dirty = false;
+            self.ui_dirty = false;
+            return 0;
+        }
+
         // Update the scene.
         self.build_scene();
 
@@ -249,36 +717,70 @@ impl<W> DemoApp<W> where W: Window {
         self.prepare_frame_rendering()
     }
 
+    // Sleeps until enough time has passed since the last frame was presented to keep under
+    // `--max-fps`.
+    fn wait_for_max_fps(&self) {
+        let max_fps = match self.options.max_fps {
+            None => return,
+            Some(max_fps) => max_fps,
+        };
+        let last_frame_time = match self.frame_timestamps.back() {
+            None => return,
+            Some(&last_frame_time) => last_frame_time,
+        };
+        let next_frame_time = last_frame_time + Duration::from_secs_f32(1.0 / max_fps);
+        let now = Instant::now();
+        if next_frame_time > now {
+            thread::sleep(next_frame_time - now);
+        }
+    }
+
+    // Returns true if the window is in the background and shouldn't be drawn. The caller skips the
+    // rest of the frame after `prepare_frame()` in that case.
+    //
+    // Runs that count frames or write their results out when they finish are never paused, and
+    // neither is the last frame before exiting, since `finish_drawing_frame()` does that work.
+    pub fn rendering_paused(&self) -> bool {
+        if self.window_focused || self.options.render_when_unfocused || self.should_exit {
+            return false;
+        }
+        self.benchmark.is_none() && self.stats_recorder.is_none() &&
+            self.options.frame_limit.is_none() && self.options.output_frames.is_none()
+    }
+
     fn build_scene(&mut self) {
-        self.render_transform = match self.camera {
-            Camera::ThreeD {
-                ref scene_transform,
-                ref mut modelview_transform,
-                ref mut velocity,
-                ..
-            } => {
-                if modelview_transform.offset(*velocity) {
-                    self.dirty = true;
-                }
-                let perspective = scene_transform.perspective *
-                    scene_transform.modelview_to_eye *
-                    modelview_transform.to_transform();
-                Some(RenderTransform::Perspective(perspective))
-            }
-            Camera::TwoD(transform) => Some(RenderTransform::Transform2D(transform)),
-        };
-
-        let build_options = BuildOptions {
-            transform: self.render_transform.clone().unwrap(),
-            dilation: if self.ui_model.stem_darkening_effect_enabled {
-                let font_size = APPROX_FONT_SIZE * self.window_size.backing_scale_factor;
-                vec2f(STEM_DARKENING_FACTORS[0], STEM_DARKENING_FACTORS[1]) * font_size
-            } else {
-                Vector2F::zero()
-            },
-            subpixel_aa_enabled: self.ui_model.subpixel_aa_effect_enabled,
+        self.advance_animation();
+        if self.camera.update_transition() {
+            self.scene_dirty = true;
+        }
+        self.update_pan_nudge();
+        self.check_scene_downscale();
+
+        // The letterbox follows the scene as the 2D camera pans and zooms.
+        if self.downscaled_scene_clip_rect() != self.applied_scene_clip_rect {
+            self.update_scene_clip_rect();
+        }
+
+        let render_transform = self.camera.render_transform();
+        let render_transform = match self.scene_downscale() {
+            None => render_transform,
+            Some(scale) => scale_render_transform(render_transform, scale),
         };
+        self.render_transform = Some(match render_transform {
+            // Snapping is done last, so that it's to the pixels actually rendered to.
+            RenderTransform::Transform2D(transform) if self.snap_to_pixel => {
+                RenderTransform::Transform2D(Transform2F {
+                    matrix: transform.matrix,
+                    vector: transform.vector.round(),
+                })
+            }
+            render_transform => render_transform,
+        });
 
+        let build_options = match self.pinned_build_options {
+            Some(ref pinned_build_options) => pinned_build_options.clone(),
+            None => self.build_options(self.render_transform.clone().unwrap()),
+        };
         self.scene_proxy.build(build_options);
         /*
         self.render_command_stream =    
@@ -286,52 +788,357 @@ impl<W> DemoApp<W> where W: Window {
             */
     }
 
+    // Returns the options to build the scene with, given the transform to render it with.
+    fn build_options(&self, transform: RenderTransform) -> BuildOptions {
+        scene_build_options(transform,
+                            &self.ui_model,
+                            &self.options,
+                            self.window_size.backing_scale_factor)
+    }
+
+    // Moves and spins the camera up to the current time on the animation clock. While the clock
+    // is paused, it only moves when a frame is stepped, so the animations mustn't ask for more
+    // frames.
+    fn advance_animation(&mut self) {
+        let scene_dirty = self.scene_dirty;
+        self.update_camera_position();
+        self.update_auto_rotate();
+        if self.animation_clock.is_paused() {
+            self.scene_dirty = scene_dirty;
+        }
+    }
+
+    // Shows or hides the foreground and background color swatches. They're only drawn for
+    // monochrome scenes, so say why nothing appeared otherwise.
+    fn toggle_color_legend(&mut self) {
+        self.color_legend_visible = !self.color_legend_visible;
+        if self.color_legend_visible && self.scene_metadata.legend_color.is_none() {
+            emit_message(&mut self.ui_model,
+                         &mut self.message_epoch,
+                         &self.message_timer,
+                         "The color legend is only shown for monochrome scenes.".to_owned());
+        }
+        self.ui_dirty = true;
+    }
+
+    // Pins the options that the scene was last built with, or unpins them. The camera keeps
+    // moving while they're pinned, but the scene is built as if it hadn't, so the picture stays
+    // where it was while the rulers, outlines, and tile grid follow the camera.
+    fn toggle_build_options_pinned(&mut self) {
+        let message = if self.pinned_build_options.take().is_some() {
+            "Unpinned the build transform.".to_owned()
+        } else {
+            let render_transform = match self.render_transform {
+                Some(ref render_transform) => render_transform.clone(),
+                None => return,
+            };
+            self.pinned_build_options = Some(self.build_options(render_transform));
+            "Pinned the build transform. Press K to unpin it.".to_owned()
+        };
+        self.ui_model.build_transform_pinned = self.pinned_build_options.is_some();
+        emit_message(&mut self.ui_model, &mut self.message_epoch, &self.message_timer, message);
+
+        // The cached rendering is keyed on the camera, which pinning doesn't change, so a scene
+        // built with the pinned options could otherwise be shown after unpinning.
+        self.scene_cache_key = None;
+        self.scene_dirty = true;
+    }
+
+    // Pauses or resumes the animation clock.
+    fn toggle_animation_paused(&mut self) {
+        let message = if self.animation_clock.is_paused() {
+            self.animation_clock.resume();
+            "Resumed.".to_owned()
+        } else {
+            self.animation_clock.pause();
+            format!("Paused at frame {}. Press F to step one frame or Space to resume.",
+                    self.frame_counter)
+        };
+        emit_message(&mut self.ui_model, &mut self.message_epoch, &self.message_timer, message);
+        self.ui_dirty = true;
+    }
+
+    // Advances the paused animations by exactly one frame. The frame that handles the key press
+    // is the one that draws it, so no more frames are requested.
+    fn step_animation_frame(&mut self) {
+        if !self.animation_clock.is_paused() {
+            return;
+        }
+        self.animation_clock.step(FRAME_STEP_DURATION);
+        let message = format!("Frame {}", self.frame_counter);
+        emit_message(&mut self.ui_model, &mut self.message_epoch, &self.message_timer, message);
+    }
+
+    // Shows or hides the flattened outlines of the scene's paths. The scene proxy doesn't hand
+    // the scene back, so turning the wireframe on converts the content again to get at them.
+    fn toggle_wireframe(&mut self) {
+        if self.wireframe_outlines.take().is_none() {
+            let viewport_size = scene_viewport_size(&self.window, self.ui_model.mode);
+            let filters = build_filters(&self.ui_model);
+            let (scene, _) = self.content.render(viewport_size, filters);
+            self.wireframe_outlines = Some(wireframe_outlines(&scene));
+        }
+        self.ui_dirty = true;
+    }
+
+    // Keeps the wireframe, if it's showing, in step with a newly built scene.
+    fn update_wireframe(&mut self, scene: &Scene) {
+        if self.wireframe_outlines.is_some() {
+            self.wireframe_outlines = Some(wireframe_outlines(scene));
+        }
+    }
+
+    fn cycle_color_blindness(&mut self) {
+        self.ui_model.color_blindness = self.ui_model.color_blindness.next();
+        let message = match self.ui_model.color_blindness {
+            ColorBlindness::None => "Color blindness simulation off".to_owned(),
+            color_blindness => format!("Simulating {}", color_blindness.name()),
+        };
+        emit_message(&mut self.ui_model, &mut self.message_epoch, &self.message_timer, message);
+
+        // The simulation is applied to each finished frame, so the scene needn't be rebuilt.
+        self.ui_dirty = true;
+    }
+
+    // Moves the 3D camera according to its velocity and the time elapsed since the last frame, so
+    // that the speed of movement doesn't depend on the frame rate.
+    fn update_camera_position(&mut self) {
+        let now = self.animation_clock.now();
+        let elapsed = match self.last_camera_move_time {
+            None => 0.0,
+            Some(last_camera_move_time) => (now - last_camera_move_time).as_secs_f32(),
+        };
+
+        match self.camera {
+            Camera::ThreeD { ref mut modelview_transform, velocity, .. } if !velocity.is_zero() => {
+                modelview_transform.offset(velocity.scale(elapsed));
+                self.last_camera_move_time = Some(now);
+                self.scene_dirty = true;
+            }
+            _ => self.last_camera_move_time = None,
+        }
+    }
+
+    // Nudges the 2D pan by one step when an arrow key is pressed, and starts repeating the step
+    // if it's held.
+    fn press_arrow_key(&mut self, keycode: Keycode) {
+        // The window system's own key repeats arrive as more presses; `update_pan_nudge()` does
+        // the repeating instead, so that it's steady.
+        if self.held_arrow_keys.contains(&keycode) {
+            return;
+        }
+        self.held_arrow_keys.push(keycode);
+        self.nudge_pan_2d(arrow_key_direction(keycode).unwrap());
+        self.next_pan_nudge_time = Some(Instant::now() + PAN_NUDGE_REPEAT_DELAY);
+    }
+
+    // Repeats the pan nudge for each repeat interval that has passed while arrow keys are held.
+    fn update_pan_nudge(&mut self) {
+        let mut next_pan_nudge_time = match self.next_pan_nudge_time {
+            None => return,
+            Some(next_pan_nudge_time) => next_pan_nudge_time,
+        };
+
+        let direction = self.held_arrow_keys
+                            .iter()
+                            .filter_map(|&keycode| arrow_key_direction(keycode))
+                            .fold(Vector2F::zero(), |sum, direction| sum + direction);
+        let now = Instant::now();
+        while next_pan_nudge_time <= now {
+            self.nudge_pan_2d(direction);
+            next_pan_nudge_time += PAN_NUDGE_REPEAT_INTERVAL;
+        }
+        self.next_pan_nudge_time = Some(next_pan_nudge_time);
+
+        // Keep drawing frames while the keys are held, so that the repeats aren't held up waiting
+        // for other events.
+        self.scene_dirty = true;
+    }
+
+    // Pans the 2D scene by a fixed number of device pixels in the given direction, so that
+    // positions can be reproduced exactly, as for screenshots.
+    fn nudge_pan_2d(&mut self, direction: Vector2F) {
+        let step = if self.shift_down { PAN_NUDGE_STEP_LARGE } else { PAN_NUDGE_STEP };
+        if let Camera::TwoD(ref mut transform) = self.camera {
+            *transform = transform.translate(direction * step);
+            self.scene_dirty = true;
+        }
+    }
+
+    // Returns the factor by which the scene has to be scaled down for its viewport to fit in a
+    // texture, or `None` if it fits as is. A scaled-down scene is rendered offscreen and stretched
+    // over the window.
+    pub(crate) fn scene_downscale(&self) -> Option<f32> {
+        let viewport_size = scene_viewport_size(&self.window, self.camera.mode());
+        let largest_side = i32::max(viewport_size.x(), viewport_size.y());
+        if largest_side <= self.max_texture_size {
+            None
+        } else {
+            Some(self.max_texture_size as f32 / largest_side as f32)
+        }
+    }
+
+    // Returns the size of the texture that the scene is rendered to for a viewport of the given
+    // size.
+    pub(crate) fn scene_texture_size(&self, viewport_size: Vector2I) -> Vector2I {
+        match self.scene_downscale() {
+            None => viewport_size,
+            Some(scale) => {
+                (viewport_size.to_f32() * scale).to_i32()
+                                                .max(Vector2I::splat(1))
+                                                .min(Vector2I::splat(self.max_texture_size))
+            }
+        }
+    }
+
+    // Warns when the scene starts being scaled down to fit in a texture, or is scaled down to a
+    // different size than before.
+    fn check_scene_downscale(&mut self) {
+        let viewport_size = scene_viewport_size(&self.window, self.camera.mode());
+        let downscaled_size = match self.scene_downscale() {
+            None => None,
+            Some(_) => Some(self.scene_texture_size(viewport_size)),
+        };
+        if downscaled_size == self.downscaled_scene_size {
+            return;
+        }
+        self.downscaled_scene_size = downscaled_size;
+        self.update_scene_clip_rect();
+
+        if let Some(size) = downscaled_size {
+            let message = format!("Rendering at {}x{} and upscaling: the {}x{} viewport exceeds \
+                                   the {}-pixel texture size limit.",
+                                  size.x(),
+                                  size.y(),
+                                  viewport_size.x(),
+                                  viewport_size.y(),
+                                  self.max_texture_size);
+            warn!("{}", message);
+            emit_message(&mut self.ui_model,
+                         &mut self.message_epoch,
+                         &self.message_timer,
+                         message);
+        }
+    }
+
+    // Spins the 3D camera about the vertical axis at a rate independent of the frame rate.
+    fn update_auto_rotate(&mut self) {
+        let modelview_transform = match self.camera {
+            Camera::ThreeD { ref mut modelview_transform, .. } if self.auto_rotate_enabled => {
+                modelview_transform
+            }
+            _ => {
+                self.last_auto_rotate_time = None;
+                return;
+            }
+        };
+
+        let now = self.animation_clock.now();
+        let speed = self.options.auto_rotate.unwrap_or(DEFAULT_AUTO_ROTATE_SPEED);
+        match (self.options.capture_frame, self.last_auto_rotate_time) {
+            // A single captured frame is turned to its angle in one go, a fixed step per frame,
+            // so that it comes out the same no matter how long frames take to draw.
+            (Some(frame_index), None) => {
+                let elapsed = FRAME_STEP_DURATION.as_secs_f32() * frame_index as f32;
+                modelview_transform.yaw += speed.to_radians() * elapsed;
+            }
+            (Some(_), Some(_)) => return,
+            (None, Some(last_auto_rotate_time)) => {
+                let elapsed = (now - last_auto_rotate_time).as_secs_f32();
+                modelview_transform.yaw += speed.to_radians() * elapsed;
+            }
+            (None, None) => {}
+        }
+        self.last_auto_rotate_time = Some(now);
+        self.scene_dirty = true;
+    }
+
     fn handle_events(&mut self, events: Vec<Event>) -> Vec<UIEvent> {
         let mut ui_events = vec![];
-        self.dirty = false;
+        let mut dropped_files = vec![];
+        self.scene_dirty = false;
+        self.ui_dirty = false;
 
         for event in events {
+            if self.options.kiosk && is_camera_event(&event) {
+                continue;
+            }
+
+            // While a zoom percentage is being typed, it takes all keystrokes.
+            if self.ui_model.zoom_input.is_some() {
+                if let Event::KeyDown(keycode) = event {
+                    self.handle_zoom_input_key(keycode);
+                    continue;
+                }
+            }
+
             match event {
                 Event::Quit { .. } | Event::KeyDown(Keycode::Escape) => {
                     self.should_exit = true;
-                    self.dirty = true;
+                    self.ui_dirty = true;
                 }
                 Event::WindowResized(new_size) => {
-                    self.window_size = new_size;
-                    let viewport = self.window.viewport(self.ui_model.mode.view(0));
-                    self.scene_proxy.set_view_box(RectF::new(Vector2F::zero(),
-                                                             viewport.size().to_f32()));
+                    self.window_backing_scale_factor = new_size.backing_scale_factor;
+                    self.window_size = match self.options.dpr {
+                        None => new_size,
+                        Some(dpr) => emulate_backing_scale_factor(new_size, dpr),
+                    };
+                    self.update_scene_clip_rect();
                     self.renderer.options_mut().dest =
                         DestFramebuffer::full_window(self.window_size.device_size());
                     self.renderer.dest_framebuffer_size_changed();
-                    self.dirty = true;
+                    self.scene_dirty = true;
+                }
+                Event::FocusGained => {
+                    self.window_focused = true;
+                    self.scene_dirty = true;
+                }
+                Event::FocusLost => {
+                    self.window_focused = false;
+                    // The key releases go to whichever window has focus now, so stop panning.
+                    self.held_arrow_keys.clear();
+                    self.next_pan_nudge_time = None;
+                }
+                Event::MouseDown(new_position) if self.shift_down && !self.options.kiosk &&
+                        self.camera.mode() == Mode::TwoD => {
+                    let position = self.process_mouse_position(new_position).absolute.to_f32();
+                    self.rubber_band = Some(RubberBand { start: position, current: position });
+                    self.ui_dirty = true;
                 }
                 Event::MouseDown(new_position) => {
                     let mouse_position = self.process_mouse_position(new_position);
                     ui_events.push(UIEvent::MouseDown(mouse_position));
                 }
+                Event::MouseDragged(new_position) if self.rubber_band.is_some() => {
+                    let position = self.process_mouse_position(new_position).absolute.to_f32();
+                    self.rubber_band.as_mut().unwrap().current = position;
+                    self.ui_dirty = true;
+                }
+                Event::MouseUp(_) => self.finish_rubber_band(),
                 Event::MouseMoved(new_position) if self.mouselook_enabled => {
                     let mouse_position = self.process_mouse_position(new_position);
                     if let Camera::ThreeD { ref mut modelview_transform, .. } = self.camera {
-                        let rotation = mouse_position.relative.to_f32() * MOUSELOOK_ROTATION_SPEED;
-                        modelview_transform.yaw += rotation.x();
-                        modelview_transform.pitch += rotation.y();
-                        self.dirty = true;
+                        // A negative speed inverts the Y axis.
+                        let speed = self.options.mouselook_speed;
+                        let relative = mouse_position.relative.to_f32();
+                        modelview_transform.yaw += relative.x() * speed.abs();
+                        modelview_transform.pitch += relative.y() * speed;
+                        self.scene_dirty = true;
                     }
                 }
                 Event::MouseDragged(new_position) => {
                     let mouse_position = self.process_mouse_position(new_position);
                     ui_events.push(UIEvent::MouseDragged(mouse_position));
-                    self.dirty = true;
+                    self.ui_dirty = true;
                 }
                 Event::Zoom(d_dist, position) => {
                     if let Camera::TwoD(ref mut transform) = self.camera {
-                        let backing_scale_factor = self.window_size.backing_scale_factor;
-                        let position = position.to_f32() * backing_scale_factor;
-                        let scale_delta = 1.0 + d_dist * CAMERA_SCALE_SPEED_2D;
+                        let position = position.to_f32() * self.window_backing_scale_factor;
+                        let scale_delta = 1.0 + d_dist * self.options.scale_speed;
                         *transform = transform.translate(-position)
                                               .scale(scale_delta)
                                               .translate(position);
+                        *transform = clamp_scale_2d(*transform, position, &self.options);
                     }
                 }
                 Event::Look { pitch, yaw } => {
@@ -377,7 +1184,7 @@ impl<W> DemoApp<W> where W: Window {
                         let scale_factor =
                             camera::scale_factor_for_view_box(self.scene_metadata.view_box);
                         velocity.set_z(-CAMERA_VELOCITY / scale_factor);
-                        self.dirty = true;
+                        self.scene_dirty = true;
                     }
                 }
                 Event::KeyDown(Keycode::Alphanumeric(b's')) => {
@@ -388,7 +1195,7 @@ impl<W> DemoApp<W> where W: Window {
                         let scale_factor =
                             camera::scale_factor_for_view_box(self.scene_metadata.view_box);
                         velocity.set_z(CAMERA_VELOCITY / scale_factor);
-                        self.dirty = true;
+                        self.scene_dirty = true;
                     }
                 }
                 Event::KeyDown(Keycode::Alphanumeric(b'a')) => {
@@ -399,7 +1206,7 @@ impl<W> DemoApp<W> where W: Window {
                         let scale_factor =
                             camera::scale_factor_for_view_box(self.scene_metadata.view_box);
                         velocity.set_x(-CAMERA_VELOCITY / scale_factor);
-                        self.dirty = true;
+                        self.scene_dirty = true;
                     }
                 }
                 Event::KeyDown(Keycode::Alphanumeric(b'd')) => {
@@ -410,7 +1217,7 @@ impl<W> DemoApp<W> where W: Window {
                         let scale_factor =
                             camera::scale_factor_for_view_box(self.scene_metadata.view_box);
                         velocity.set_x(CAMERA_VELOCITY / scale_factor);
-                        self.dirty = true;
+                        self.scene_dirty = true;
                     }
                 }
                 Event::KeyUp(Keycode::Alphanumeric(b'w'))
@@ -420,7 +1227,7 @@ impl<W> DemoApp<W> where W: Window {
                     } = self.camera
                     {
                         velocity.set_z(0.0);
-                        self.dirty = true;
+                        self.scene_dirty = true;
                     }
                 }
                 Event::KeyUp(Keycode::Alphanumeric(b'a'))
@@ -430,9 +1237,106 @@ impl<W> DemoApp<W> where W: Window {
                     } = self.camera
                     {
                         velocity.set_x(0.0);
-                        self.dirty = true;
+                        self.scene_dirty = true;
+                    }
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'q')) => {
+                    self.rotate_2d(-CAMERA_ROTATION_AMOUNT_2D);
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'e')) => {
+                    self.rotate_2d(CAMERA_ROTATION_AMOUNT_2D);
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'z')) => self.roll_3d(-CAMERA_ROLL_AMOUNT_3D),
+                Event::KeyDown(Keycode::Alphanumeric(b'c')) => self.roll_3d(CAMERA_ROLL_AMOUNT_3D),
+                Event::KeyDown(Keycode::Alphanumeric(b' ')) => self.toggle_animation_paused(),
+                Event::KeyDown(Keycode::Alphanumeric(b'f')) => self.step_animation_frame(),
+                Event::KeyDown(Keycode::Alphanumeric(b'r')) => {
+                    self.auto_rotate_enabled = !self.auto_rotate_enabled;
+                    self.scene_dirty = true;
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'v')) => {
+                    if self.thread_count > 1 {
+                        self.set_thread_count(self.thread_count - 1);
+                    }
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'b')) => {
+                    self.set_thread_count(self.thread_count + 1);
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b',')) => {
+                    if self.camera.adjust_eye_offset(-1) {
+                        self.scene_dirty = true;
+                    }
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'.')) => {
+                    if self.camera.adjust_eye_offset(1) {
+                        self.scene_dirty = true;
+                    }
+                }
+                Event::KeyDown(keycode) if arrow_key_direction(keycode).is_some() => {
+                    self.press_arrow_key(keycode);
+                }
+                Event::KeyUp(keycode) if arrow_key_direction(keycode).is_some() => {
+                    self.held_arrow_keys.retain(|&held_keycode| held_keycode != keycode);
+                    if self.held_arrow_keys.is_empty() {
+                        self.next_pan_nudge_time = None;
+                    }
+                }
+                Event::KeyDown(Keycode::Shift) => self.shift_down = true,
+                Event::KeyUp(Keycode::Shift) => self.shift_down = false,
+                Event::KeyDown(Keycode::Alphanumeric(digit @ b'0'..=b'9')) => {
+                    let slot = (digit - b'0') as usize;
+                    if self.shift_down {
+                        self.save_camera_preset(slot);
+                    } else {
+                        self.recall_camera_preset(slot);
+                    }
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'=')) |
+                Event::KeyDown(Keycode::Alphanumeric(b'+')) => self.step_zoom_power_of_two(1),
+                Event::KeyDown(Keycode::Alphanumeric(b'-')) => self.step_zoom_power_of_two(-1),
+                Event::KeyDown(Keycode::Alphanumeric(b'i')) => {
+                    if let Camera::TwoD(_) = self.camera {
+                        self.ui_model.zoom_input = Some(String::new());
+                        self.ui_dirty = true;
                     }
                 }
+                Event::KeyDown(Keycode::Alphanumeric(b'x')) => {
+                    self.checkerboard_enabled = !self.checkerboard_enabled;
+                    self.scene_dirty = true;
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'u')) => {
+                    self.ruler_visible = !self.ruler_visible;
+                    self.ui_dirty = true;
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b't')) => {
+                    self.ui_model.tile_grid_visible = !self.ui_model.tile_grid_visible;
+                    self.ui_dirty = true;
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'o')) => {
+                    self.bounds_outline_visible = !self.bounds_outline_visible;
+                    self.ui_dirty = true;
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'l')) => self.toggle_wireframe(),
+                Event::KeyDown(Keycode::Alphanumeric(b'j')) => self.toggle_color_legend(),
+                Event::KeyDown(Keycode::Alphanumeric(b'h')) => {
+                    self.snap_to_pixel = !self.snap_to_pixel;
+                    let message = if self.snap_to_pixel {
+                        "Snapping to pixels"
+                    } else {
+                        "Not snapping to pixels"
+                    };
+                    emit_message(&mut self.ui_model,
+                                 &mut self.message_epoch,
+                                 &self.message_timer,
+                                 message.to_owned());
+                    self.scene_dirty = true;
+                }
+                Event::KeyDown(Keycode::Alphanumeric(b'm')) => self.cycle_color_blindness(),
+                Event::KeyDown(Keycode::Alphanumeric(b'k')) => self.toggle_build_options_pinned(),
+                Event::KeyDown(Keycode::Alphanumeric(b'g')) => {
+                    self.options.show_ground = !self.options.show_ground;
+                    self.scene_dirty = true;
+                }
                 Event::KeyDown(Keycode::Tab) => {
                     self.options.ui = match self.options.ui {
                         UIVisibility::None => UIVisibility::Stats,
@@ -441,25 +1345,18 @@ impl<W> DemoApp<W> where W: Window {
                     }
                 }
 
-                Event::OpenData(ref data_path) => {
-                    let viewport = self.window.viewport(self.ui_model.mode.view(0));
-                    let filter = build_filter(&self.ui_model);
-                    self.content = load_scene(self.window.resource_loader(), data_path);
-
-                    let (mut scene, message) = self.content.render(viewport.size(), filter);
-                    self.ui_model.message = message;
-
-                    let viewport_size = self.window.viewport(self.ui_model.mode.view(0)).size();
-                    self.scene_metadata =
-                        SceneMetadata::new_clipping_view_box(&mut scene, viewport_size);
-                    self.camera = Camera::new(self.ui_model.mode,
-                                              self.scene_metadata.view_box,
-                                              viewport_size);
-
-                    self.scene_proxy.replace_scene(scene);
-
-                    self.dirty = true;
+                Event::OpenData(ref data_path) => self.open_data(data_path),
+                Event::KeyDown(Keycode::F5) => {
+                    // Reopen the current file to pick up changes made to it since it was loaded.
+                    // If it can't be read anymore, `open_data()` says so and keeps the scene.
+                    let data_path = self.data_path.clone();
+                    self.open_data(&data_path);
                 }
+                Event::DroppedFile(data_path) => dropped_files.push(data_path),
+                Event::KeyDown(Keycode::Alphanumeric(b'n')) => self.step_sample(1),
+                Event::KeyDown(Keycode::Alphanumeric(b'p')) => self.step_sample(-1),
+                Event::KeyDown(Keycode::Alphanumeric(b'[')) => self.step_playlist(-1),
+                Event::KeyDown(Keycode::Alphanumeric(b']')) => self.step_playlist(1),
 
                 Event::User {
                     message_type: event_id,
@@ -468,64 +1365,610 @@ impl<W> DemoApp<W> where W: Window {
                     && expected_epoch as u32 == self.message_epoch =>
                 {
                     self.ui_model.message = String::new();
-                    self.dirty = true;
+                    self.ui_dirty = true;
+                }
+                Event::User { message_type: event_id, .. }
+                        if event_id == self.slideshow_event_id => {
+                    self.step_playlist(1);
                 }
                 _ => continue,
             }
         }
 
-        ui_events
-    }
+        // Files dropped together arrive in the same batch of events. Several of them form a
+        // playlist, which `[` and `]` step through.
+        if !dropped_files.is_empty() {
+            self.open_data(&dropped_files[0]);
+            self.playlist = dropped_files;
+            self.playlist_index = 0;
+        }
 
-    fn process_mouse_position(&mut self, new_position: Vector2I) -> MousePosition {
-        let absolute = (new_position.to_f32() * self.window_size.backing_scale_factor).to_i32();
-        let relative = absolute - self.last_mouse_position;
-        self.last_mouse_position = absolute;
-        MousePosition { absolute, relative }
+        ui_events
     }
 
-    pub fn finish_drawing_frame(&mut self) {
-        self.maybe_take_screenshot();
+    // Replaces the scene with the one at the given path. The camera is reset to frame the new
+    // scene.
+    fn open_data(&mut self, data_path: &DataPath) {
+        // Standard input was consumed at startup, so there's nothing left to read.
+        if let DataPath::Stdin = *data_path {
+            emit_message(&mut self.ui_model,
+                         &mut self.message_epoch,
+                         &self.message_timer,
+                         "Standard input can only be read once.".to_owned());
+            self.ui_dirty = true;
+            return;
+        }
 
-        let frame = self.current_frame.take().unwrap();
-        for ui_event in &frame.ui_events {
-            self.dirty = true;
-            self.renderer
-                .debug_ui_presenter_mut()
-                .debug_ui_presenter
-                .ui_presenter
-                .event_queue
-                .push(*ui_event);
+        let viewport_size = scene_viewport_size(&self.window, self.ui_model.mode);
+        let filters = build_filters(&self.ui_model);
+        match load_scene(self.window.resource_loader(), data_path, &self.options) {
+            Ok(content) => self.content = content,
+            Err(load_error) => {
+                // Keep showing whatever was loaded before.
+                if self.options.quiet {
+                    eprintln!("error: {}", load_error);
+                } else {
+                    emit_message(&mut self.ui_model,
+                                 &mut self.message_epoch,
+                                 &self.message_timer,
+                                 load_error);
+                    self.ui_dirty = true;
+                }
+                return;
+            }
         }
+        // Reloading the same file, as `--watch` does, is usually a small edit, so keep the tiles
+        // of the paths that didn't change.
+        let reloading = *data_path == self.data_path;
+        self.data_path = (*data_path).clone();
 
-        self.renderer.debug_ui_presenter_mut().debug_ui_presenter.ui_presenter.mouse_position =
-            self.last_mouse_position.to_f32() * self.window_size.backing_scale_factor;
+        let (mut scene, result_flags) = self.content.render(viewport_size, filters);
+        self.ui_model.message = if self.options.quiet {
+            String::new()
+        } else {
+            get_svg_building_message(result_flags)
+        };
 
-        let mut ui_action = UIAction::None;
-        if self.options.ui == UIVisibility::All {
-            let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
-                self.renderer.debug_ui_presenter_mut();
-            self.ui_presenter.update(device,
-                                     allocator,
-                                     &mut self.window,
-                                     debug_ui_presenter,
-                                     &mut ui_action,
-                                     &mut self.ui_model);
+        self.scene_metadata =
+            SceneMetadata::new_clipping_view_box(&mut scene, viewport_size, &self.options);
+        self.camera = Camera::new(self.ui_model.mode,
+                                  self.scene_metadata.view_box,
+                                  viewport_size,
+                                  &self.options);
+
+        self.update_wireframe(&scene);
+        if reloading {
+            self.scene_proxy.update_scene(scene);
+        } else {
+            self.scene_proxy.replace_scene(scene);
         }
+        self.update_scene_clip_rect();
 
-        self.handle_ui_events(frame, &mut ui_action);
+        self.scene_dirty = true;
+    }
 
-        self.renderer.device().end_commands();
+    // Clips the scene to the viewport or, when letterboxing in 2D, to the part of the viewport
+    // that the scene's view box is drawn in, so that its aspect ratio is kept. The rest of the
+    // viewport shows the background color. `--clip-rect` letterboxes to the clip rect.
+    fn update_scene_clip_rect(&mut self) {
+        let clip_rect = self.downscaled_scene_clip_rect();
+        self.scene_proxy.set_view_box(clip_rect);
+        self.applied_scene_clip_rect = clip_rect;
 
-        self.window.present(self.renderer.device_mut());
-        self.frame_counter += 1;
+        // The scene itself may have been replaced, so the cached rendering can't be trusted.
+        self.scene_cache_key = None;
     }
 
-    fn maybe_take_screenshot(&mut self) {
-        match self.pending_screenshot_info.take() {
-            None => {}
-            Some(ScreenshotInfo { kind: ScreenshotType::PNG, path }) => {
-                self.take_raster_screenshot(path)
+    // Returns the part of the viewport, in device pixels, that `update_scene_clip_rect()` clips
+    // the scene to. The letterbox is wherever the camera puts the view box, so it takes the fit
+    // padding and any panning and zooming into account.
+    fn scene_clip_rect(&self) -> RectF {
+        let viewport_size = self.window.viewport(self.ui_model.mode.view(0)).size().to_f32();
+        let viewport = RectF::new(Vector2F::zero(), viewport_size);
+        let letterbox = self.options.letterbox || self.options.clip_rect.is_some();
+        match self.camera {
+            Camera::TwoD(ref transform) if letterbox && self.ui_model.mode == Mode::TwoD => {
+                (*transform * self.scene_metadata.view_box).intersection(viewport)
+                                                           .unwrap_or_default()
+            }
+            _ => viewport,
+        }
+    }
+
+    // Returns `scene_clip_rect()` in the pixels of the framebuffer that the scene is rendered to.
+    fn downscaled_scene_clip_rect(&self) -> RectF {
+        match self.scene_downscale() {
+            None => self.scene_clip_rect(),
+            Some(scale) => self.scene_clip_rect() * scale,
+        }
+    }
+
+    // Zooms the 2D camera in (`delta` = 1) or out (`delta` = -1) about the center of the window to
+    // the next power-of-two scale, so that scene pixels map exactly to device pixels or to whole
+    // blocks of them.
+    fn step_zoom_power_of_two(&mut self, delta: i32) {
+        let transform = match self.camera {
+            Camera::TwoD(ref mut transform) => transform,
+            _ => return,
+        };
+        let scale = transform.scale_factor();
+        if scale <= 0.0 {
+            return;
+        }
+
+        // Tolerate rounding error so that a scale that's already a power of two steps to the next
+        // one instead of snapping to itself.
+        let exponent = scale.log2();
+        let new_exponent = if delta > 0 {
+            (exponent + POWER_OF_TWO_ZOOM_EPSILON).floor() + 1.0
+        } else {
+            (exponent - POWER_OF_TWO_ZOOM_EPSILON).ceil() - 1.0
+        };
+
+        let center = center_of_window(&self.window_size);
+        let new_scale = new_exponent.exp2();
+        *transform = transform.translate(-center).scale(new_scale / scale).translate(center);
+        *transform = clamp_scale_2d(*transform, center, &self.options);
+        self.scene_dirty = true;
+    }
+
+    // Edits the zoom percentage being typed. Enter zooms to it and Escape cancels.
+    fn handle_zoom_input_key(&mut self, keycode: Keycode) {
+        let zoom_input = match self.ui_model.zoom_input {
+            None => return,
+            Some(ref mut zoom_input) => zoom_input,
+        };
+        match keycode {
+            Keycode::Alphanumeric(digit @ b'0'..=b'9') | Keycode::Alphanumeric(digit @ b'.') => {
+                if zoom_input.len() < MAX_ZOOM_INPUT_LENGTH {
+                    zoom_input.push(digit as char);
+                }
+            }
+            Keycode::Backspace => drop(zoom_input.pop()),
+            Keycode::Escape => self.ui_model.zoom_input = None,
+            Keycode::Enter => {
+                let zoom_input = self.ui_model.zoom_input.take().unwrap();
+                match zoom_input.parse::<f32>() {
+                    Ok(percentage) if percentage > 0.0 => self.zoom_to_scale(percentage / 100.0),
+                    _ => {
+                        let message = format!("\"{}\" isn't a zoom percentage.", zoom_input);
+                        emit_message(&mut self.ui_model,
+                                     &mut self.message_epoch,
+                                     &self.message_timer,
+                                     message);
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.ui_dirty = true;
+    }
+
+    // Zooms the 2D camera about the center of the window to the given scale, where 1.0 shows the
+    // scene at its native size. The scale is still clamped to the limits in the options.
+    fn zoom_to_scale(&mut self, new_scale: f32) {
+        let transform = match self.camera {
+            Camera::TwoD(ref mut transform) => transform,
+            _ => return,
+        };
+        let scale = transform.scale_factor();
+        if scale <= 0.0 {
+            return;
+        }
+
+        let center = center_of_window(&self.window_size);
+        *transform = transform.translate(-center).scale(new_scale / scale).translate(center);
+        *transform = clamp_scale_2d(*transform, center, &self.options);
+        self.scene_dirty = true;
+    }
+
+    // Opens the next (`delta` = 1) or previous (`delta` = -1) bundled sample SVG, wrapping around
+    // at either end.
+    fn step_sample(&mut self, delta: isize) {
+        let resource_loader = self.window.resource_loader();
+        let sample_paths = match resource_loader.list_directory(SAMPLE_SVG_VIRTUAL_DIRECTORY) {
+            Some(paths) => paths.into_iter().filter(|path| path.ends_with(".svg")).collect(),
+            None => vec![],
+        };
+        let sample_paths = if sample_paths.is_empty() {
+            FALLBACK_SAMPLE_SVG_VIRTUAL_PATHS.iter().map(|&path| path.to_owned()).collect()
+        } else {
+            sample_paths
+        };
+
+        let current_path = match self.data_path {
+            DataPath::Default => Some(DEFAULT_SVG_VIRTUAL_PATH),
+            DataPath::Resource(ref path) => Some(&**path),
+            DataPath::Path(_) | DataPath::Stdin => None,
+        };
+        let sample_count = sample_paths.len() as isize;
+        let index = match sample_paths.iter().position(|path| Some(&**path) == current_path) {
+            Some(index) => (index as isize + delta).rem_euclid(sample_count),
+            // Start from whichever end we're heading away from.
+            None if delta > 0 => 0,
+            None => sample_count - 1,
+        };
+
+        let data_path = DataPath::Resource(sample_paths[index as usize].clone());
+        self.open_data(&data_path);
+    }
+
+    // Opens the next (`delta` = 1) or previous (`delta` = -1) file in the playlist, wrapping
+    // around at either end.
+    fn step_playlist(&mut self, delta: isize) {
+        if self.playlist.len() < 2 {
+            return;
+        }
+        let playlist_length = self.playlist.len() as isize;
+        let index = (self.playlist_index as isize + delta).rem_euclid(playlist_length) as usize;
+        self.playlist_index = index;
+        let data_path = self.playlist[index].clone();
+        self.open_data(&data_path);
+    }
+
+    // Rebuilds the executor with the given number of threads, keeping the current scene. One
+    // thread selects sequential mode.
+    fn set_thread_count(&mut self, thread_count: usize) {
+        let executor = DemoExecutor::new(Some(thread_count));
+        self.thread_count = executor.thread_count();
+
+        let scene = self.scene_proxy.copy_scene();
+        let level = self.renderer.mode().level;
+        self.scene_proxy = SceneProxy::from_scene(scene, level, executor);
+        self.scene_cache_key = None;
+
+        let message = if self.thread_count == 1 {
+            "Building scenes sequentially.".to_owned()
+        } else {
+            format!("Building scenes on {} threads.", self.thread_count)
+        };
+        emit_message(&mut self.ui_model, &mut self.message_epoch, &self.message_timer, message);
+        self.scene_dirty = true;
+    }
+
+    // Shows the file name in the title bar, along with the zoom level in 2D or the direction the
+    // camera is facing in 3D.
+    fn update_window_title(&mut self) {
+        let file_name = data_path_display_name(&self.data_path);
+        let title = match self.camera {
+            Camera::TwoD(ref transform) => {
+                format!("{} \u{2014} {:.0}% \u{2014} {}",
+                        file_name,
+                        transform.scale_factor() * 100.0,
+                        WINDOW_TITLE)
+            }
+            Camera::ThreeD { ref modelview_transform, .. } => {
+                format!("{} \u{2014} yaw {:.0}\u{b0}, pitch {:.0}\u{b0}, roll {:.0}\u{b0} \
+                         \u{2014} {}",
+                        file_name,
+                        modelview_transform.yaw.to_degrees(),
+                        modelview_transform.pitch.to_degrees(),
+                        modelview_transform.roll.to_degrees(),
+                        WINDOW_TITLE)
+            }
+            Camera::Transition(_) => format!("{} \u{2014} {}", file_name, WINDOW_TITLE),
+        };
+        if title != self.window_title {
+            self.window.set_title(&title);
+            self.window_title = title;
+        }
+    }
+
+    // Stores the 3D camera position in the given slot and writes all presets to disk.
+    fn save_camera_preset(&mut self, slot: usize) {
+        let preset = match self.camera {
+            Camera::ThreeD { ref modelview_transform, .. } => modelview_transform.to_preset(),
+            _ => return,
+        };
+        self.camera_presets.set(slot, preset);
+        let message = match self.camera_presets.save(&self.options.camera_presets) {
+            Ok(()) => format!("Saved camera preset {}.", slot),
+            Err(error) => format!("Couldn't save camera presets: {}.", error),
+        };
+        emit_message(&mut self.ui_model, &mut self.message_epoch, &self.message_timer, message);
+        self.ui_dirty = true;
+    }
+
+    fn recall_camera_preset(&mut self, slot: usize) {
+        let preset = match self.camera_presets.get(slot) {
+            None => return,
+            Some(preset) => preset,
+        };
+        if let Camera::ThreeD { ref mut modelview_transform, .. } = self.camera {
+            modelview_transform.apply_preset(&preset);
+            self.scene_dirty = true;
+        }
+    }
+
+    // Zooms the 2D camera so that the rectangle that was dragged out fills as much of the window
+    // as it can without changing its aspect ratio.
+    fn finish_rubber_band(&mut self) {
+        let rect = match self.rubber_band.take() {
+            None => return,
+            Some(rubber_band) => rubber_band.rect(),
+        };
+        self.ui_dirty = true;
+        if rect.width() < MIN_RUBBER_BAND_SIZE || rect.height() < MIN_RUBBER_BAND_SIZE {
+            return;
+        }
+
+        let transform = match self.camera {
+            Camera::TwoD(ref mut transform) => transform,
+            _ => return,
+        };
+        let window_size = self.window_size.device_size().to_f32();
+        let scale = f32::min(window_size.x() / rect.width(), window_size.y() / rect.height());
+        let center = center_of_window(&self.window_size);
+        *transform = transform.translate(-rect.center()).scale(scale).translate(center);
+        *transform = clamp_scale_2d(*transform, center, &self.options);
+        self.scene_dirty = true;
+    }
+
+    // Rolls the 3D camera about its line of sight.
+    fn roll_3d(&mut self, delta: f32) {
+        if let Camera::ThreeD { ref mut modelview_transform, .. } = self.camera {
+            modelview_transform.roll += delta;
+            self.scene_dirty = true;
+        }
+    }
+
+    // Rotates the 2D camera about the center of the window.
+    fn rotate_2d(&mut self, delta: f32) {
+        if let Camera::TwoD(ref mut transform) = self.camera {
+            let center = center_of_window(&self.window_size);
+            *transform = transform.translate(-center).rotate(delta).translate(center);
+            self.ui_model.set_rotation(transform.rotation());
+            self.scene_dirty = true;
+        }
+    }
+
+    fn process_mouse_position(&mut self, new_position: Vector2I) -> MousePosition {
+        let absolute = (new_position.to_f32() * self.window_backing_scale_factor).to_i32();
+        let relative = absolute - self.last_mouse_position;
+        self.last_mouse_position = absolute;
+        MousePosition { absolute, relative }
+    }
+
+    pub fn finish_drawing_frame(&mut self) {
+        if !self.options.screenshot_include_ui {
+            self.capture_frame();
+        }
+
+        let frame = self.current_frame.take().unwrap();
+        for ui_event in &frame.ui_events {
+            self.ui_dirty = true;
+            self.renderer
+                .debug_ui_presenter_mut()
+                .debug_ui_presenter
+                .ui_presenter
+                .event_queue
+                .push(*ui_event);
+        }
+
+        self.renderer.debug_ui_presenter_mut().debug_ui_presenter.ui_presenter.mouse_position =
+            self.last_mouse_position.to_f32() * self.window_size.backing_scale_factor;
+
+        let mut ui_action = UIAction::None;
+        if self.options.ui == UIVisibility::All {
+            let file_name = data_path_display_name(&self.data_path);
+            if let Camera::TwoD(ref transform) = self.camera {
+                self.ui_model.zoom_scale = transform.scale_factor();
+            }
+            let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
+                self.renderer.debug_ui_presenter_mut();
+            self.ui_presenter.update(device,
+                                     allocator,
+                                     &mut self.window,
+                                     debug_ui_presenter,
+                                     &mut ui_action,
+                                     &mut self.ui_model,
+                                     &file_name);
+            self.ui_model.apply_forced_effects(&self.options);
+        }
+
+        if self.ui_model.tile_grid_visible && self.camera.mode() != Mode::VR {
+            // Tiles are laid out from the origin of the framebuffer that the scene is rendered to.
+            let tile_size = vec2f(TILE_WIDTH as f32, TILE_HEIGHT as f32);
+            let tile_size = match self.scene_downscale() {
+                None => tile_size,
+                Some(scale) => tile_size * (1.0 / scale),
+            };
+            let view_box = self.scene_clip_rect().round_out().to_i32();
+            let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
+                self.renderer.debug_ui_presenter_mut();
+            self.ui_presenter.draw_tile_grid(device,
+                                             allocator,
+                                             debug_ui_presenter,
+                                             view_box,
+                                             tile_size);
+        }
+
+        if self.bounds_outline_visible {
+            if let Camera::TwoD(ref transform) = self.camera {
+                let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
+                    self.renderer.debug_ui_presenter_mut();
+                self.ui_presenter.draw_bounds_outlines(device,
+                                                       allocator,
+                                                       debug_ui_presenter,
+                                                       transform,
+                                                       self.scene_metadata.view_box,
+                                                       self.scene_metadata.bounds);
+            }
+        }
+
+        if let Some(ref wireframe_outlines) = self.wireframe_outlines {
+            if let Camera::TwoD(ref transform) = self.camera {
+                // A downscaled scene is flattened at the lower resolution.
+                let tolerance = self.options.antialiasing_quality.flattening_tolerance() /
+                    self.scene_downscale().unwrap_or(1.0);
+                let clip_rect = self.scene_clip_rect();
+                let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
+                    self.renderer.debug_ui_presenter_mut();
+                self.ui_presenter.draw_wireframe(device,
+                                                 allocator,
+                                                 debug_ui_presenter,
+                                                 wireframe_outlines,
+                                                 transform,
+                                                 tolerance,
+                                                 clip_rect);
+            }
+        }
+
+        if let Some(ref rubber_band) = self.rubber_band {
+            let rect = rubber_band.rect().round_out().to_i32();
+            let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
+                self.renderer.debug_ui_presenter_mut();
+            self.ui_presenter.draw_rubber_band(device, allocator, debug_ui_presenter, rect);
+        }
+
+        if self.color_legend_visible {
+            if let Some(foreground_color) = self.scene_metadata.legend_color {
+                let background_color = self.ui_model.background_color();
+                let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
+                    self.renderer.debug_ui_presenter_mut();
+                self.ui_presenter.draw_color_legend(device,
+                                                    allocator,
+                                                    debug_ui_presenter,
+                                                    foreground_color,
+                                                    background_color);
+            }
+        }
+
+        if self.ruler_visible {
+            if let Camera::TwoD(ref transform) = self.camera {
+                let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
+                    self.renderer.debug_ui_presenter_mut();
+                self.ui_presenter.draw_ruler(device, allocator, debug_ui_presenter, transform);
+            }
+        }
+
+        if self.options.screenshot_include_ui {
+            self.capture_frame();
+        }
+
+        self.handle_ui_events(frame, &mut ui_action);
+        self.update_window_title();
+
+        self.renderer.device().end_commands();
+
+        self.window.present(self.renderer.device_mut());
+        self.frame_counter += 1;
+
+        if self.frame_timestamps.len() == FPS_SAMPLE_COUNT {
+            self.frame_timestamps.pop_front();
+        }
+        self.frame_timestamps.push_back(Instant::now());
+
+        // The captured frame is the first one drawn, so there's nothing left to do.
+        if self.options.capture_frame.is_some() {
+            self.should_exit = true;
+        }
+
+        if let Some(frame_limit) = self.options.frame_limit {
+            if self.frame_counter >= frame_limit {
+                self.should_exit = true;
+            } else {
+                // Keep rendering so that the limit is reached even if nothing changes.
+                self.scene_dirty = true;
+            }
+        }
+
+        self.update_benchmark();
+        self.record_stats();
+        self.log_timing();
+        self.report_frame_stats();
+    }
+
+    fn report_frame_stats(&mut self) {
+        let stats = match self.frame_render_stats.take() {
+            None => return,
+            Some(stats) => stats,
+        };
+        if let Some(ref mut frame_stats_callback) = self.frame_stats_callback {
+            frame_stats_callback(&stats, stats.cpu_build_time);
+        }
+    }
+
+    // Logs how long the CPU spent building the scene and the GPU spent rendering it, so that CPU
+    // and GPU bottlenecks can be told apart. GPU timer queries complete asynchronously, so the GPU
+    // time is from the most recent frame for which it's available.
+    fn log_timing(&self) {
+        if !self.options.log_timing {
+            return;
+        }
+
+        let build_time = stats::duration_to_ms(self.renderer.stats().cpu_build_time);
+        match self.renderer.last_rendering_time() {
+            None => info!("frame {}: build {:.3} ms", self.frame_counter, build_time),
+            Some(rendering_time) => {
+                info!("frame {}: build {:.3} ms, GPU {:.3} ms",
+                      self.frame_counter,
+                      build_time,
+                      stats::duration_to_ms(rendering_time.total_time()));
+            }
+        }
+    }
+
+    fn update_benchmark(&mut self) {
+        let finished = match self.benchmark {
+            None => return,
+            Some(ref mut benchmark) => benchmark.end_frame(),
+        };
+
+        if finished {
+            self.benchmark.take().unwrap().print_results();
+            self.should_exit = true;
+        } else {
+            // Keep rendering as fast as possible, regardless of whether anything changed.
+            self.scene_dirty = true;
+        }
+    }
+
+    fn record_stats(&mut self) {
+        let stats_recorder = match self.stats_recorder {
+            None => return,
+            Some(ref mut stats_recorder) => stats_recorder,
+        };
+
+        stats_recorder.record(*self.renderer.stats(), self.renderer.last_rendering_time());
+
+        if self.should_exit {
+            if let Some(ref path) = self.options.stats_json {
+                let result = File::create(path).and_then(|file| {
+                    let mut writer = BufWriter::new(file);
+                    stats_recorder.write_json(&mut writer)?;
+                    writer.flush()
+                });
+                if let Err(error) = result {
+                    eprintln!("error: couldn't write \"{}\": {}", path.display(), error);
+                    process::exit(1);
+                }
+            }
+        }
+    }
+
+    // Saves any screenshots due this frame.
+    fn capture_frame(&mut self) {
+        self.maybe_take_screenshot();
+        self.maybe_output_frame();
+        self.maybe_advance_dpi_sweep();
+    }
+
+    // Returns true if the frame being rendered will be saved to disk.
+    fn is_capturing_frame(&self) -> bool {
+        self.pending_screenshot_info.is_some() || self.options.output_frames.is_some() ||
+            self.dpi_sweep.is_some()
+    }
+
+    fn maybe_take_screenshot(&mut self) {
+        match self.pending_screenshot_info.take() {
+            None => {}
+            Some(ScreenshotInfo { kind: ScreenshotType::PNG, path }) => {
+                if self.options.screenshot_scale > 1 && self.camera.mode() != Mode::VR {
+                    self.take_scaled_raster_screenshot(path, self.options.screenshot_scale)
+                } else {
+                    self.take_raster_screenshot(path)
+                }
             }
             Some(ScreenshotInfo { kind: ScreenshotType::SVG, path }) => {
                 // FIXME(pcwalton): This won't work on Android.
@@ -535,6 +1978,56 @@ impl<W> DemoApp<W> where W: Window {
         }
     }
 
+    fn maybe_output_frame(&mut self) {
+        let path = match self.options.output_frames {
+            None => return,
+            Some(ref output_frames_dir) => {
+                let frame_index = self.options.capture_frame.unwrap_or(self.frame_counter);
+                output_frames_dir.join(format!("frame_{:05}.png", frame_index))
+            }
+        };
+        self.take_raster_screenshot(path);
+    }
+
+    // Captures the frame just rendered at the current DPI sweep scale factor and moves on to the
+    // next one, restoring the real window size once the sweep is complete.
+    fn maybe_advance_dpi_sweep(&mut self) {
+        let index = match self.dpi_sweep {
+            None => return,
+            Some(ref dpi_sweep) => dpi_sweep.index,
+        };
+
+        let backing_scale_factor = self.options.dpi_sweep[index];
+        // The images go with the frames when those are being written out too.
+        let file_name = format!("dpi-sweep-{}x.png", backing_scale_factor);
+        let path = match self.options.output_frames {
+            None => PathBuf::from(file_name),
+            Some(ref output_frames_dir) => output_frames_dir.join(file_name),
+        };
+        self.take_raster_screenshot(path);
+
+        match self.options.dpi_sweep.get(index + 1) {
+            Some(&next_backing_scale_factor) => {
+                self.dpi_sweep.as_mut().unwrap().index = index + 1;
+                self.set_dpi_sweep_scale_factor(next_backing_scale_factor);
+            }
+            None => {
+                self.window_size = self.dpi_sweep.take().unwrap().original_window_size;
+                self.scene_dirty = true;
+            }
+        }
+    }
+
+    fn set_dpi_sweep_scale_factor(&mut self, backing_scale_factor: f32) {
+        let original_window_size = match self.dpi_sweep {
+            None => return,
+            Some(ref dpi_sweep) => dpi_sweep.original_window_size,
+        };
+        self.window_size = emulate_backing_scale_factor(original_window_size,
+                                                        backing_scale_factor);
+        self.scene_dirty = true;
+    }
+
     fn handle_ui_events(&mut self, mut frame: Frame, ui_action: &mut UIAction) {
         frame.ui_events = self.renderer
                               .debug_ui_presenter_mut()
@@ -548,20 +2041,33 @@ impl<W> DemoApp<W> where W: Window {
         // Switch camera mode (2D/3D) if requested.
         //
         // FIXME(pcwalton): This should really be an MVC setup.
-        if self.camera.mode() != self.ui_model.mode {
-            let viewport_size = self.window.viewport(self.ui_model.mode.view(0)).size();
-            self.camera = Camera::new(self.ui_model.mode,
-                                      self.scene_metadata.view_box,
-                                      viewport_size);
+        if self.camera.target_mode() != self.ui_model.mode {
+            let viewport_size = scene_viewport_size(&self.window, self.ui_model.mode);
+            let new_camera = Camera::new(self.ui_model.mode,
+                                         self.scene_metadata.view_box,
+                                         viewport_size,
+                                         &self.options);
+
+            // Animate between 2D and 3D. Switches to and from VR happen immediately, since the
+            // framebuffer setup differs.
+            self.camera = if self.ui_model.mode == Mode::VR || self.camera.mode() == Mode::VR {
+                new_camera
+            } else {
+                let old_camera = mem::replace(&mut self.camera,
+                                              Camera::TwoD(Transform2F::default()));
+                Camera::new_transition(old_camera, new_camera, viewport_size)
+            };
+            self.update_scene_clip_rect();
+            self.scene_dirty = true;
         }
 
         for ui_event in frame.ui_events {
             match ui_event {
-                UIEvent::MouseDown(_) if self.camera.is_3d() => {
+                UIEvent::MouseDown(_) if self.camera.is_3d() && !self.options.kiosk => {
                     // If nothing handled the mouse-down event, toggle mouselook.
                     self.mouselook_enabled = !self.mouselook_enabled;
                 }
-                UIEvent::MouseDragged(position) => {
+                UIEvent::MouseDragged(position) if !self.options.kiosk => {
                     if let Camera::TwoD(ref mut transform) = self.camera {
                         *transform = transform.translate(position.relative.to_f32());
                     }
@@ -574,40 +2080,49 @@ impl<W> DemoApp<W> where W: Window {
     fn handle_ui_action(&mut self, ui_action: &mut UIAction) {
         match ui_action {
             UIAction::None => {}
-            UIAction::ModelChanged => self.dirty = true,
+            UIAction::ModelChanged => self.ui_dirty = true,
             UIAction::EffectsChanged => {
                 let viewport_size = self.window.viewport(self.ui_model.mode.view(0)).size();
-                let filter = build_filter(&self.ui_model);
-                let (mut scene, _) = self.content.render(viewport_size, filter);
+                let filters = build_filters(&self.ui_model);
+                let (mut scene, _) = self.content.render(viewport_size, filters);
                 self.scene_metadata =
-                    SceneMetadata::new_clipping_view_box(&mut scene, viewport_size);
+                    SceneMetadata::new_clipping_view_box(&mut scene,
+                                                         viewport_size,
+                                                         &self.options);
+                self.update_wireframe(&scene);
                 self.scene_proxy.replace_scene(scene);
-                self.dirty = true;
+                self.update_scene_clip_rect();
+                self.scene_dirty = true;
             }
             UIAction::TakeScreenshot(ref info) => {
                 self.pending_screenshot_info = Some((*info).clone());
-                self.dirty = true;
+                self.scene_dirty = true;
             }
+            UIAction::ZoomIn | UIAction::ZoomOut | UIAction::ZoomActualSize |
+            UIAction::Rotate(_) if self.options.kiosk => {}
             UIAction::ZoomIn => {
                 if let Camera::TwoD(ref mut transform) = self.camera {
-                    let scale = 1.0 + CAMERA_ZOOM_AMOUNT_2D;
+                    let scale = 1.0 + self.options.zoom_amount;
                     let center = center_of_window(&self.window_size);
                     *transform = transform.translate(-center).scale(scale).translate(center);
-                    self.dirty = true;
+                    *transform = clamp_scale_2d(*transform, center, &self.options);
+                    self.scene_dirty = true;
                 }
             }
             UIAction::ZoomOut => {
                 if let Camera::TwoD(ref mut transform) = self.camera {
-                    let scale = 1.0 - CAMERA_ZOOM_AMOUNT_2D;
+                    let scale = 1.0 - self.options.zoom_amount;
                     let center = center_of_window(&self.window_size);
                     *transform = transform.translate(-center).scale(scale).translate(center);
-                    self.dirty = true;
+                    *transform = clamp_scale_2d(*transform, center, &self.options);
+                    self.scene_dirty = true;
                 }
             }
             UIAction::ZoomActualSize => {
                 if let Camera::TwoD(ref mut transform) = self.camera {
-                    *transform = Transform2F::default();
-                    self.dirty = true;
+                    let center = center_of_window(&self.window_size);
+                    *transform = clamp_scale_2d(Transform2F::default(), center, &self.options);
+                    self.scene_dirty = true;
                 }
             }
             UIAction::Rotate(theta) => {
@@ -632,6 +2147,71 @@ pub struct Options {
     pub background_color: BackgroundColor,
     pub high_performance_gpu: bool,
     pub renderer_level: Option<RendererLevel>,
+    pub output_frames: Option<PathBuf>,
+    pub dpi_sweep: Vec<f32>,
+    pub fit_padding: f32,
+    pub stats_json: Option<PathBuf>,
+    pub show_ground: bool,
+    pub benchmark_frames: Option<u32>,
+    pub mouselook_speed: f32,
+    pub watch: bool,
+    pub strict: bool,
+    pub warnings_json: Option<PathBuf>,
+    pub min_scale_2d: f32,
+    pub max_scale_2d: f32,
+    pub orthographic: bool,
+    pub initial_transform: Option<String>,
+    pub message_timeout: Duration,
+    pub auto_rotate: Option<f32>,
+    pub print_metadata: bool,
+    pub near_clip_plane: Option<f32>,
+    pub far_clip_plane: Option<f32>,
+    pub no_aa: bool,
+    pub screenshot_include_ui: bool,
+    pub log_timing: bool,
+    pub letterbox: bool,
+    pub dump_commands: Option<PathBuf>,
+    pub fit: Fit,
+    pub camera_presets: PathBuf,
+    pub no_stem_darkening: bool,
+    pub no_subpixel_aa: bool,
+    pub ground_depth_func: Option<DepthFunc>,
+    pub infinite_ground: bool,
+    pub no_fit: bool,
+    pub msaa: Option<u32>,
+    pub list_fonts_used: bool,
+    pub frame_limit: Option<u32>,
+    pub force_mono: Option<ColorU>,
+    pub force_multicolor: bool,
+    pub render_when_unfocused: bool,
+    pub background_gradient: Option<(ColorU, ColorU)>,
+    pub background_image: Option<PathBuf>,
+    pub kiosk: bool,
+    pub slideshow: Option<PathBuf>,
+    pub slideshow_interval: Duration,
+    pub clip_rect: Option<RectF>,
+    pub export_svg: Option<PathBuf>,
+    pub scale_speed: f32,
+    pub zoom_amount: f32,
+    pub srgb: bool,
+    pub max_texture_size: Option<i32>,
+    pub defringing_kernel: DefringingKernelKind,
+    pub screenshot_scale: i32,
+    pub antialiasing_quality: AntialiasingQuality,
+    pub transform_file: Option<PathBuf>,
+    pub quiet: bool,
+    pub window_size: Option<Vector2I>,
+    pub snap_to_pixel: bool,
+    pub dpr: Option<f32>,
+    pub backend: Option<Backend>,
+    pub reprojection_filter: ReprojectionFilter,
+    pub stats_only: bool,
+    pub watermark: Option<PathBuf>,
+    pub watermark_opacity: f32,
+    pub watermark_corner: Corner,
+    pub antialias_gamma: f32,
+    pub capture_frame: Option<u32>,
+    pub max_fps: Option<f32>,
     hidden_field_for_future_proofing: (),
 }
 
@@ -645,6 +2225,71 @@ impl Default for Options {
             background_color: BackgroundColor::Light,
             high_performance_gpu: false,
             renderer_level: None,
+            output_frames: None,
+            dpi_sweep: vec![],
+            fit_padding: 0.05,
+            stats_json: None,
+            show_ground: true,
+            benchmark_frames: None,
+            mouselook_speed: MOUSELOOK_ROTATION_SPEED,
+            watch: false,
+            strict: false,
+            warnings_json: None,
+            min_scale_2d: DEFAULT_MIN_SCALE_2D,
+            max_scale_2d: DEFAULT_MAX_SCALE_2D,
+            orthographic: false,
+            initial_transform: None,
+            message_timeout: Duration::from_secs(DEFAULT_MESSAGE_TIMEOUT_SECS),
+            auto_rotate: None,
+            print_metadata: false,
+            near_clip_plane: None,
+            far_clip_plane: None,
+            no_aa: false,
+            screenshot_include_ui: false,
+            log_timing: false,
+            letterbox: false,
+            dump_commands: None,
+            fit: Fit::Auto,
+            camera_presets: PathBuf::from(DEFAULT_CAMERA_PRESETS_PATH),
+            no_stem_darkening: false,
+            no_subpixel_aa: false,
+            ground_depth_func: Some(DepthFunc::Less),
+            infinite_ground: false,
+            no_fit: false,
+            msaa: None,
+            list_fonts_used: false,
+            frame_limit: None,
+            force_mono: None,
+            force_multicolor: false,
+            render_when_unfocused: false,
+            background_gradient: None,
+            background_image: None,
+            kiosk: false,
+            slideshow: None,
+            slideshow_interval: Duration::from_secs(DEFAULT_SLIDESHOW_INTERVAL_SECS),
+            clip_rect: None,
+            export_svg: None,
+            scale_speed: DEFAULT_CAMERA_SCALE_SPEED_2D,
+            zoom_amount: DEFAULT_CAMERA_ZOOM_AMOUNT_2D,
+            srgb: false,
+            max_texture_size: None,
+            defringing_kernel: DefringingKernelKind::CoreGraphics,
+            screenshot_scale: 1,
+            antialiasing_quality: AntialiasingQuality::High,
+            transform_file: None,
+            quiet: false,
+            window_size: None,
+            snap_to_pixel: false,
+            dpr: None,
+            backend: None,
+            reprojection_filter: ReprojectionFilter::Linear,
+            stats_only: false,
+            watermark: None,
+            watermark_opacity: 1.0,
+            watermark_corner: Corner::BottomRight,
+            antialias_gamma: 1.0,
+            capture_frame: None,
+            max_fps: None,
             hidden_field_for_future_proofing: (),
         }
     }
@@ -653,13 +2298,27 @@ impl Default for Options {
 impl Options {
     pub fn command_line_overrides(&mut self) {
         let matches = App::new("demo")
+            .arg(
+                Arg::with_name("config")
+                    .long("config")
+                    .value_name("PATH")
+                    .takes_value(true)
+                    .help("Read options from a TOML file; flags given on the command line \
+                           override it"),
+            )
             .arg(
                 Arg::with_name("jobs")
                     .short("j")
                     .long("jobs")
                     .value_name("THREADS")
                     .takes_value(true)
-                    .help("Number of threads to use"),
+                    .help("Number of threads to use (0 or 1 builds scenes sequentially)"),
+            )
+            .arg(
+                Arg::with_name("single-threaded")
+                    .long("single-threaded")
+                    .help("Build scenes sequentially on a single thread")
+                    .conflicts_with("jobs"),
             )
             .arg(
                 Arg::with_name("3d")
@@ -706,116 +2365,1306 @@ impl Options {
                     .possible_values(&["9", "11"])
             )
             .arg(
-                Arg::with_name("INPUT")
-                    .help("Path to the SVG file to render")
-                    .index(1),
+                Arg::with_name("output-frames")
+                    .long("output-frames")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .help("Write every rendered frame to DIR as a numbered PNG; add --max-fps \
+                           for evenly paced frames")
             )
-            .get_matches();
-
-        if let Some(jobs) = matches.value_of("jobs") {
-            self.jobs = jobs.parse().ok();
-        }
-
-        if matches.is_present("3d") {
-            self.mode = Mode::ThreeD;
-        } else if matches.is_present("vr") {
-            self.mode = Mode::VR;
-        }
-
-        if let Some(ui) = matches.value_of("ui") {
-            self.ui = match ui {
-                "none" => UIVisibility::None,
-                "stats" => UIVisibility::Stats,
-                _ => UIVisibility::All,
-            };
-        }
-
-        if let Some(background_color) = matches.value_of("background") {
-            self.background_color = match background_color {
-                "light" => BackgroundColor::Light,
-                "dark" => BackgroundColor::Dark,
-                _ => BackgroundColor::Transparent,
-            };
-        }
-
-        if matches.is_present("high-performance-gpu") {
-            self.high_performance_gpu = true;
-        }
-
-        if let Some(renderer_level) = matches.value_of("level") {
-            if renderer_level == "11" {
-                self.renderer_level = Some(RendererLevel::D3D11);
-            } else if renderer_level == "9" {
-                self.renderer_level = Some(RendererLevel::D3D9);
-            }
-        }
-
-        if let Some(path) = matches.value_of("INPUT") {
-            self.input_path = DataPath::Path(PathBuf::from(path));
-        };
-    }
-}
-
-#[derive(Clone, Copy, PartialEq)]
-pub enum UIVisibility {
+            .arg(
+                Arg::with_name("dpi-sweep")
+                    .long("dpi-sweep")
+                    .value_name("SCALES")
+                    .takes_value(true)
+                    .help("Capture a screenshot at each of the comma-separated backing scale \
+                           factors, into the --output-frames directory if one is given")
+            )
+            .arg(
+                Arg::with_name("fit-padding")
+                    .long("fit-padding")
+                    .value_name("FRACTION")
+                    .takes_value(true)
+                    .help("Fraction of the viewport to leave empty around the scene")
+            )
+            .arg(
+                Arg::with_name("stats-json")
+                    .long("stats-json")
+                    .value_name("PATH")
+                    .takes_value(true)
+                    .help("Write per-frame rendering statistics to PATH as JSON on exit")
+            )
+            .arg(
+                Arg::with_name("no-ground")
+                    .long("no-ground")
+                    .help("Don't draw the ground plane in 3D and VR modes")
+            )
+            .arg(
+                Arg::with_name("benchmark")
+                    .long("benchmark")
+                    .value_name("FRAMES")
+                    .takes_value(true)
+                    .help("Render FRAMES frames, print frame time statistics, and exit")
+            )
+            .arg(
+                Arg::with_name("mouselook-speed")
+                    .long("mouselook-speed")
+                    .value_name("SPEED")
+                    .takes_value(true)
+                    .allow_hyphen_values(true)
+                    .help("Mouselook sensitivity in radians per pixel (negative inverts Y)")
+            )
+            .arg(
+                Arg::with_name("watch")
+                    .long("watch")
+                    .help("Reload the input file whenever it changes on disk")
+            )
+            .arg(
+                Arg::with_name("strict")
+                    .long("strict")
+                    .help("Exit with an error if the SVG uses any unsupported features")
+            )
+            .arg(
+                Arg::with_name("warnings-json")
+                    .long("warnings-json")
+                    .value_name("PATH")
+                    .takes_value(true)
+                    .help("Write the unsupported SVG features encountered to PATH as JSON")
+            )
+            .arg(
+                Arg::with_name("min-scale")
+                    .long("min-scale")
+                    .value_name("SCALE")
+                    .takes_value(true)
+                    .help("The smallest scale factor the 2D camera can zoom out to")
+            )
+            .arg(
+                Arg::with_name("max-scale")
+                    .long("max-scale")
+                    .value_name("SCALE")
+                    .takes_value(true)
+                    .help("The largest scale factor the 2D camera can zoom in to")
+            )
+            .arg(
+                Arg::with_name("orthographic")
+                    .long("orthographic")
+                    .help("Use an orthographic projection instead of a perspective one in 3D")
+            )
+            .arg(
+                Arg::with_name("transform")
+                    .long("transform")
+                    .value_name("SCALE,TX,TY,ROTATION")
+                    .takes_value(true)
+                    .allow_hyphen_values(true)
+                    .help("The initial 2D transform: scale, then rotate (in degrees), then \
+                           translate")
+            )
+            .arg(
+                Arg::with_name("message-timeout")
+                    .long("message-timeout")
+                    .value_name("SECONDS")
+                    .takes_value(true)
+                    .help("How long to show messages and warnings for")
+            )
+            .arg(
+                Arg::with_name("auto-rotate")
+                    .long("auto-rotate")
+                    .value_name("DEGREES-PER-SECOND")
+                    .takes_value(true)
+                    .allow_hyphen_values(true)
+                    .help("Continuously rotate the scene in 3D mode")
+            )
+            .arg(
+                Arg::with_name("print-metadata")
+                    .long("print-metadata")
+                    .help("Print the view box, bounds, and monochrome color of the input, then exit")
+            )
+            .arg(
+                Arg::with_name("near")
+                    .long("near")
+                    .value_name("DISTANCE")
+                    .takes_value(true)
+                    .help("The distance to the near clip plane in 3D (default: derived from the \
+                           scene size)")
+            )
+            .arg(
+                Arg::with_name("far")
+                    .long("far")
+                    .value_name("DISTANCE")
+                    .takes_value(true)
+                    .help("The distance to the far clip plane in 3D (default: derived from the \
+                           scene size)")
+            )
+            .arg(
+                Arg::with_name("no-aa")
+                    .long("no-aa")
+                    .help("Render paths with hard, aliased edges, for pixel-exact comparisons")
+            )
+            .arg(
+                Arg::with_name("screenshot-include-ui")
+                    .long("screenshot-include-ui")
+                    .help("Include the UI and performance stats in screenshots")
+            )
+            .arg(
+                Arg::with_name("log-timing")
+                    .long("log-timing")
+                    .help("Log the CPU build time and GPU rendering time of each frame")
+            )
+            .arg(
+                Arg::with_name("letterbox")
+                    .long("letterbox")
+                    .help("Preserve the aspect ratio of the scene in 2D, filling the rest of the \
+                           window with the background color")
+            )
+            .arg(
+                Arg::with_name("dump-commands")
+                    .long("dump-commands")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .help("Write a transcript of the render commands for the first frame to a file")
+            )
+            .arg(
+                Arg::with_name("fit")
+                    .long("fit")
+                    .takes_value(true)
+                    .possible_values(&["auto", "width", "height"])
+                    .help("Which dimension of the scene to fit to the window initially in 2D")
+            )
+            .arg(
+                Arg::with_name("camera-presets")
+                    .long("camera-presets")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .help("The TOML file to save 3D camera presets to (Shift+0-9) and recall them \
+                           from (0-9)")
+            )
+            .arg(
+                Arg::with_name("no-stem-darkening")
+                    .long("no-stem-darkening")
+                    .help("Keep the stem darkening effect off, for reproducible text rendering")
+            )
+            .arg(
+                Arg::with_name("no-subpixel-aa")
+                    .long("no-subpixel-aa")
+                    .help("Keep the subpixel AA effect off, for reproducible text rendering")
+            )
+            .arg(
+                Arg::with_name("ground-depth")
+                    .long("ground-depth")
+                    .takes_value(true)
+                    .possible_values(&["less", "always", "none"])
+                    .help("The depth test for the 3D ground; `none` disables depth testing and \
+                           writes")
+            )
+            .arg(
+                Arg::with_name("infinite-ground")
+                    .long("infinite-ground")
+                    .help("Extend the 3D ground toward the horizon, fading the grid out with \
+                           distance")
+            )
+            .arg(
+                Arg::with_name("no-fit")
+                    .long("no-fit")
+                    .help("Show 2D scenes at their natural size instead of fitting them to the \
+                           window when they're opened")
+            )
+            .arg(
+                Arg::with_name("msaa")
+                    .long("msaa")
+                    .value_name("SAMPLES")
+                    .takes_value(true)
+                    .help("Render with the given number of samples per pixel, smoothing the \
+                           edges of the ground and the scene in 3D")
+            )
+            .arg(
+                Arg::with_name("list-fonts-used")
+                    .long("list-fonts-used")
+                    .help("Print the font families each SVG refers to, flagging missing ones")
+            )
+            .arg(
+                Arg::with_name("frame-limit")
+                    .long("frame-limit")
+                    .value_name("N")
+                    .takes_value(true)
+                    .help("Exit after rendering N frames")
+            )
+            .arg(
+                Arg::with_name("force-mono")
+                    .long("force-mono")
+                    .value_name("COLOR")
+                    .takes_value(true)
+                    .help("Fill every path with one color, given as #rrggbb or #rrggbbaa")
+            )
+            .arg(
+                Arg::with_name("force-multicolor")
+                    .long("force-multicolor")
+                    .conflicts_with("force-mono")
+                    .help("Treat the scene as multicolored even if every path has the same color")
+            )
+            .arg(
+                Arg::with_name("render-when-unfocused")
+                    .long("render-when-unfocused")
+                    .help("Keep rendering while the window is in the background")
+            )
+            .arg(
+                Arg::with_name("background-gradient")
+                    .long("background-gradient")
+                    .value_name("TOP,BOTTOM")
+                    .takes_value(true)
+                    .help("Fill the light and dark backgrounds in 2D with a vertical gradient \
+                           between two #rrggbb colors")
+            )
+            .arg(
+                Arg::with_name("background-image")
+                    .long("background-image")
+                    .value_name("PATH")
+                    .takes_value(true)
+                    .help("Stretch a PNG or JPEG image over the window behind the scene in 2D, \
+                           in place of the light and dark backgrounds")
+            )
+            .arg(
+                Arg::with_name("kiosk")
+                    .long("kiosk")
+                    .help("Lock the camera for unattended displays; auto-rotate still works")
+            )
+            .arg(
+                Arg::with_name("slideshow")
+                    .long("slideshow")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .conflicts_with("INPUT")
+                    .help("Cycle through the SVG files in a directory")
+            )
+            .arg(
+                Arg::with_name("interval")
+                    .long("interval")
+                    .value_name("SECONDS")
+                    .takes_value(true)
+                    .requires("slideshow")
+                    .help("How long to show each file of a slideshow for")
+            )
+            .arg(
+                Arg::with_name("clip-rect")
+                    .long("clip-rect")
+                    .value_name("X,Y,WIDTH,HEIGHT")
+                    .takes_value(true)
+                    .allow_hyphen_values(true)
+                    .help("Render and capture only this region of the scene, in scene units")
+            )
+            .arg(
+                Arg::with_name("export-svg")
+                    .long("export-svg")
+                    .value_name("PATH")
+                    .takes_value(true)
+                    .help("Write the input out as flattened SVG paths, then exit")
+            )
+            .arg(
+                Arg::with_name("scale-speed")
+                    .long("scale-speed")
+                    .value_name("SPEED")
+                    .takes_value(true)
+                    .help("How fast scale gestures zoom the 2D scene (default: 6)")
+            )
+            .arg(
+                Arg::with_name("zoom-amount")
+                    .long("zoom-amount")
+                    .value_name("FRACTION")
+                    .takes_value(true)
+                    .help("How much each click of a zoom button scales the 2D scene by \
+                           (default: 0.1)")
+            )
+            .arg(
+                Arg::with_name("srgb")
+                    .long("srgb")
+                    .conflicts_with("msaa")
+                    .help("Blend in linear light and encode the result as sRGB, outside of VR")
+            )
+            .arg(
+                Arg::with_name("max-texture-size")
+                    .long("max-texture-size")
+                    .value_name("PIXELS")
+                    .takes_value(true)
+                    .help("Renders the scene at a reduced size and upscales it if the viewport is \
+                           larger than this or the GPU's texture size limit")
+            )
+            .arg(
+                Arg::with_name("defringe-kernel")
+                    .long("defringe-kernel")
+                    .takes_value(true)
+                    .possible_values(&["coregraphics", "freetype"])
+                    .help("Whether subpixel antialiasing defringes text like macOS or like \
+                           FreeType")
+            )
+            .arg(
+                Arg::with_name("screenshot-scale")
+                    .long("screenshot-scale")
+                    .value_name("FACTOR")
+                    .takes_value(true)
+                    .help("Render PNG screenshots offscreen at this many times the window's \
+                           resolution")
+            )
+            .arg(
+                Arg::with_name("aa-quality")
+                    .long("aa-quality")
+                    .takes_value(true)
+                    .possible_values(&["low", "medium", "high"])
+                    .help("How closely antialiased edges follow curves; lower is faster. Only \
+                           the D3D9 level honors this")
+            )
+            .arg(
+                Arg::with_name("transform-file")
+                    .long("transform-file")
+                    .value_name("PATH")
+                    .takes_value(true)
+                    .help("Read the initial transform from a file: the six numbers of an SVG \
+                           matrix() in 2D, or a row-major 4x4 matrix to normalized device \
+                           coordinates in 3D")
+            )
+            .arg(
+                Arg::with_name("quiet")
+                    .long("quiet")
+                    .help("Don't show startup or reload warnings; print errors to stderr instead")
+            )
+            .arg(
+                Arg::with_name("window-size")
+                    .long("window-size")
+                    .value_name("WxH")
+                    .takes_value(true)
+                    .help("The initial size of the window, in logical pixels")
+            )
+            .arg(
+                Arg::with_name("snap-to-pixel")
+                    .long("snap-to-pixel")
+                    .help("Round the 2D translation to whole device pixels for crisper edges")
+            )
+            .arg(
+                Arg::with_name("dpr")
+                    .long("dpr")
+                    .value_name("RATIO")
+                    .takes_value(true)
+                    .help("Draw with this device pixel ratio instead of the display's, so that \
+                           captures are reproducible. The window is sized to have as many device \
+                           pixels as it would at this ratio, where the platform allows it")
+            )
+            .arg(
+                Arg::with_name("backend")
+                    .long("backend")
+                    .takes_value(true)
+                    .possible_values(&["gl3", "gl4", "metal"])
+                    .help("The graphics API to render with; Metal and OpenGL are chosen when \
+                           building, so this selects among those that were built in")
+            )
+            .arg(
+                Arg::with_name("reprojection-filter")
+                    .long("reprojection-filter")
+                    .takes_value(true)
+                    .possible_values(&["linear", "nearest"])
+                    .help("How to sample the scene when reprojecting it for each eye in VR; \
+                           linear can blur text, and nearest aliases")
+            )
+            .arg(
+                Arg::with_name("stats-only")
+                    .long("stats-only")
+                    .help("Print the scene's path, segment, approximate tile, and render command \
+                           counts as key=value lines and exit without rendering")
+            )
+            .arg(
+                Arg::with_name("watermark")
+                    .long("watermark")
+                    .value_name("PATH")
+                    .takes_value(true)
+                    .help("An SVG to draw over the scene in a corner of the window, at its natural \
+                           size, without moving with the camera")
+            )
+            .arg(
+                Arg::with_name("watermark-opacity")
+                    .long("watermark-opacity")
+                    .value_name("OPACITY")
+                    .takes_value(true)
+                    .help("The opacity of each of the watermark's colors, from 0 to 1; \
+                           overlapping shapes show through each other")
+            )
+            .arg(
+                Arg::with_name("watermark-corner")
+                    .long("watermark-corner")
+                    .takes_value(true)
+                    .possible_values(&["top-left", "top-right", "bottom-left", "bottom-right"])
+                    .help("The corner of the window to draw the watermark in")
+            )
+            .arg(
+                Arg::with_name("antialias-gamma")
+                    .long("antialias-gamma")
+                    .value_name("GAMMA")
+                    .takes_value(true)
+                    .help("Raise the coverage of antialiased edges to this power before using it \
+                           as alpha, to match other renderers; 1 leaves it linear")
+            )
+            .arg(
+                Arg::with_name("capture-frame")
+                    .long("capture-frame")
+                    .value_name("N")
+                    .takes_value(true)
+                    .requires("output-frames")
+                    .help("Auto-rotate the 3D scene to frame N at a fixed step per frame, write \
+                           that frame to the --output-frames directory, and exit")
+            )
+            .arg(
+                Arg::with_name("max-fps")
+                    .long("max-fps")
+                    .value_name("FPS")
+                    .takes_value(true)
+                    .help("Present at most this many frames per second. With --output-frames, \
+                           animations also advance exactly one frame's time per frame, so the \
+                           frames can be played back at this rate")
+            )
+            .arg(
+                Arg::with_name("INPUT")
+                    .help("Path to the SVG file to render, or `-` to read it from standard input")
+                    .index(1),
+            )
+            .get_matches();
+
+        // The file is applied first so that the flags below take precedence over it.
+        if let Some(config_path) = matches.value_of("config") {
+            if let Err(error) = config::apply_config_file(self, Path::new(config_path)) {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        }
+
+        if let Some(jobs) = matches.value_of("jobs") {
+            self.jobs = jobs.parse().ok();
+        }
+
+        if matches.is_present("single-threaded") {
+            self.jobs = Some(1);
+        }
+
+        if matches.is_present("3d") {
+            self.mode = Mode::ThreeD;
+        } else if matches.is_present("vr") {
+            self.mode = Mode::VR;
+        }
+
+        if let Some(ui) = matches.value_of("ui") {
+            self.ui = match ui {
+                "none" => UIVisibility::None,
+                "stats" => UIVisibility::Stats,
+                _ => UIVisibility::All,
+            };
+        }
+
+        if let Some(background_color) = matches.value_of("background") {
+            self.background_color = match background_color {
+                "light" => BackgroundColor::Light,
+                "dark" => BackgroundColor::Dark,
+                _ => BackgroundColor::Transparent,
+            };
+        }
+
+        if matches.is_present("high-performance-gpu") {
+            self.high_performance_gpu = true;
+        }
+
+        if let Some(renderer_level) = matches.value_of("level") {
+            if renderer_level == "11" {
+                self.renderer_level = Some(RendererLevel::D3D11);
+            } else if renderer_level == "9" {
+                self.renderer_level = Some(RendererLevel::D3D9);
+            }
+        }
+
+        if let Some(output_frames_dir) = matches.value_of("output-frames") {
+            self.output_frames = Some(PathBuf::from(output_frames_dir));
+        }
+
+        if let Some(dpi_sweep) = matches.value_of("dpi-sweep") {
+            self.dpi_sweep = dpi_sweep.split(',')
+                                      .filter_map(|scale| scale.trim().parse().ok())
+                                      .filter(|&scale: &f32| scale > 0.0)
+                                      .collect();
+        }
+
+        if let Some(fit_padding) = matches.value_of("fit-padding") {
+            if let Ok(fit_padding) = fit_padding.parse() {
+                self.fit_padding = fit_padding;
+            }
+        }
+
+        if let Some(stats_json_path) = matches.value_of("stats-json") {
+            self.stats_json = Some(PathBuf::from(stats_json_path));
+        }
+
+        if matches.is_present("no-ground") {
+            self.show_ground = false;
+        }
+
+        if let Some(benchmark_frames) = matches.value_of("benchmark") {
+            self.benchmark_frames = benchmark_frames.parse().ok();
+        }
+
+        if let Some(mouselook_speed) = matches.value_of("mouselook-speed") {
+            if let Ok(mouselook_speed) = mouselook_speed.parse() {
+                self.mouselook_speed = mouselook_speed;
+            }
+        }
+
+        if matches.is_present("watch") {
+            self.watch = true;
+        }
+
+        if matches.is_present("strict") {
+            self.strict = true;
+        }
+
+        if let Some(warnings_json_path) = matches.value_of("warnings-json") {
+            self.warnings_json = Some(PathBuf::from(warnings_json_path));
+        }
+
+        if let Some(min_scale) = matches.value_of("min-scale") {
+            if let Ok(min_scale) = min_scale.parse() {
+                self.min_scale_2d = min_scale;
+            }
+        }
+
+        if let Some(max_scale) = matches.value_of("max-scale") {
+            if let Ok(max_scale) = max_scale.parse() {
+                self.max_scale_2d = max_scale;
+            }
+        }
+
+        if matches.is_present("orthographic") {
+            self.orthographic = true;
+        }
+
+        if let Some(transform) = matches.value_of("transform") {
+            self.initial_transform = Some(transform.to_owned());
+        }
+
+        if let Some(message_timeout) = matches.value_of("message-timeout") {
+            if let Ok(message_timeout) = message_timeout.parse::<f32>() {
+                if message_timeout >= 0.0 {
+                    let millis = (message_timeout * 1000.0) as u64;
+                    self.message_timeout = Duration::from_millis(millis);
+                }
+            }
+        }
+
+        if let Some(auto_rotate) = matches.value_of("auto-rotate") {
+            self.auto_rotate = auto_rotate.parse().ok();
+        }
+
+        if matches.is_present("print-metadata") {
+            self.print_metadata = true;
+        }
+
+        if let Some(near_clip_plane) = matches.value_of("near") {
+            self.near_clip_plane = near_clip_plane.parse().ok();
+        }
+
+        if let Some(far_clip_plane) = matches.value_of("far") {
+            self.far_clip_plane = far_clip_plane.parse().ok();
+        }
+
+        if matches.is_present("no-aa") {
+            self.no_aa = true;
+        }
+
+        if matches.is_present("screenshot-include-ui") {
+            self.screenshot_include_ui = true;
+        }
+
+        if matches.is_present("log-timing") {
+            self.log_timing = true;
+        }
+
+        if matches.is_present("letterbox") {
+            self.letterbox = true;
+        }
+
+        if let Some(dump_commands_path) = matches.value_of("dump-commands") {
+            self.dump_commands = Some(PathBuf::from(dump_commands_path));
+        }
+
+        if let Some(fit) = matches.value_of("fit") {
+            self.fit = match fit {
+                "width" => Fit::Width,
+                "height" => Fit::Height,
+                _ => Fit::Auto,
+            };
+        }
+
+        if let Some(camera_presets_path) = matches.value_of("camera-presets") {
+            self.camera_presets = PathBuf::from(camera_presets_path);
+        }
+
+        if matches.is_present("no-stem-darkening") {
+            self.no_stem_darkening = true;
+        }
+
+        if matches.is_present("no-subpixel-aa") {
+            self.no_subpixel_aa = true;
+        }
+
+        if let Some(ground_depth) = matches.value_of("ground-depth") {
+            self.ground_depth_func = match ground_depth {
+                "always" => Some(DepthFunc::Always),
+                "none" => None,
+                _ => Some(DepthFunc::Less),
+            };
+        }
+
+        if matches.is_present("infinite-ground") {
+            self.infinite_ground = true;
+        }
+
+        if matches.is_present("no-fit") {
+            self.no_fit = true;
+        }
+
+        if let Some(msaa) = matches.value_of("msaa") {
+            self.msaa = msaa.parse().ok().filter(|&samples| samples > 1);
+        }
+
+        if matches.is_present("list-fonts-used") {
+            self.list_fonts_used = true;
+        }
+
+        if let Some(frame_limit) = matches.value_of("frame-limit") {
+            self.frame_limit = frame_limit.parse().ok();
+        }
+
+        if let Some(force_mono) = matches.value_of("force-mono") {
+            match parse_hex_color(force_mono) {
+                Ok(color) => self.force_mono = Some(color),
+                Err(err) => {
+                    eprintln!("error: invalid --force-mono color: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+
+        if matches.is_present("force-multicolor") {
+            self.force_multicolor = true;
+        }
+
+        if matches.is_present("render-when-unfocused") {
+            self.render_when_unfocused = true;
+        }
+
+        if let Some(background_image) = matches.value_of("background-image") {
+            self.background_image = Some(PathBuf::from(background_image));
+        }
+
+        if let Some(background_gradient) = matches.value_of("background-gradient") {
+            let colors: Result<Vec<_>, _> =
+                background_gradient.split(',').map(|color| parse_hex_color(color.trim())).collect();
+            match colors {
+                Ok(ref colors) if colors.len() == 2 => {
+                    self.background_gradient = Some((colors[0], colors[1]));
+                }
+                Ok(_) => {
+                    eprintln!("error: --background-gradient takes two comma-separated colors");
+                    process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("error: invalid --background-gradient color: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+
+        if matches.is_present("kiosk") {
+            self.kiosk = true;
+        }
+
+        if let Some(slideshow_dir) = matches.value_of("slideshow") {
+            self.slideshow = Some(PathBuf::from(slideshow_dir));
+        }
+
+        if let Some(interval) = matches.value_of("interval") {
+            if let Ok(interval) = interval.parse::<f32>() {
+                if interval > 0.0 {
+                    self.slideshow_interval = Duration::from_millis((interval * 1000.0) as u64);
+                }
+            }
+        }
+
+        if let Some(clip_rect) = matches.value_of("clip-rect") {
+            match parse_clip_rect(clip_rect) {
+                Ok(clip_rect) => self.clip_rect = Some(clip_rect),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+
+        if let Some(export_svg_path) = matches.value_of("export-svg") {
+            self.export_svg = Some(PathBuf::from(export_svg_path));
+        }
+
+        if let Some(scale_speed) = matches.value_of("scale-speed") {
+            if let Ok(scale_speed) = scale_speed.parse::<f32>() {
+                if scale_speed > 0.0 {
+                    self.scale_speed = scale_speed;
+                }
+            }
+        }
+
+        // Zooming out scales by `1 - zoom_amount`, which must stay positive.
+        if let Some(zoom_amount) = matches.value_of("zoom-amount") {
+            if let Ok(zoom_amount) = zoom_amount.parse::<f32>() {
+                if zoom_amount > 0.0 && zoom_amount < 1.0 {
+                    self.zoom_amount = zoom_amount;
+                }
+            }
+        }
+
+        if matches.is_present("srgb") {
+            self.srgb = true;
+        }
+
+        if let Some(max_texture_size) = matches.value_of("max-texture-size") {
+            if let Ok(max_texture_size) = max_texture_size.parse::<i32>() {
+                if max_texture_size > 0 {
+                    self.max_texture_size = Some(max_texture_size);
+                }
+            }
+        }
+
+        if let Some(defringing_kernel) = matches.value_of("defringe-kernel") {
+            self.defringing_kernel = match defringing_kernel {
+                "freetype" => DefringingKernelKind::FreeType,
+                _ => DefringingKernelKind::CoreGraphics,
+            };
+        }
+
+        if let Some(screenshot_scale) = matches.value_of("screenshot-scale") {
+            if let Ok(screenshot_scale) = screenshot_scale.parse::<i32>() {
+                if screenshot_scale > 0 {
+                    self.screenshot_scale = screenshot_scale;
+                }
+            }
+        }
+
+        if let Some(antialiasing_quality) = matches.value_of("aa-quality") {
+            self.antialiasing_quality = match antialiasing_quality {
+                "low" => AntialiasingQuality::Low,
+                "medium" => AntialiasingQuality::Medium,
+                _ => AntialiasingQuality::High,
+            };
+        }
+
+        if let Some(transform_path) = matches.value_of("transform-file") {
+            self.transform_file = Some(PathBuf::from(transform_path));
+        }
+
+        if matches.is_present("quiet") {
+            self.quiet = true;
+        }
+
+        if let Some(window_size) = matches.value_of("window-size") {
+            match parse_window_size(window_size) {
+                Some(window_size) => self.window_size = Some(window_size),
+                None => {
+                    eprintln!("error: --window-size takes two positive integers, like 1280x720");
+                    process::exit(1);
+                }
+            }
+        }
+
+        if matches.is_present("snap-to-pixel") {
+            self.snap_to_pixel = true;
+        }
+
+        if let Some(dpr) = matches.value_of("dpr") {
+            match dpr.parse::<f32>() {
+                Ok(dpr) if dpr > 0.0 => self.dpr = Some(dpr),
+                _ => {
+                    eprintln!("error: --dpr takes a positive number, like 2");
+                    process::exit(1);
+                }
+            }
+        }
+
+        if let Some(backend) = matches.value_of("backend") {
+            let backend = match backend {
+                "gl3" => Backend::GL3,
+                "gl4" => Backend::GL4,
+                _ => Backend::Metal,
+            };
+            if !backend.is_available() {
+                eprintln!("error: this build can't render with {}; the `pf-gl` feature chooses \
+                           between Metal and OpenGL on macOS",
+                          backend.name());
+                process::exit(1);
+            }
+            self.backend = Some(backend);
+        }
+
+        if let Some(reprojection_filter) = matches.value_of("reprojection-filter") {
+            self.reprojection_filter = match reprojection_filter {
+                "nearest" => ReprojectionFilter::Nearest,
+                _ => ReprojectionFilter::Linear,
+            };
+        }
+
+        if matches.is_present("stats-only") {
+            self.stats_only = true;
+        }
+
+        if let Some(watermark_path) = matches.value_of("watermark") {
+            self.watermark = Some(PathBuf::from(watermark_path));
+        }
+
+        if let Some(watermark_opacity) = matches.value_of("watermark-opacity") {
+            match watermark_opacity.parse::<f32>() {
+                Ok(opacity) if opacity >= 0.0 && opacity <= 1.0 => {
+                    self.watermark_opacity = opacity
+                }
+                _ => {
+                    eprintln!("error: --watermark-opacity takes a number from 0 to 1, like 0.5");
+                    process::exit(1);
+                }
+            }
+        }
+
+        if let Some(watermark_corner) = matches.value_of("watermark-corner") {
+            self.watermark_corner = match watermark_corner {
+                "top-left" => Corner::TopLeft,
+                "top-right" => Corner::TopRight,
+                "bottom-left" => Corner::BottomLeft,
+                _ => Corner::BottomRight,
+            };
+        }
+
+        if let Some(antialias_gamma) = matches.value_of("antialias-gamma") {
+            match antialias_gamma.parse::<f32>() {
+                Ok(gamma) if gamma > 0.0 => self.antialias_gamma = gamma,
+                _ => {
+                    eprintln!("error: --antialias-gamma takes a positive number, like 2.2");
+                    process::exit(1);
+                }
+            }
+        }
+
+        if let Some(capture_frame) = matches.value_of("capture-frame") {
+            match capture_frame.parse() {
+                Ok(capture_frame) => self.capture_frame = Some(capture_frame),
+                Err(_) => {
+                    eprintln!("error: --capture-frame takes a frame number");
+                    process::exit(1);
+                }
+            }
+            // Auto-rotation only turns the 3D camera, so every frame would look the same in 2D.
+            if self.mode == Mode::TwoD {
+                eprintln!("error: --capture-frame needs --3d or --vr");
+                process::exit(1);
+            }
+        }
+
+        if let Some(max_fps) = matches.value_of("max-fps") {
+            match max_fps.parse::<f32>() {
+                Ok(max_fps) if max_fps > 0.0 => self.max_fps = Some(max_fps),
+                _ => {
+                    eprintln!("error: --max-fps takes a positive number, like 30");
+                    process::exit(1);
+                }
+            }
+        }
+        if let Some(path) = matches.value_of("INPUT") {
+            self.input_path = if path == "-" {
+                DataPath::Stdin
+            } else {
+                DataPath::Path(PathBuf::from(path))
+            };
+        };
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum UIVisibility {
     None,
     Stats,
     All,
 }
 
+// How the 2D camera initially frames the scene.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Fit {
+    // Fit whichever dimension of the scene leaves it entirely visible.
+    Auto,
+    Width,
+    Height,
+}
+
+// A graphics API and version for the demo to render with.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Backend {
+    // OpenGL 3.3, core profile. Scenes are rendered with the D3D9-level renderer.
+    GL3,
+    // OpenGL 4.3, core profile, which the D3D11-level renderer's compute shaders need.
+    GL4,
+    Metal,
+}
+
+impl Backend {
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::GL3 => "OpenGL 3",
+            Backend::GL4 => "OpenGL 4",
+            Backend::Metal => "Metal",
+        }
+    }
+
+    // Whether the device type that this build was compiled with can use this backend.
+    pub fn is_available(self) -> bool {
+        let metal = cfg!(all(target_os = "macos", not(feature = "pf-gl")));
+        (self == Backend::Metal) == metal
+    }
+
+    // The GL version to create the device with, for the OpenGL backends.
+    pub fn gl_version(self) -> Option<GLVersion> {
+        match self {
+            Backend::GL3 => Some(GLVersion::GL3),
+            Backend::GL4 => Some(GLVersion::GL4),
+            Backend::Metal => None,
+        }
+    }
+}
+
+// How the scene texture is sampled when it's reprojected for each eye in VR.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ReprojectionFilter {
+    Linear,
+    Nearest,
+}
+
+// A corner of the window, for things drawn over the scene.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+// Which platform's subpixel text rendering the defringing filter imitates.
+#[derive(Clone, Copy, PartialEq)]
+pub enum DefringingKernelKind {
+    CoreGraphics = 0,
+    FreeType = 1,
+}
+
+// A color vision deficiency to simulate, to check whether the scene's colors stay distinguishable.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorBlindness {
+    None,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl ColorBlindness {
+    fn next(self) -> ColorBlindness {
+        match self {
+            ColorBlindness::None => ColorBlindness::Protanopia,
+            ColorBlindness::Protanopia => ColorBlindness::Deuteranopia,
+            ColorBlindness::Deuteranopia => ColorBlindness::Tritanopia,
+            ColorBlindness::Tritanopia => ColorBlindness::None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorBlindness::None => "none",
+            ColorBlindness::Protanopia => "protanopia",
+            ColorBlindness::Deuteranopia => "deuteranopia",
+            ColorBlindness::Tritanopia => "tritanopia",
+        }
+    }
+
+    // The full-severity simulation matrices from Machado et al., "A Physiologically-based Model
+    // for Simulation of Color Vision Deficiency" 2009. They're meant for linear RGB, so applying
+    // them to the sRGB values in the window, as the demo does, is an approximation. Each row sums
+    // to 1, which keeps grays gray and makes them safe to apply to premultiplied colors.
+    fn color_matrix(self) -> Option<ColorMatrix> {
+        let rows = match self {
+            ColorBlindness::None => return None,
+            ColorBlindness::Protanopia => [
+                [ 0.152286,  1.052583, -0.204868],
+                [ 0.114503,  0.786281,  0.099216],
+                [-0.003882, -0.048116,  1.051998],
+            ],
+            ColorBlindness::Deuteranopia => [
+                [ 0.367322,  0.860646, -0.227968],
+                [ 0.280085,  0.672501,  0.047413],
+                [-0.011820,  0.042940,  0.968881],
+            ],
+            ColorBlindness::Tritanopia => [
+                [ 1.255528, -0.076749, -0.178779],
+                [-0.078411,  0.930809,  0.147602],
+                [ 0.004733,  0.691367,  0.303900],
+            ],
+        };
+        Some(ColorMatrix::from_rows([
+            [rows[0][0], rows[0][1], rows[0][2], 0.0, 0.0],
+            [rows[1][0], rows[1][1], rows[1][2], 0.0, 0.0],
+            [rows[2][0], rows[2][1], rows[2][2], 0.0, 0.0],
+            [0.0,        0.0,        0.0,        1.0, 0.0],
+        ]))
+    }
+}
+
 impl Content {
-    fn render(&mut self, viewport_size: Vector2I, filter: Option<PatternFilter>) -> (Scene, String) {
+    // Returns the scene along with any unsupported features that were encountered building it.
+    fn render(&mut self, viewport_size: Vector2I, filters: Vec<PatternFilter>)
+              -> (Scene, BuildResultFlags) {
         match *self {
             Content::Svg(ref tree) => {
-                let built_svg = build_svg_tree(&tree, viewport_size, filter);
-                let message = get_svg_building_message(&built_svg);
-                (built_svg.scene, message)
+                let built_svg = build_svg_tree(&tree, viewport_size, filters);
+                (built_svg.scene, built_svg.result_flags)
             }
             Content::Pdf { ref file, ref mut cache, page_nr } => {
                 let page = file.get_page(page_nr).expect("no such page");
                 let (scene, _) = cache.render_page(file, &page, Transform2F::default()).unwrap();
-                (scene, String::new())
+                (scene, BuildResultFlags::empty())
             }
+            Content::Fallback => (build_fallback_scene(), BuildResultFlags::empty()),
+            Content::Scene(ref scene) => (scene.clone(), BuildResultFlags::empty()),
+        }
+    }
+}
+
+// Loads the input scene without creating a window and prints its metadata, one property per line.
+// The metadata is computed exactly as it is for rendering at the given viewport size.
+pub fn print_scene_metadata(resource_loader: &dyn ResourceLoader,
+                            options: &Options,
+                            viewport_size: Vector2I) {
+    let mut content = match load_scene(resource_loader, &options.input_path, options) {
+        Ok(content) => content,
+        Err(load_error) => {
+            eprintln!("error: {}", load_error);
+            process::exit(1);
+        }
+    };
+
+    let filters = build_filters(&DemoUIModel::new(options));
+    let (mut scene, _) = content.render(viewport_size, filters);
+    let scene_metadata = SceneMetadata::new_clipping_view_box(&mut scene, viewport_size, options);
+
+    let rects = [("view_box", scene_metadata.view_box), ("bounds", scene_metadata.bounds)];
+    for &(name, rect) in &rects {
+        println!("{} {} {} {} {}",
+                 name,
+                 rect.origin_x(),
+                 rect.origin_y(),
+                 rect.width(),
+                 rect.height());
+    }
+    match scene_metadata.monochrome_color {
+        None => println!("monochrome_color none"),
+        Some(color) => {
+            println!("monochrome_color {} {} {} {}", color.r, color.g, color.b, color.a)
+        }
+    }
+}
+
+// Returns the options to build a scene with: everything but the transform comes from the UI's
+// effect settings and the command line. The window builds its scene through the scene proxy and
+// `--stats-only` builds one with `Scene::build_commands()`, but both take their options from here.
+fn scene_build_options(transform: RenderTransform,
+                       ui_model: &DemoUIModel,
+                       options: &Options,
+                       backing_scale_factor: f32)
+                       -> BuildOptions {
+    BuildOptions {
+        transform,
+        dilation: if ui_model.stem_darkening_effect_enabled {
+            let font_size = APPROX_FONT_SIZE * backing_scale_factor;
+            vec2f(STEM_DARKENING_FACTORS[0], STEM_DARKENING_FACTORS[1]) * font_size
+        } else {
+            Vector2F::zero()
+        },
+        subpixel_aa_enabled: ui_model.subpixel_aa_effect_enabled,
+        antialiasing_disabled: options.no_aa,
+        antialiasing_quality: options.antialiasing_quality,
+    }
+}
+
+// Prints measures of how costly the scene is to render, one `key=value` pair per line. Tiles are
+// counted at the scene's own size; see `Scene::approximate_tile_count()`. The scene is also built,
+// without a GPU, at its own size to count the render commands that it produces.
+pub fn print_scene_complexity(resource_loader: &dyn ResourceLoader,
+                              options: &Options,
+                              viewport_size: Vector2I) {
+    let mut content = match load_scene(resource_loader, &options.input_path, options) {
+        Ok(content) => content,
+        Err(load_error) => {
+            eprintln!("error: {}", load_error);
+            process::exit(1);
+        }
+    };
+
+    let ui_model = DemoUIModel::new(options);
+    let filters = build_filters(&ui_model);
+    let (mut scene, _) = content.render(viewport_size, filters);
+
+    println!("paths={}", scene.draw_path_count());
+    println!("segments={}", scene.segment_count());
+    println!("tiles={}", scene.approximate_tile_count());
+
+    let level = options.renderer_level.unwrap_or(RendererLevel::D3D9);
+    let build_options = scene_build_options(RenderTransform::default(), &ui_model, options, 1.0);
+    let commands = scene.build_commands(level, build_options, DemoExecutor::new(options.jobs));
+    println!("commands={}", commands.len());
+}
+
+// Writes the input back out as SVG, one `<path>` per filled path, with transforms applied and
+// references resolved. Gradients, patterns, and clipping are lost; paths take the base color of
+// their paint.
+pub fn export_scene_svg(resource_loader: &dyn ResourceLoader,
+                        options: &Options,
+                        export_path: &Path,
+                        viewport_size: Vector2I) {
+    let mut content = match load_scene(resource_loader, &options.input_path, options) {
+        Ok(content) => content,
+        Err(load_error) => {
+            eprintln!("error: {}", load_error);
+            process::exit(1);
         }
+    };
+
+    let filters = build_filters(&DemoUIModel::new(options));
+    let (scene, _) = content.render(viewport_size, filters);
+
+    let result = File::create(export_path).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        scene.export(&mut writer, FileFormat::SVG)?;
+        writer.flush()
+    });
+    if let Err(error) = result {
+        eprintln!("error: couldn't write \"{}\": {}", export_path.display(), error);
+        process::exit(1);
     }
 }
 
-fn load_scene(resource_loader: &dyn ResourceLoader,
-              input_path: &DataPath,)
-              -> Content {
+// On failure, returns a message suitable for display to the user.
+fn load_scene(resource_loader: &dyn ResourceLoader, input_path: &DataPath, options: &Options)
+              -> Result<Content, String> {
     let data = match *input_path {
-        DataPath::Default => resource_loader.slurp(DEFAULT_SVG_VIRTUAL_PATH).unwrap(),
-        DataPath::Resource(ref name) => resource_loader.slurp(name).unwrap(),
-        DataPath::Path(ref path) => std::fs::read(path).unwrap().into()
+        DataPath::Default => {
+            resource_loader.slurp(DEFAULT_SVG_VIRTUAL_PATH).map_err(|error| {
+                format!("Default asset \"{}\" is missing: {}.", DEFAULT_SVG_VIRTUAL_PATH, error)
+            })?
+        }
+        DataPath::Resource(ref name) => resource_loader.slurp(name).map_err(|error| {
+            format!("Couldn't load resource \"{}\": {}.", name, error)
+        })?,
+        DataPath::Path(ref path) => std::fs::read(path).map_err(|error| {
+            format!("Couldn't read \"{}\": {}.", path.display(), error)
+        })?,
+        DataPath::Stdin => {
+            let mut data = vec![];
+            io::stdin().read_to_end(&mut data).map_err(|error| {
+                format!("Couldn't read standard input: {}.", error)
+            })?;
+            if data.is_empty() {
+                return Err("Standard input was empty.".to_owned());
+            }
+            data
+        }
     };
+    let data = decompress_if_gzipped(data, input_path)?;
 
     if let Ok(tree) = SvgTree::from_data(&data, &UsvgOptions::default()) {
-        Content::Svg(tree)
+        if options.list_fonts_used {
+            fonts::print_fonts_used(&data);
+        }
+        Ok(Content::Svg(tree))
     } else if let Ok(file) = PdfFile::from_data(data) {
-        Content::Pdf { file, cache: PdfRenderCache::new(), page_nr: 0 }
+        Ok(Content::Pdf { file, cache: PdfRenderCache::new(), page_nr: 0 })
     } else {
-        panic!("can't load data");
+        Err(format!("Couldn't parse \"{}\" as SVG or PDF.", data_path_display_name(input_path)))
+    }
+}
+
+// Loads the SVG to draw over the scene with `--watermark`. Its opacity is applied to its colors
+// up front, so that it can be composited like an opaque scene; image patterns stay opaque. Since
+// each color is made translucent by itself, overlapping shapes in the watermark show through each
+// other, as they would with `fill-opacity` rather than `opacity` in SVG.
+fn load_watermark(resource_loader: &dyn ResourceLoader,
+                  path: &Path,
+                  viewport_size: Vector2I,
+                  options: &Options,
+                  level: RendererLevel)
+                  -> Result<Watermark, String> {
+    let mut content = load_scene(resource_loader, &DataPath::Path(path.to_owned()), options)?;
+    let (mut scene, _) = content.render(viewport_size, vec![]);
+    let opacity = options.watermark_opacity;
+    if opacity < 1.0 {
+        scene.map_colors(|color| ColorU { a: (color.a as f32 * opacity).round() as u8, ..color });
+    }
+    let view_box = scene.view_box();
+    let scene_proxy = SceneProxy::from_scene(scene, level, DemoExecutor::new(Some(1)));
+    Ok(Watermark::new(scene_proxy, view_box))
+}
+
+// Decompresses SVGZ files and other gzip-compressed data. Anything else is returned as is.
+#[cfg(feature = "svgz")]
+fn decompress_if_gzipped(data: Vec<u8>, input_path: &DataPath) -> Result<Vec<u8>, String> {
+    if !data.starts_with(&GZIP_MAGIC) {
+        return Ok(data);
     }
+    let mut decompressed = vec![];
+    GzDecoder::new(&data[..]).read_to_end(&mut decompressed).map_err(|error| {
+        format!("Couldn't decompress \"{}\": {}.", data_path_display_name(input_path), error)
+    })?;
+    Ok(decompressed)
+}
+
+// Without the `svgz` feature, the data is passed through as is. `usvg` inflates SVGZ files by
+// itself, so they still open; only the clearer error for corrupt files is lost.
+#[cfg(not(feature = "svgz"))]
+fn decompress_if_gzipped(data: Vec<u8>, _: &DataPath) -> Result<Vec<u8>, String> {
+    Ok(data)
+}
+
+// Builds a simple placeholder scene: a colored rectangle with a margin around it.
+fn build_fallback_scene() -> Scene {
+    let mut scene = Scene::new();
+    let view_box = RectF::new(Vector2F::zero(), vec2f(400.0, 300.0));
+    scene.set_view_box(view_box);
+
+    let paint_id = scene.push_paint(&Paint::from_color(FALLBACK_SCENE_COLOR));
+    let outline = Outline::from_rect(RectF::new(vec2f(40.0, 40.0), vec2f(320.0, 220.0)));
+    scene.push_draw_path(DrawPath::new(outline, paint_id));
+    scene
 }
 
 // FIXME(pcwalton): Rework how transforms work in the demo. The transform affects the final
 // composite steps, breaking this approach.
-fn build_svg_tree(tree: &SvgTree, viewport_size: Vector2I, filter: Option<PatternFilter>) -> SVGScene {
+fn build_svg_tree(tree: &SvgTree, viewport_size: Vector2I, filters: Vec<PatternFilter>)
+                  -> SVGScene {
     let mut scene = Scene::new();
-    let filter_info = filter.map(|filter| {
-        let scale = match filter {
-            PatternFilter::Text { defringing_kernel: Some(_), .. } => vec2i(3, 1),
-            _ => vec2i(1, 1),
+
+    // Filters are applied in order, so the first filter gets the innermost render target.
+    let filter_infos: Vec<FilterInfo> = filters.into_iter().rev().map(|filter| {
+        let (scale, name) = match filter {
+            PatternFilter::Text { defringing_kernel: Some(_), .. } => (vec2i(3, 1), "Text"),
+            PatternFilter::Text { .. } => (vec2i(1, 1), "Text"),
+            _ => (vec2i(1, 1), "Effect"),
         };
-        let name = "Text".to_owned();
         let render_target_size = viewport_size * scale;
-        let render_target = RenderTarget::new(render_target_size, name);
+        let render_target = RenderTarget::new(render_target_size, name.to_owned());
         let render_target_id = scene.push_render_target(render_target);
         FilterInfo { filter, render_target_id, render_target_size }
-    });
+    }).collect();
 
     let mut built_svg = SVGScene::from_tree_and_scene(&tree, scene);
-    if let Some(FilterInfo { filter, render_target_id, render_target_size }) = filter_info {
+    for filter_info in filter_infos.into_iter().rev() {
+        let FilterInfo { filter, render_target_id, render_target_size } = filter_info;
         let mut pattern = Pattern::from_render_target(render_target_id, render_target_size);
         pattern.set_filter(Some(filter));
         let paint_id = built_svg.scene.push_paint(&Paint::from_pattern(pattern));
@@ -836,28 +3685,272 @@ fn build_svg_tree(tree: &SvgTree, viewport_size: Vector2I, filter: Option<Patter
     }
 }
 
+// Returns a short name for the given data path, truncated to its last two components.
+fn data_path_display_name(data_path: &DataPath) -> String {
+    let path = match *data_path {
+        DataPath::Default => Path::new(DEFAULT_SVG_VIRTUAL_PATH),
+        DataPath::Resource(ref name) => Path::new(name),
+        DataPath::Path(ref path) => path.as_path(),
+        DataPath::Stdin => return "<stdin>".to_owned(),
+    };
+    let components: Vec<_> = path.components().collect();
+    let first_component = components.len().saturating_sub(2);
+    components[first_component..].iter().collect::<PathBuf>().display().to_string()
+}
+
+// Returns the size of the viewport that the scene is rendered into. In VR mode, the scene is
+// rendered once to a framebuffer large enough for every eye and then reprojected per eye.
+fn scene_viewport_size<W>(window: &W, mode: Mode) -> Vector2I where W: Window {
+    (0..mode.viewport_count() as u32).map(|index| window.viewport(mode.view(index)).size())
+                                     .fold(Vector2I::zero(), |max_size, size| max_size.max(size))
+}
+
+// Scales a render transform about the origin, so that the scene fills a framebuffer `scale` times
+// the size of the viewport.
+pub(crate) fn scale_render_transform(transform: RenderTransform, scale: f32) -> RenderTransform {
+    match transform {
+        RenderTransform::Transform2D(transform) => {
+            RenderTransform::Transform2D(Transform2F::from_scale(scale) * transform)
+        }
+        RenderTransform::Perspective(perspective) => {
+            let window_size = (perspective.window_size.to_f32() * scale).to_i32();
+            RenderTransform::Perspective(Perspective::new(&perspective.transform, window_size))
+        }
+    }
+}
+
+// Rescales the transform about `pivot` as necessary to keep its scale factor within the bounds
+// given in the options.
+fn clamp_scale_2d(transform: Transform2F, pivot: Vector2F, options: &Options) -> Transform2F {
+    let scale = transform.scale_factor();
+    let clamped_scale = f32::max(f32::min(scale, options.max_scale_2d), options.min_scale_2d);
+    if scale == clamped_scale || scale == 0.0 {
+        return transform;
+    }
+    transform.translate(-pivot).scale(clamped_scale / scale).translate(pivot)
+}
+
+// Parses a transform of the form `scale,tx,ty,rotation`, where the rotation is in degrees. The
+// scene is scaled, then rotated about the origin, then translated.
+fn parse_transform_2d(string: &str) -> Result<Transform2F, String> {
+    let error = || {
+        format!("Invalid transform \"{}\"; expected \"scale,tx,ty,rotation\".", string)
+    };
+    let values: Vec<f32> = string.split(',')
+                                 .map(|value| value.trim().parse())
+                                 .collect::<Result<_, _>>()
+                                 .map_err(|_| error())?;
+    match values[..] {
+        [scale, tx, ty, rotation] => {
+            Ok(Transform2F::from_scale(scale).rotate(rotation.to_radians())
+                                             .translate(vec2f(tx, ty)))
+        }
+        _ => Err(error()),
+    }
+}
+
+// Reads a matrix from a file and makes the camera render with it. The file holds numbers separated
+// by whitespace or commas: either the six of a 2D affine transform, in the order of SVG's
+// `matrix(a b c d e f)`, or the sixteen of a 3D transform to normalized device coordinates, in
+// row-major order.
+fn apply_transform_file(camera: &mut Camera, path: &Path) -> Result<(), String> {
+    let source = std::fs::read_to_string(path).map_err(|error| {
+        format!("Couldn't read \"{}\": {}.", path.display(), error)
+    })?;
+    let values: Vec<f32> = source.split(|c: char| c.is_whitespace() || c == ',')
+                                 .filter(|value| !value.is_empty())
+                                 .map(|value| {
+                                     value.parse().map_err(|_| {
+                                         format!("Invalid number \"{}\" in \"{}\".",
+                                                 value,
+                                                 path.display())
+                                     })
+                                 })
+                                 .collect::<Result<_, _>>()?;
+
+    let mode = camera.mode();
+    match (&values[..], camera) {
+        (&[a, b, c, d, e, f], &mut Camera::TwoD(ref mut transform)) => {
+            *transform = Transform2F::row_major(a, c, e, b, d, f);
+            Ok(())
+        }
+        (&[_, _, _, _, _, _], _) => Err("A 3x2 transform can only be used in 2D mode.".to_owned()),
+        (&[m00, m01, m02, m03, m10, m11, m12, m13, m20, m21, m22, m23, m30, m31, m32, m33],
+         camera) if mode == Mode::ThreeD => {
+            camera.set_transform_3d(Transform4F::row_major(m00, m01, m02, m03,
+                                                           m10, m11, m12, m13,
+                                                           m20, m21, m22, m23,
+                                                           m30, m31, m32, m33));
+            Ok(())
+        }
+        (values, _) if values.len() == 16 => {
+            Err("A 4x4 transform can only be used in 3D mode.".to_owned())
+        }
+        (values, _) => {
+            Err(format!("\"{}\" holds {} numbers; expected 6 for a 3x2 transform or 16 for a 4x4 \
+                         one.",
+                        path.display(),
+                        values.len()))
+        }
+    }
+}
+
+// Parses a rectangle of the form `x,y,width,height` with a nonzero area.
+fn parse_clip_rect(string: &str) -> Result<RectF, String> {
+    let error = || {
+        format!("Invalid clip rect \"{}\"; expected \"x,y,width,height\".", string)
+    };
+    let values: Vec<f32> = string.split(',')
+                                 .map(|value| value.trim().parse())
+                                 .collect::<Result<_, _>>()
+                                 .map_err(|_| error())?;
+    match values[..] {
+        [x, y, width, height] if width > 0.0 && height > 0.0 => {
+            Ok(RectF::new(vec2f(x, y), vec2f(width, height)))
+        }
+        _ => Err(error()),
+    }
+}
+
+// Parses a color written as `#rrggbb` or `#rrggbbaa`.
+fn parse_hex_color(string: &str) -> Result<ColorU, String> {
+    let digits = if string.starts_with('#') { &string[1..] } else { string };
+    if (digits.len() != 6 && digits.len() != 8) || !digits.is_ascii() {
+        return Err(format!("expected #rrggbb or #rrggbbaa, found `{}`", string));
+    }
+
+    let mut channels = [255; 4];
+    for (channel, index) in channels.iter_mut().zip((0..digits.len()).step_by(2)) {
+        *channel = u8::from_str_radix(&digits[index..(index + 2)], 16)
+            .map_err(|_| format!("`{}` is not a hexadecimal color", string))?;
+    }
+    Ok(ColorU { r: channels[0], g: channels[1], b: channels[2], a: channels[3] })
+}
+
+// Returns true if the event moves, turns, or zooms the camera. These are ignored in kiosk mode.
+fn is_camera_event(event: &Event) -> bool {
+    match *event {
+        Event::MouseMoved(_) | Event::Zoom(..) | Event::Look { .. } => true,
+        Event::KeyDown(keycode) | Event::KeyUp(keycode)
+                if arrow_key_direction(keycode).is_some() => true,
+        Event::KeyDown(Keycode::Alphanumeric(key)) | Event::KeyUp(Keycode::Alphanumeric(key)) => {
+            match key {
+                b'w' | b'a' | b's' | b'd' | b'q' | b'e' | b'z' | b'c' | b'i' | b'=' | b'+' |
+                b'-' | b'0'..=b'9' => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+// Emulates a display with the given backing scale factor. The device size is kept the same so
+// that the rendered output still fits in the window; for `--dpr`, the native demo has already
+// sized the window to have the device size the emulated display would give it.
+fn emulate_backing_scale_factor(window_size: WindowSize, backing_scale_factor: f32)
+                                -> WindowSize {
+    let device_size = window_size.device_size();
+    WindowSize {
+        logical_size: (device_size.to_f32() * (1.0 / backing_scale_factor)).to_i32(),
+        backing_scale_factor,
+    }
+}
+
 fn center_of_window(window_size: &WindowSize) -> Vector2F {
     window_size.device_size().to_f32() * 0.5
 }
 
-fn get_svg_building_message(built_svg: &SVGScene) -> String {
-    if built_svg.result_flags.is_empty() {
+// Returns the direction, in device space, that an arrow key pans the scene, or `None` if the key
+// isn't an arrow key. The arrow points the way the scene moves.
+fn arrow_key_direction(keycode: Keycode) -> Option<Vector2F> {
+    match keycode {
+        Keycode::Left => Some(vec2f(-1.0, 0.0)),
+        Keycode::Right => Some(vec2f(1.0, 0.0)),
+        Keycode::Up => Some(vec2f(0.0, -1.0)),
+        Keycode::Down => Some(vec2f(0.0, 1.0)),
+        _ => None,
+    }
+}
+
+fn get_svg_building_message(result_flags: BuildResultFlags) -> String {
+    if result_flags.is_empty() {
         return String::new();
     }
     format!(
         "Warning: These features in the SVG are unsupported: {}.",
-        built_svg.result_flags
+        result_flags
     )
 }
 
-fn emit_message<W>(
+fn write_warnings_json(path: &Path, result_flags: BuildResultFlags) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let feature_names: Vec<String> = result_flags.feature_names()
+                                                 .into_iter()
+                                                 .map(|name| format!("\"{}\"", name))
+                                                 .collect();
+    writeln!(writer, "{{ \"unsupported_features\": [{}] }}", feature_names.join(", "))
+}
+
+// Returns the SVG and SVGZ files in the given directory, sorted by name.
+fn list_svg_files(dir: &Path) -> io::Result<Vec<DataPath>> {
+    let mut paths = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_svg = path.extension().map_or(false, |extension| {
+            let extension = extension.to_string_lossy();
+            extension.eq_ignore_ascii_case("svg") || extension.eq_ignore_ascii_case("svgz")
+        });
+        if is_svg && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths.into_iter().map(DataPath::Path).collect())
+}
+
+// Returns the amount that the animation clock should advance each frame, if it shouldn't follow
+// real time. When writing frames out at a capped rate, each one is exactly one frame's time
+// after the last, however long it took to draw, so the frames play back at that rate.
+fn animation_frame_duration(options: &Options) -> Option<Duration> {
+    match (options.max_fps, &options.output_frames) {
+        (Some(max_fps), Some(_)) => Some(Duration::from_secs_f32(1.0 / max_fps)),
+        _ => None,
+    }
+}
+
+// Advances the slideshow every `interval` for as long as the app runs.
+fn start_slideshow_timer<W>(slideshow_event_id: u32, interval: Duration) where W: Window {
+    thread::spawn(move || {
+        loop {
+            thread::sleep(interval);
+            W::push_user_event(slideshow_event_id, 0);
+        }
+    });
+}
+
+// Parses a size of the form `WxH`, where both dimensions are positive.
+fn parse_window_size(string: &str) -> Option<Vector2I> {
+    let mut dimensions = string.splitn(2, 'x').map(|dimension| dimension.trim().parse::<i32>());
+    match (dimensions.next(), dimensions.next()) {
+        (Some(Ok(width)), Some(Ok(height))) if width > 0 && height > 0 => {
+            Some(vec2i(width, height))
+        }
+        _ => None,
+    }
+}
+
+fn wireframe_outlines(scene: &Scene) -> Vec<Outline> {
+    (0..scene.draw_path_count()).map(|draw_path_index| {
+        scene.get_draw_path(DrawPathId(draw_path_index)).outline.clone()
+    }).collect()
+}
+
+fn emit_message(
     ui_model: &mut DemoUIModel,
     message_epoch: &mut u32,
-    expire_message_event_id: u32,
+    message_timer: &dyn MessageTimer,
     message: String,
-) where
-    W: Window,
-{
+) {
     if message.is_empty() {
         return;
     }
@@ -865,10 +3958,69 @@ fn emit_message<W>(
     ui_model.message = message;
     let expected_epoch = *message_epoch + 1;
     *message_epoch = expected_epoch;
-    thread::spawn(move || {
-        thread::sleep(Duration::from_secs(MESSAGE_TIMEOUT_SECS));
-        W::push_user_event(expire_message_event_id, expected_epoch);
-    });
+    message_timer.schedule(expected_epoch);
+}
+
+// The clock that the camera animations run on. It can be paused and, while paused, stepped
+// forward by whole frames. It can also be made to ignore real time and advance by a fixed amount
+// each frame.
+struct AnimationClock {
+    // How far this clock is behind real time because of the time it spent paused.
+    lag: Duration,
+    // The time on this clock at which it was paused, if it's paused.
+    paused_time: Option<Instant>,
+    // The amount that the clock advances each frame, if it doesn't follow real time.
+    frame_duration: Option<Duration>,
+    // The time on this clock for the current frame, if it advances by `frame_duration`.
+    frame_time: Instant,
+}
+
+impl AnimationClock {
+    fn new(frame_duration: Option<Duration>) -> AnimationClock {
+        AnimationClock {
+            lag: Duration::default(),
+            paused_time: None,
+            frame_duration,
+            frame_time: Instant::now(),
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.paused_time.unwrap_or_else(|| {
+            match self.frame_duration {
+                None => Instant::now() - self.lag,
+                Some(_) => self.frame_time,
+            }
+        })
+    }
+
+    // Moves a clock with a fixed frame duration on to the next frame.
+    fn next_frame(&mut self) {
+        if let (Some(frame_duration), None) = (self.frame_duration, self.paused_time) {
+            self.frame_time += frame_duration;
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused_time.is_some()
+    }
+
+    fn pause(&mut self) {
+        self.paused_time = Some(self.now());
+    }
+
+    fn resume(&mut self) {
+        if let Some(paused_time) = self.paused_time.take() {
+            self.lag = Instant::now() - paused_time;
+            self.frame_time = paused_time;
+        }
+    }
+
+    fn step(&mut self, duration: Duration) {
+        if let Some(ref mut paused_time) = self.paused_time {
+            *paused_time += duration;
+        }
+    }
 }
 
 struct Frame {
@@ -899,34 +4051,99 @@ impl BackgroundColor {
     }
 }
 
+#[derive(Clone, Copy)]
+struct RubberBand {
+    // Where the drag started and where the mouse is now, in device pixels.
+    start: Vector2F,
+    current: Vector2F,
+}
+
+impl RubberBand {
+    fn rect(&self) -> RectF {
+        RectF::from_points(self.start.min(self.current), self.start.max(self.current))
+    }
+}
+
+struct DpiSweep {
+    // The index into `Options::dpi_sweep` of the scale factor currently being rendered.
+    index: usize,
+    original_window_size: WindowSize,
+}
+
 struct SceneMetadata {
     view_box: RectF,
+    bounds: RectF,
+    // The color of every path in the scene, if they are all the same solid color.
+    monochrome_color: Option<ColorU>,
+    // `monochrome_color` as written in the file, or as forced, before any conversion to linear
+    // light, for the color legend.
+    legend_color: Option<ColorU>,
 }
 
 impl SceneMetadata {
     // FIXME(pcwalton): The fact that this mutates the scene is really ugly!
     // Can we simplify this?
-    fn new_clipping_view_box(scene: &mut Scene, viewport_size: Vector2I) -> SceneMetadata {
-        let view_box = scene.view_box();
+    fn new_clipping_view_box(scene: &mut Scene, viewport_size: Vector2I, options: &Options)
+                             -> SceneMetadata {
+        if let Some(color) = options.force_mono {
+            scene.replace_paints(&Paint::from_color(color));
+        }
+
+        let legend_color = if options.force_multicolor {
+            None
+        } else {
+            scene.monochrome_color()
+        };
+        if options.srgb {
+            scene.map_colors(|color| color.to_f32().srgb_to_linear().to_u8());
+        }
+
+        let view_box = options.clip_rect.unwrap_or_else(|| scene.view_box());
+        let bounds = scene.bounds();
+        let monochrome_color = if options.force_multicolor {
+            None
+        } else {
+            scene.monochrome_color()
+        };
         scene.set_view_box(RectF::new(Vector2F::zero(), viewport_size.to_f32()));
-        SceneMetadata { view_box }
+        SceneMetadata { view_box, bounds, monochrome_color, legend_color }
     }
 }
 
-fn build_filter(ui_model: &DemoUIModel) -> Option<PatternFilter> {
-    if !ui_model.gamma_correction_effect_enabled && !ui_model.subpixel_aa_effect_enabled {
-        return None;
+// Returns the filters to apply to the scene, in the order in which they should be applied.
+fn build_filters(ui_model: &DemoUIModel) -> Vec<PatternFilter> {
+    let mut filters = vec![];
+
+    if ui_model.gamma_correction_effect_enabled || ui_model.subpixel_aa_effect_enabled {
+        filters.push(PatternFilter::Text {
+            fg_color: ui_model.foreground_color().to_f32(),
+            bg_color: ui_model.background_color().to_f32(),
+            gamma_correction: ui_model.gamma_correction_effect_enabled,
+            defringing_kernel: if ui_model.subpixel_aa_effect_enabled {
+                match ui_model.defringing_kernel {
+                    DefringingKernelKind::CoreGraphics => Some(DEFRINGING_KERNEL_CORE_GRAPHICS),
+                    DefringingKernelKind::FreeType => Some(DEFRINGING_KERNEL_FREETYPE),
+                }
+            } else {
+                None
+            }
+        });
+    }
+
+    if ui_model.grayscale_effect_enabled {
+        // Replace each color with its luminance.
+        filters.push(PatternFilter::ColorMatrix(ColorMatrix::saturate(0.0)));
     }
 
-    Some(PatternFilter::Text {
-        fg_color: ui_model.foreground_color().to_f32(),
-        bg_color: ui_model.background_color().to_f32(),
-        gamma_correction: ui_model.gamma_correction_effect_enabled,
-        defringing_kernel: if ui_model.subpixel_aa_effect_enabled {
-            // TODO(pcwalton): Select FreeType defringing kernel as necessary.
-            Some(DEFRINGING_KERNEL_CORE_GRAPHICS)
-        } else {
-            None
-        }
-    })
+    if ui_model.invert_colors_effect_enabled {
+        // Colors are premultiplied, so invert each channel relative to alpha.
+        filters.push(PatternFilter::ColorMatrix(ColorMatrix::from_rows([
+            [-1.0,  0.0,  0.0, 1.0, 0.0],
+            [ 0.0, -1.0,  0.0, 1.0, 0.0],
+            [ 0.0,  0.0, -1.0, 1.0, 0.0],
+            [ 0.0,  0.0,  0.0, 1.0, 0.0],
+        ])));
+    }
+
+    filters
 }
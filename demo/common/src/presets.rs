@@ -0,0 +1,109 @@
+// pathfinder/demo/common/src/presets.rs
+//
+// Copyright © 2020 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Numbered 3D camera positions that persist across sessions.
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use toml::Value;
+use toml::value::Table;
+
+pub const CAMERA_PRESET_SLOT_COUNT: usize = 10;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CameraPreset {
+    // In SVG coordinates.
+    pub position: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+}
+
+// The file looks like:
+//
+//     [slot1]
+//     position = [450.0, -450.0, 1200.0]
+//     yaw = 0.5
+//     pitch = -0.25
+//     roll = 0.1
+//
+// `roll` may be left out, for presets saved before the camera could roll.
+pub struct CameraPresets {
+    slots: [Option<CameraPreset>; CAMERA_PRESET_SLOT_COUNT],
+}
+
+impl CameraPresets {
+    // A missing file is treated as having no presets, and malformed presets are ignored.
+    pub fn load(path: &Path) -> CameraPresets {
+        let mut presets = CameraPresets { slots: [None; CAMERA_PRESET_SLOT_COUNT] };
+
+        let table = match fs::read_to_string(path).ok().and_then(|data| data.parse().ok()) {
+            Some(Value::Table(table)) => table,
+            _ => return presets,
+        };
+        for (slot, preset) in presets.slots.iter_mut().enumerate() {
+            *preset = table.get(&slot_key(slot)).and_then(parse_preset);
+        }
+        presets
+    }
+
+    pub fn get(&self, slot: usize) -> Option<CameraPreset> {
+        self.slots[slot]
+    }
+
+    pub fn set(&mut self, slot: usize, preset: CameraPreset) {
+        self.slots[slot] = Some(preset);
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut table = Table::new();
+        for (slot, preset) in self.slots.iter().enumerate() {
+            if let Some(ref preset) = *preset {
+                table.insert(slot_key(slot), serialize_preset(preset));
+            }
+        }
+        let data = toml::to_string(&Value::Table(table)).map_err(|error| {
+            io::Error::new(ErrorKind::Other, error)
+        })?;
+        fs::write(path, data)
+    }
+}
+
+fn slot_key(slot: usize) -> String {
+    format!("slot{}", slot)
+}
+
+fn parse_preset(value: &Value) -> Option<CameraPreset> {
+    let position = value.get("position")?.as_array()?;
+    if position.len() != 3 {
+        return None;
+    }
+    let mut preset = CameraPreset {
+        position: [0.0; 3],
+        yaw: value.get("yaw")?.as_float()? as f32,
+        pitch: value.get("pitch")?.as_float()? as f32,
+        roll: value.get("roll").and_then(Value::as_float).unwrap_or(0.0) as f32,
+    };
+    for (component, value) in preset.position.iter_mut().zip(position.iter()) {
+        *component = value.as_float()? as f32;
+    }
+    Some(preset)
+}
+
+fn serialize_preset(preset: &CameraPreset) -> Value {
+    let mut table = Table::new();
+    let position = preset.position.iter().map(|&component| Value::Float(component as f64));
+    table.insert("position".to_owned(), Value::Array(position.collect()));
+    table.insert("yaw".to_owned(), Value::Float(preset.yaw as f64));
+    table.insert("pitch".to_owned(), Value::Float(preset.pitch as f64));
+    table.insert("roll".to_owned(), Value::Float(preset.roll as f64));
+    Value::Table(table)
+}
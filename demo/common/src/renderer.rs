@@ -10,20 +10,30 @@
 
 //! Rendering functionality for the demo.
 
-use crate::camera::{Camera, Mode};
+use crate::camera::{Camera, Mode, clip_planes};
 use crate::window::{View, Window};
-use crate::{BackgroundColor, DemoApp, UIVisibility};
+use crate::{BackgroundColor, Corner, DemoApp, DeviceImpl, ReprojectionFilter, UIVisibility};
+use crate::scale_render_transform;
+use crate::scene_viewport_size;
 use image::ColorType;
 use pathfinder_color::{ColorF, ColorU};
-use pathfinder_gpu::{ClearOps, DepthFunc, DepthState, Device, Primitive, RenderOptions};
-use pathfinder_gpu::{RenderState, RenderTarget, TextureData, TextureFormat, UniformData};
-use pathfinder_geometry::rect::RectI;
+use pathfinder_color::matrix::ColorMatrix;
+use pathfinder_gpu::{BlendFactor, BlendState, ClearOps, DepthState, Device, Primitive};
+use pathfinder_gpu::{RenderOptions, RenderState, RenderTarget, TextureData, TextureDataRef};
+use pathfinder_gpu::{TextureFormat, TextureSamplingFlags, UniformData};
+use pathfinder_geometry::rect::{RectF, RectI};
+use pathfinder_geometry::transform2d::Transform2F;
 use pathfinder_geometry::transform3d::Transform4F;
-use pathfinder_geometry::vector::{Vector2I, Vector4F};
+use pathfinder_geometry::vector::{Vector2F, Vector2I, Vector4F, vec2f, vec2i};
 use pathfinder_renderer::gpu::options::{DestFramebuffer, RendererOptions};
-use pathfinder_renderer::options::RenderTransform;
+use pathfinder_renderer::gpu::renderer::DebugUIPresenterInfo;
+use pathfinder_renderer::concurrent::scene_proxy::SceneProxy;
+use pathfinder_renderer::options::{BuildOptions, RenderTransform};
+use pathfinder_simd::default::F32x4;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::mem;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const GROUND_SOLID_COLOR: ColorU = ColorU {
     r: 80,
@@ -41,6 +51,53 @@ const GROUND_LINE_COLOR: ColorU = ColorU {
 
 const GRIDLINE_COUNT: i32 = 10;
 
+// How many times larger than the finite ground the infinite ground is drawn. The plane is really
+// just a big quad; the shader fades the grid into the background long before its edge.
+const INFINITE_GROUND_SCALE: i32 = 256;
+
+// The distance between the watermark and the edges of the window, in logical pixels.
+const WATERMARK_MARGIN: f32 = 16.0;
+
+// The size of each checkerboard square, in logical pixels.
+const CHECKERBOARD_SQUARE_SIZE: i32 = 8;
+const CHECKERBOARD_LIGHT_COLOR: ColorU = ColorU { r: 255, g: 255, b: 255, a: 255 };
+const CHECKERBOARD_DARK_COLOR: ColorU = ColorU { r: 204, g: 204, b: 204, a: 255 };
+
+// The number of texels in the background gradient texture, which is stretched over the window.
+const BACKGROUND_GRADIENT_STEPS: i32 = 256;
+
+// Everything that affects the rendering of a 2D scene, other than the scene itself. If this is
+// unchanged since the scene was last rendered to the scene framebuffer, that rendering can be
+// reused.
+#[derive(Clone, PartialEq)]
+pub(crate) struct SceneCacheKey {
+    transform: Transform2F,
+    viewport: RectI,
+    background_color: ColorU,
+    stem_darkening: bool,
+    subpixel_aa: bool,
+    // Snapping changes the transform the scene is built with but not the camera's.
+    snap_to_pixel: bool,
+    show_debug_ui: bool,
+}
+
+// An SVG drawn over the scene in a corner of the window, with `--watermark`. It doesn't move with
+// the camera, so it's rendered once to its own framebuffer and composited over every frame, and
+// only rendered again when the window's size or device pixel ratio changes.
+pub(crate) struct Watermark {
+    scene_proxy: SceneProxy,
+    view_box: RectF,
+    framebuffer: Option<<DeviceImpl as Device>::Framebuffer>,
+    // The viewport and backing scale factor that the framebuffer was rendered for.
+    cache_key: Option<(RectI, f32)>,
+}
+
+impl Watermark {
+    pub(crate) fn new(scene_proxy: SceneProxy, view_box: RectF) -> Watermark {
+        Watermark { scene_proxy, view_box, framebuffer: None, cache_key: None }
+    }
+}
+
 impl<W> DemoApp<W> where W: Window {
     pub fn prepare_frame_rendering(&mut self) -> u32 {
         // Make the context current.
@@ -50,34 +107,49 @@ impl<W> DemoApp<W> where W: Window {
         // Clear to the appropriate color.
         let mode = self.camera.mode();
         let clear_color = match mode {
-            Mode::TwoD => Some(self.ui_model.background_color().to_f32()),
+            Mode::TwoD => Some(self.output_color(self.scene_background_color())),
+            // Clear here instead if the ground, which normally clears, isn't going to be drawn.
+            Mode::ThreeD if self.camera.is_transitioning() || !self.options.show_ground ||
+                    self.ui_model.background_color == BackgroundColor::Transparent => {
+                Some(self.output_color(self.ui_model.background_color()))
+            }
             Mode::ThreeD => None,
             Mode::VR => Some(ColorF::transparent_black()),
         };
 
+        // Leave the performance stats out of screenshots unless asked to include them.
+        let show_debug_ui = self.options.ui != UIVisibility::None &&
+            (self.options.screenshot_include_ui || !self.is_capturing_frame());
+
         // Set up framebuffers.
         let window_size = self.window_size.device_size();
         let scene_count = match mode {
             Mode::VR => {
-                let viewport = self.window.viewport(View::Stereo(0));
+                // The scene framebuffer is about to be overwritten.
+                self.scene_cache_key = None;
+
+                // The eyes may have different viewport sizes, so size the scene framebuffer to
+                // fit the largest of them.
+                let scene_size = self.scene_texture_size(scene_viewport_size(&self.window, mode));
                 if self.scene_framebuffer.is_none()
                     || self.renderer.device().texture_size(
                         &self.renderer.device().framebuffer_texture(self.scene_framebuffer
                                                                         .as_ref()
                                                                         .unwrap()),
-                    ) != viewport.size()
+                    ) != scene_size
                 {
                     let scene_texture = self
                         .renderer
                         .device()
-                        .create_texture(TextureFormat::RGBA8, viewport.size());
+                        .create_texture(TextureFormat::RGBA8, scene_size);
                     self.scene_framebuffer =
                         Some(self.renderer.device().create_framebuffer(scene_texture));
                 }
                 *self.renderer.options_mut() = RendererOptions {
                     dest: DestFramebuffer::Other(self.scene_framebuffer.take().unwrap()),
                     background_color: clear_color,
-                    show_debug_ui: self.options.ui != UIVisibility::None,
+                    show_debug_ui,
+                    coverage_gamma: self.options.antialias_gamma,
                 };
                 2
             }
@@ -88,7 +160,8 @@ impl<W> DemoApp<W> where W: Window {
                         window_size,
                     },
                     background_color: clear_color,
-                    show_debug_ui: self.options.ui != UIVisibility::None,
+                    show_debug_ui,
+                    coverage_gamma: self.options.antialias_gamma,
                 };
                 1
             }
@@ -98,12 +171,145 @@ impl<W> DemoApp<W> where W: Window {
     }
 
     pub fn draw_scene(&mut self) {
+        // The watermark is left out of VR, where there's no window corner to pin it to.
+        let watermark_visible = self.camera.mode() != Mode::VR;
+        if watermark_visible {
+            self.update_watermark_framebuffer();
+        }
+
+        // Color blindness is simulated over everything drawn here, backgrounds and the ground
+        // included, so it's all drawn offscreen first.
+        let color_matrix = match self.camera.mode() {
+            Mode::VR => None,
+            _ => self.ui_model.color_blindness.color_matrix(),
+        };
+        if color_matrix.is_some() {
+            self.begin_color_blindness_simulation();
+        }
+
+        self.draw_scene_contents();
+        if watermark_visible {
+            self.draw_watermark();
+        }
+
+        if let Some(color_matrix) = color_matrix {
+            self.finish_color_blindness_simulation(&color_matrix);
+        }
+    }
+
+    // Redirects drawing to a cleared offscreen framebuffer the size of the window.
+    fn begin_color_blindness_simulation(&mut self) {
+        let size = self.window.viewport(View::Mono).size();
+        let device = self.renderer.device();
+        let framebuffer = match self.color_blindness_framebuffer.take() {
+            Some(framebuffer) if device.texture_size(device.framebuffer_texture(&framebuffer)) ==
+                    size => framebuffer,
+            _ => device.create_framebuffer(device.create_texture(TextureFormat::RGBA8, size)),
+        };
+
+        // Only the clear matters here, as when preparing the distortion framebuffer.
+        device.begin_commands();
+        device.draw_elements(6, &RenderState {
+            target: &RenderTarget::Framebuffer(&framebuffer),
+            program: &self.color_matrix_program.program,
+            vertex_array: &self.color_matrix_vertex_array.vertex_array,
+            primitive: Primitive::Triangles,
+            textures: &[(&self.color_matrix_program.source_texture, &self.placeholder_texture)],
+            images: &[],
+            storage_buffers: &[],
+            uniforms: &[
+                (&self.color_matrix_program.color_matrix_uniform,
+                 UniformData::Mat4([F32x4::default(); 4])),
+            ],
+            viewport: RectI::new(Vector2I::zero(), size),
+            options: RenderOptions {
+                clear_ops: ClearOps {
+                    color: Some(ColorF::transparent_black()),
+                    ..ClearOps::default()
+                },
+                color_mask: false,
+                ..RenderOptions::default()
+            },
+        });
+        device.end_commands();
+
+        self.renderer.options_mut().dest = DestFramebuffer::Other(framebuffer);
+        self.renderer.dest_framebuffer_size_changed();
+    }
+
+    // Puts the window back as the destination and copies the offscreen frame to it through the
+    // color matrix. The checkerboard was drawn straight to the window, so the frame goes over it.
+    fn finish_color_blindness_simulation(&mut self, color_matrix: &ColorMatrix) {
+        let window_dest = DestFramebuffer::Default {
+            viewport: self.window.viewport(View::Mono),
+            window_size: self.window_size.device_size(),
+        };
+        let framebuffer = match mem::replace(&mut self.renderer.options_mut().dest, window_dest) {
+            DestFramebuffer::Other(framebuffer) => framebuffer,
+            DestFramebuffer::Default { .. } => unreachable!(),
+        };
+        self.renderer.dest_framebuffer_size_changed();
+
+        // The frame has premultiplied alpha.
+        let blend = if self.checkerboard_visible() {
+            Some(BlendState {
+                src_rgb_factor: BlendFactor::One,
+                dest_rgb_factor: BlendFactor::OneMinusSrcAlpha,
+                src_alpha_factor: BlendFactor::One,
+                dest_alpha_factor: BlendFactor::OneMinusSrcAlpha,
+                ..BlendState::default()
+            })
+        } else {
+            None
+        };
+        let [column_0, column_1, column_2, column_3, _] = color_matrix.0;
+        let device = self.renderer.device();
+        let texture = device.framebuffer_texture(&framebuffer);
+        device.begin_commands();
+        device.draw_elements(6, &RenderState {
+            target: &self.renderer.draw_render_target(),
+            program: &self.color_matrix_program.program,
+            vertex_array: &self.color_matrix_vertex_array.vertex_array,
+            primitive: Primitive::Triangles,
+            textures: &[(&self.color_matrix_program.source_texture, texture)],
+            images: &[],
+            storage_buffers: &[],
+            uniforms: &[
+                (&self.color_matrix_program.color_matrix_uniform,
+                 UniformData::Mat4([column_0, column_1, column_2, column_3])),
+            ],
+            viewport: self.renderer.draw_viewport(),
+            options: RenderOptions { blend, ..RenderOptions::default() },
+        });
+        device.end_commands();
+
+        self.color_blindness_framebuffer = Some(framebuffer);
+    }
+
+    fn draw_scene_contents(&mut self) {
+        // A scene that has to be scaled down to fit in a texture is rendered offscreen without
+        // multisampling, since the multisampled framebuffer would be too large too.
+        let downscaled = self.scene_downscale().is_some();
+        if let Some(sample_count) = self.options.msaa {
+            if self.camera.mode() != Mode::VR && !downscaled {
+                self.draw_scene_multisampled(sample_count);
+                return;
+            }
+        }
+
+        let cache_key = self.scene_cache_key_for_frame();
+        if cache_key.is_some() || self.checkerboard_visible() ||
+                self.background_texture_visible() || self.srgb_enabled() || downscaled {
+            self.draw_scene_offscreen(cache_key);
+            return;
+        }
+
         self.renderer.device().begin_commands();
 
         let view = self.ui_model.mode.view(0);
         self.window.make_current(view);
 
-        if self.camera.mode() != Mode::VR {
+        if self.camera.mode() != Mode::VR && !self.camera.is_transitioning() {
             self.draw_environment(0);
         }
 
@@ -128,19 +334,347 @@ impl<W> DemoApp<W> where W: Window {
         }
     }
 
+    // Renders the watermark to its framebuffer, if the window has changed since it was last
+    // rendered. This is done before the scene is rendered, so that the renderer's statistics for
+    // the frame are the scene's.
+    #[allow(deprecated)]
+    fn update_watermark_framebuffer(&mut self) {
+        let mut watermark = match self.watermark.take() {
+            None => return,
+            Some(watermark) => watermark,
+        };
+
+        let viewport = self.window.viewport(View::Mono);
+        let scale_factor = self.window_size.backing_scale_factor;
+        if watermark.cache_key != Some((viewport, scale_factor)) {
+            let size = viewport.size();
+            let texture = self.renderer.device().create_texture(TextureFormat::RGBA8, size);
+            let framebuffer = self.renderer.device().create_framebuffer(texture);
+
+            let watermark_size = watermark.view_box.size() * scale_factor;
+            let margin = WATERMARK_MARGIN * scale_factor;
+            let far_origin = size.to_f32() - watermark_size - vec2f(margin, margin);
+            let origin = match self.options.watermark_corner {
+                Corner::TopLeft => vec2f(margin, margin),
+                Corner::TopRight => vec2f(far_origin.x(), margin),
+                Corner::BottomLeft => vec2f(margin, far_origin.y()),
+                Corner::BottomRight => far_origin,
+            };
+            let transform = Transform2F::from_translation(origin) *
+                Transform2F::from_scale(scale_factor) *
+                Transform2F::from_translation(-watermark.view_box.origin());
+            watermark.scene_proxy.set_view_box(RectF::new(Vector2F::zero(), size.to_f32()));
+            watermark.scene_proxy.build(BuildOptions {
+                transform: RenderTransform::Transform2D(transform),
+                ..BuildOptions::default()
+            });
+
+            // Render over transparent black, without the debug UI, and then put the window back.
+            let options = self.renderer.options_mut();
+            let window_dest = mem::replace(&mut options.dest, DestFramebuffer::Other(framebuffer));
+            let background_color =
+                mem::replace(&mut options.background_color, Some(ColorF::transparent_black()));
+            let show_debug_ui = mem::replace(&mut options.show_debug_ui, false);
+            self.renderer.dest_framebuffer_size_changed();
+            self.renderer.disable_depth();
+
+            watermark.scene_proxy.render(&mut self.renderer);
+
+            let options = self.renderer.options_mut();
+            let framebuffer_dest = mem::replace(&mut options.dest, window_dest);
+            options.background_color = background_color;
+            options.show_debug_ui = show_debug_ui;
+            self.renderer.dest_framebuffer_size_changed();
+            watermark.framebuffer = match framebuffer_dest {
+                DestFramebuffer::Other(framebuffer) => Some(framebuffer),
+                DestFramebuffer::Default { .. } => unreachable!(),
+            };
+            watermark.cache_key = Some((viewport, scale_factor));
+        }
+
+        self.watermark = Some(watermark);
+    }
+
+    // Composites the watermark over everything drawn to the window so far.
+    fn draw_watermark(&mut self) {
+        let framebuffer = match self.watermark {
+            Some(Watermark { framebuffer: Some(ref framebuffer), .. }) => framebuffer,
+            _ => return,
+        };
+        self.renderer.device().begin_commands();
+        let texture = self.renderer.device().framebuffer_texture(framebuffer);
+        self.renderer.blit_texture_to_dest(texture, true);
+        self.renderer.device().end_commands();
+    }
+
+    // Returns the key identifying this frame's rendering of the scene, or `None` if the scene
+    // mustn't be cached. Only 2D scenes are cached, since the scene framebuffer has no depth buffer
+    // for the ground. Benchmarks and statistics need every frame to be really rendered.
+    fn scene_cache_key_for_frame(&self) -> Option<SceneCacheKey> {
+        let transform = match self.camera {
+            Camera::TwoD(transform) => transform,
+            _ => return None,
+        };
+        if self.benchmark.is_some() || self.stats_recorder.is_some() ||
+                self.options.dump_commands.is_some() {
+            return None;
+        }
+
+        Some(SceneCacheKey {
+            transform,
+            viewport: self.window.viewport(View::Mono),
+            background_color: self.scene_background_color(),
+            stem_darkening: self.ui_model.stem_darkening_effect_enabled,
+            subpixel_aa: self.ui_model.subpixel_aa_effect_enabled,
+            snap_to_pixel: self.snap_to_pixel,
+            show_debug_ui: self.renderer.options().show_debug_ui,
+        })
+    }
+
+    // Renders the scene to the scene framebuffer, unless it's cached and unchanged since the last
+    // frame, and then copies that framebuffer to the window. This way, frames in which only the
+    // demo UI changed don't render the scene at all.
+    //
+    // The performance stats are rendered along with the scene, so they're cached too; that's fine,
+    // because they only change when the scene is rendered.
+    fn draw_scene_offscreen(&mut self, cache_key: Option<SceneCacheKey>) {
+        let size = self.scene_texture_size(self.window.viewport(View::Mono).size());
+        let format = if self.srgb_enabled() { TextureFormat::SRGBA8 } else { TextureFormat::RGBA8 };
+        let needs_allocation = match self.scene_framebuffer {
+            None => true,
+            Some(ref framebuffer) => {
+                let device = self.renderer.device();
+                let texture = device.framebuffer_texture(framebuffer);
+                device.texture_size(texture) != size || device.texture_format(texture) != format
+            }
+        };
+        if needs_allocation {
+            let texture = self.renderer.device().create_texture(format, size);
+            if format == TextureFormat::SRGBA8 {
+                // Copy the encoded values straight to the window when compositing.
+                self.renderer
+                    .device()
+                    .set_texture_sampling_mode(&texture, TextureSamplingFlags::NO_SRGB_DECODE);
+            }
+            self.scene_framebuffer = Some(self.renderer.device().create_framebuffer(texture));
+            self.scene_cache_key = None;
+        }
+
+        if cache_key.is_none() || self.scene_cache_key != cache_key {
+            let scene_framebuffer = self.scene_framebuffer.take().unwrap();
+            self.scene_framebuffer = Some(self.render_scene_to_framebuffer(scene_framebuffer));
+            self.scene_cache_key = cache_key;
+        }
+
+        let scene_framebuffer = self.scene_framebuffer.take().unwrap();
+        self.composite_offscreen_scene(&scene_framebuffer);
+        self.scene_framebuffer = Some(scene_framebuffer);
+    }
+
+    // Copies a scene rendered offscreen to the window, over the checkerboard or the background
+    // image or gradient if one is shown.
+    fn composite_offscreen_scene(&mut self, framebuffer: &<DeviceImpl as Device>::Framebuffer) {
+        self.renderer.device().begin_commands();
+        let checkerboard_visible = self.checkerboard_visible();
+        let background_texture_visible = self.background_texture_visible();
+        if checkerboard_visible {
+            self.draw_checkerboard();
+        } else if background_texture_visible {
+            let background_texture = self.background_texture.as_ref().unwrap();
+            self.renderer.blit_texture_to_dest(background_texture, false);
+        }
+        let texture = self.renderer.device().framebuffer_texture(framebuffer);
+        self.renderer.blit_texture_to_dest(texture,
+                                           checkerboard_visible || background_texture_visible);
+        self.renderer.device().end_commands();
+    }
+
+    // The background image or gradient replaces the light and dark background colors in 2D.
+    // Unlike the checkerboard, it's part of the picture, so screenshots and recorded frames
+    // include it.
+    fn background_texture_visible(&self) -> bool {
+        self.background_texture.is_some() && self.camera.mode() == Mode::TwoD &&
+            self.ui_model.background_color != BackgroundColor::Transparent
+    }
+
+    // The color that the scene is rendered over. When the background image or gradient is shown,
+    // the scene is rendered over transparent black and then composited over it.
+    fn scene_background_color(&self) -> ColorU {
+        if self.background_texture_visible() {
+            ColorU::transparent_black()
+        } else {
+            self.ui_model.background_color()
+        }
+    }
+
+    // With `--srgb`, colors go through this pipeline:
+    //
+    // 1. Scene colors, which are sRGB-encoded as in SVG and CSS, are converted to linear light when
+    //    the scene is loaded, and so are the colors that the demo clears to and draws the ground
+    //    with.
+    //
+    // 2. The scene is rendered offscreen to an `SRGBA8` framebuffer. The GPU blends in linear
+    //    light there, converting the framebuffer contents to linear on read and back to sRGB on
+    //    write.
+    //
+    // 3. The framebuffer is copied to the window without decoding, so the window receives sRGB
+    //    values just as a browser would produce, except that antialiased edges and translucent
+    //    paths are blended in linear light rather than in sRGB. This needs
+    //    `GL_EXT_texture_sRGB_decode`; the Metal and WebGL backends, and OpenGL drivers without
+    //    the extension, can't skip decoding, so the picture comes out too dark there. The demo
+    //    warns about this at startup.
+    //
+    // Since colors are stored in 8 bits per channel in between steps 1 and 2, dark colors lose
+    // some precision. VR mode doesn't render offscreen this way, so it's left alone.
+    fn srgb_enabled(&self) -> bool {
+        self.options.srgb && self.camera.mode() != Mode::VR
+    }
+
+    // Converts a color that the demo draws with to the space that the scene is rendered in.
+    fn output_color(&self, color: ColorU) -> ColorF {
+        if self.srgb_enabled() {
+            color.to_f32().srgb_to_linear()
+        } else {
+            color.to_f32()
+        }
+    }
+
+    // The checkerboard shows through the transparent background to make alpha visible. It's left
+    // out of screenshots and recorded frames so that they keep their real alpha.
+    fn checkerboard_visible(&self) -> bool {
+        self.checkerboard_enabled && self.camera.mode() != Mode::VR &&
+            self.ui_model.background_color == BackgroundColor::Transparent &&
+            !self.is_capturing_frame()
+    }
+
+    fn draw_checkerboard(&mut self) {
+        let viewport = self.window.viewport(View::Mono);
+        let square_size = (CHECKERBOARD_SQUARE_SIZE as f32 *
+                           self.window_size.backing_scale_factor).round().max(1.0) as i32;
+
+        let mut dark_squares = vec![];
+        for row in 0..((viewport.height() + square_size - 1) / square_size) {
+            for column in 0..((viewport.width() + square_size - 1) / square_size) {
+                if (row + column) % 2 == 1 {
+                    let origin = viewport.origin() + vec2i(column, row) * square_size;
+                    dark_squares.push(RectI::new(origin, vec2i(square_size, square_size)));
+                }
+            }
+        }
+
+        let DebugUIPresenterInfo { device, allocator, debug_ui_presenter } =
+            self.renderer.debug_ui_presenter_mut();
+        let ui_presenter = &mut debug_ui_presenter.ui_presenter;
+        ui_presenter.set_framebuffer_size(self.window_size.device_size());
+        ui_presenter.draw_solid_rect(device, allocator, viewport, CHECKERBOARD_LIGHT_COLOR);
+        ui_presenter.draw_solid_rects(device, allocator, &dark_squares, CHECKERBOARD_DARK_COLOR);
+    }
+
+    // Renders the scene to an offscreen framebuffer instead of the window and returns the color
+    // texture, so that an embedding application can composite it however it likes. Call this
+    // in place of `draw_scene()`, after `prepare_frame()`. Only mono (2D and 3D) rendering is
+    // supported.
+    //
+    // The texture is owned by the `DemoApp` and reused across frames: the next call overwrites
+    // its contents, and reallocates it if the viewport size has changed. The returned reference
+    // therefore can't outlive the next mutation of the app; copy the texture if its contents need
+    // to be kept. If the viewport is larger than the texture size limit, the texture is smaller
+    // than the viewport, with the same aspect ratio.
+    pub fn render_to_texture(&mut self) -> &<DeviceImpl as Device>::Texture {
+        let size = self.scene_texture_size(self.window.viewport(View::Mono).size());
+        let needs_allocation = match self.offscreen_framebuffer {
+            None => true,
+            Some(ref framebuffer) => {
+                let device = self.renderer.device();
+                device.texture_size(device.framebuffer_texture(framebuffer)) != size
+            }
+        };
+        if needs_allocation {
+            let texture = self.renderer.device().create_texture(TextureFormat::RGBA8, size);
+            self.offscreen_framebuffer = Some(self.renderer.device().create_framebuffer(texture));
+        }
+
+        let offscreen_framebuffer = self.offscreen_framebuffer.take().unwrap();
+        self.offscreen_framebuffer = Some(self.render_scene_to_framebuffer(offscreen_framebuffer));
+
+        let framebuffer = self.offscreen_framebuffer.as_ref().unwrap();
+        self.renderer.device().framebuffer_texture(framebuffer)
+    }
+
+    // Renders the scene and the ground, with multisampling, and then resolves the result into the
+    // window. Pathfinder's own antialiasing still applies to the vector scene; multisampling
+    // additionally smooths the ground and the edges of the scene plane in 3D.
+    fn draw_scene_multisampled(&mut self, sample_count: u32) {
+        let size = self.window.viewport(View::Mono).size();
+        let needs_allocation = match self.msaa_framebuffer {
+            None => true,
+            Some(ref framebuffer) => {
+                let device = self.renderer.device();
+                device.texture_size(device.framebuffer_texture(framebuffer)) != size
+            }
+        };
+        if needs_allocation {
+            let device = self.renderer.device();
+            let texture = device.create_texture(TextureFormat::RGBA8, size);
+            self.msaa_framebuffer = device.create_multisampled_framebuffer(texture, sample_count);
+            if self.msaa_framebuffer.is_none() {
+                eprintln!("warning: {}x MSAA isn't supported; disabling it", sample_count);
+                self.options.msaa = None;
+                self.draw_scene_contents();
+                return;
+            }
+        }
+
+        let msaa_framebuffer = self.msaa_framebuffer.take().unwrap();
+        let msaa_framebuffer = self.render_scene_to_framebuffer(msaa_framebuffer);
+
+        self.renderer.device().begin_commands();
+        self.renderer.device().resolve_framebuffer(&msaa_framebuffer);
+        self.renderer.device().end_commands();
+        self.composite_offscreen_scene(&msaa_framebuffer);
+        self.msaa_framebuffer = Some(msaa_framebuffer);
+    }
+
+    // Temporarily redirects rendering of the ground and the scene to the given framebuffer.
+    fn render_scene_to_framebuffer(&mut self, framebuffer: <DeviceImpl as Device>::Framebuffer)
+                                   -> <DeviceImpl as Device>::Framebuffer {
+        let framebuffer_dest = DestFramebuffer::Other(framebuffer);
+        let window_dest = mem::replace(&mut self.renderer.options_mut().dest, framebuffer_dest);
+        self.renderer.dest_framebuffer_size_changed();
+
+        self.renderer.device().begin_commands();
+        if !self.camera.is_transitioning() {
+            self.draw_environment(0);
+        }
+        self.renderer.device().end_commands();
+
+        self.render_vector_scene();
+
+        let framebuffer_dest = mem::replace(&mut self.renderer.options_mut().dest, window_dest);
+        self.renderer.dest_framebuffer_size_changed();
+        match framebuffer_dest {
+            DestFramebuffer::Other(framebuffer) => framebuffer,
+            DestFramebuffer::Default { .. } => unreachable!(),
+        }
+    }
+
     pub fn begin_compositing(&mut self) {
         self.renderer.device().begin_commands();
     }
 
     #[allow(deprecated)]
     pub fn composite_scene(&mut self, render_scene_index: u32) {
-        let (eye_transforms, scene_transform, modelview_transform) = match self.camera {
+        let (eye_transforms, scene_transform, modelview_transform, eye_offset_adjustment) =
+                match self.camera {
             Camera::ThreeD {
                 ref eye_transforms,
                 ref scene_transform,
                 ref modelview_transform,
+                eye_offset_adjustment,
                 ..
-            } if eye_transforms.len() > 1 => (eye_transforms, scene_transform, modelview_transform),
+            } if eye_transforms.len() > 1 => {
+                (eye_transforms, scene_transform, modelview_transform, eye_offset_adjustment)
+            }
             _ => return,
         };
 
@@ -154,13 +688,21 @@ impl<W> DemoApp<W> where W: Window {
         );
         debug!("modelview transform={:?}", modelview_transform);
 
-        let viewport = self.window.viewport(View::Stereo(render_scene_index));
-        self.window.make_current(View::Stereo(render_scene_index));
+        let view = View::Stereo(render_scene_index);
+        let viewport = self.window.viewport(view);
+        self.window.make_current(view);
 
-        self.renderer.options_mut().dest = DestFramebuffer::Default {
-            viewport,
-            window_size: self.window_size.device_size(),
+        // With lens distortion correction, draw the eye offscreen and warp it into place after.
+        let distortion_coefficients = self.window.lens_distortion_coefficients(view);
+        let distort = distortion_coefficients != [0.0, 0.0];
+        let dest = if distort {
+            let distortion_framebuffer = self.distortion_framebuffer.take();
+            DestFramebuffer::Other(self.prepare_distortion_framebuffer(distortion_framebuffer,
+                                                                       viewport.size()))
+        } else {
+            DestFramebuffer::Default { viewport, window_size: self.window_size.device_size() }
         };
+        self.renderer.options_mut().dest = dest;
 
         self.draw_environment(render_scene_index);
 
@@ -176,8 +718,19 @@ impl<W> DemoApp<W> where W: Window {
             modelview_transform.to_transform() *
             quad_scale_transform;
 
+        // The first eye is offset in the positive X direction and the second in the negative one.
+        // (See `Camera::new_3d()`.)
+        let eye_offset_adjustment = if render_scene_index == 0 {
+            eye_offset_adjustment
+        } else {
+            -eye_offset_adjustment
+        };
+        let eye_offset_adjustment =
+            Transform4F::from_translation(Vector4F::new(eye_offset_adjustment, 0.0, 0.0, 1.0));
+
         let eye_transform = &eye_transforms[render_scene_index as usize];
         let eye_transform_matrix = eye_transform.perspective *
+            eye_offset_adjustment *
             eye_transform.modelview_to_eye *
             modelview_transform.to_transform() *
             quad_scale_transform;
@@ -192,15 +745,117 @@ impl<W> DemoApp<W> where W: Window {
         );
         debug!("---");
 
+        // Keep copying the encoded values of an sRGB scene, as when compositing it in 2D.
+        let mut sampling_flags = match self.options.reprojection_filter {
+            ReprojectionFilter::Linear => TextureSamplingFlags::empty(),
+            ReprojectionFilter::Nearest => {
+                TextureSamplingFlags::NEAREST_MIN | TextureSamplingFlags::NEAREST_MAG
+            }
+        };
+        if self.renderer.device().texture_format(scene_texture) == TextureFormat::SRGBA8 {
+            sampling_flags |= TextureSamplingFlags::NO_SRGB_DECODE;
+        }
+        self.renderer.device().set_texture_sampling_mode(scene_texture, sampling_flags);
+
         self.renderer.reproject_texture(
             scene_texture,
             &scene_transform_matrix.transform,
             &eye_transform_matrix.transform,
         );
+
+        if distort {
+            let dest = DestFramebuffer::Default {
+                viewport,
+                window_size: self.window_size.device_size(),
+            };
+            if let DestFramebuffer::Other(distortion_framebuffer) =
+                    mem::replace(&mut self.renderer.options_mut().dest, dest) {
+                self.draw_lens_distortion(&distortion_framebuffer, distortion_coefficients);
+                self.distortion_framebuffer = Some(distortion_framebuffer);
+            }
+        }
+    }
+
+    // Returns a cleared framebuffer of the given size to draw an eye into before distorting it,
+    // reusing the previous one if it's the right size.
+    //
+    // The framebuffer has no depth buffer, so the scene always draws over the ground.
+    fn prepare_distortion_framebuffer(&self,
+                                      framebuffer: Option<<DeviceImpl as Device>::Framebuffer>,
+                                      size: Vector2I)
+                                      -> <DeviceImpl as Device>::Framebuffer {
+        let device = self.renderer.device();
+        let framebuffer = match framebuffer {
+            Some(framebuffer) if device.texture_size(device.framebuffer_texture(&framebuffer)) ==
+                    size => framebuffer,
+            _ => device.create_framebuffer(device.create_texture(TextureFormat::RGBA8, size)),
+        };
+
+        // The eyes share this framebuffer, so clear out the other eye's image. Drawing with the
+        // color mask off leaves just the clear. The framebuffer is the render target, so it can't
+        // be bound as the source texture too; a placeholder is bound instead.
+        let texture = &self.placeholder_texture;
+        device.draw_elements(6, &RenderState {
+            target: &RenderTarget::Framebuffer(&framebuffer),
+            program: &self.distortion_program.program,
+            vertex_array: &self.distortion_vertex_array.vertex_array,
+            primitive: Primitive::Triangles,
+            textures: &[(&self.distortion_program.source_texture, texture)],
+            images: &[],
+            storage_buffers: &[],
+            uniforms: &[
+                (&self.distortion_program.coefficients_uniform,
+                 UniformData::Vec2(Vector2F::zero().0)),
+                (&self.distortion_program.aspect_ratio_uniform, UniformData::Float(1.0)),
+            ],
+            viewport: RectI::new(Vector2I::zero(), size),
+            options: RenderOptions {
+                clear_ops: ClearOps {
+                    color: Some(ColorF::transparent_black()),
+                    ..ClearOps::default()
+                },
+                color_mask: false,
+                ..RenderOptions::default()
+            },
+        });
+
+        framebuffer
+    }
+
+    // Warps an eye's image with radial barrel distortion onto that eye's viewport.
+    fn draw_lens_distortion(&self,
+                            distortion_framebuffer: &<DeviceImpl as Device>::Framebuffer,
+                            coefficients: [f32; 2]) {
+        let viewport = self.renderer.draw_viewport();
+        let aspect_ratio = viewport.width() as f32 / viewport.height() as f32;
+        let device = self.renderer.device();
+        device.draw_elements(6, &RenderState {
+            target: &self.renderer.draw_render_target(),
+            program: &self.distortion_program.program,
+            vertex_array: &self.distortion_vertex_array.vertex_array,
+            primitive: Primitive::Triangles,
+            textures: &[
+                (&self.distortion_program.source_texture,
+                 device.framebuffer_texture(distortion_framebuffer)),
+            ],
+            images: &[],
+            storage_buffers: &[],
+            uniforms: &[
+                (&self.distortion_program.coefficients_uniform,
+                 UniformData::Vec2(Vector2F::new(coefficients[0], coefficients[1]).0)),
+                (&self.distortion_program.aspect_ratio_uniform, UniformData::Float(aspect_ratio)),
+            ],
+            viewport,
+            options: RenderOptions::default(),
+        });
     }
 
     // Draws the ground, if applicable.
     fn draw_environment(&self, render_scene_index: u32) {
+        if !self.options.show_ground {
+            return;
+        }
+
         let frame = &self.current_frame.as_ref().unwrap();
 
         let perspective = match frame.transform {
@@ -212,11 +867,21 @@ impl<W> DemoApp<W> where W: Window {
             return;
         }
 
-        let ground_scale = self.scene_metadata.view_box.max_x() * 2.0;
+        let (ground_scale, gridline_count, fade) = if self.options.infinite_ground {
+            (self.scene_metadata.view_box.max_x() * 2.0 * INFINITE_GROUND_SCALE as f32,
+             GRIDLINE_COUNT * INFINITE_GROUND_SCALE,
+             1.0)
+        } else {
+            (self.scene_metadata.view_box.max_x() * 2.0, GRIDLINE_COUNT, 0.0)
+        };
+        let (_, far_clip_plane) = clip_planes(self.scene_metadata.view_box, &self.options);
 
-        let mut offset = self.scene_metadata.view_box.lower_right().to_4d();
-        offset.set_z(ground_scale);
-        offset = offset * Vector4F::new(-0.5, 1.0, -0.5, 1.0);
+        // Center the ground on the scene, however far it extends.
+        let view_box = self.scene_metadata.view_box;
+        let offset = Vector4F::new(0.5 * view_box.max_x() - 0.5 * ground_scale,
+                                   view_box.max_y(),
+                                   -0.5 * ground_scale,
+                                   1.0);
         let base_transform = perspective.transform * Transform4F::from_translation(offset);
 
         // Fill ground.
@@ -225,7 +890,7 @@ impl<W> DemoApp<W> where W: Window {
 
         // Don't clear the first scene after drawing it.
         let clear_color = if render_scene_index == 0 {
-            Some(self.ui_model.background_color().to_f32())
+            Some(self.output_color(self.ui_model.background_color()))
         } else {
             None
         };
@@ -242,14 +907,20 @@ impl<W> DemoApp<W> where W: Window {
                 (&self.ground_program.transform_uniform,
                  UniformData::from_transform_3d(&transform)),
                 (&self.ground_program.ground_color_uniform,
-                 UniformData::Vec4(GROUND_SOLID_COLOR.to_f32().0)),
+                 UniformData::Vec4(self.output_color(GROUND_SOLID_COLOR).0)),
                 (&self.ground_program.gridline_color_uniform,
-                 UniformData::Vec4(GROUND_LINE_COLOR.to_f32().0)),
-                (&self.ground_program.gridline_count_uniform, UniformData::Int(GRIDLINE_COUNT)),
+                 UniformData::Vec4(self.output_color(GROUND_LINE_COLOR).0)),
+                (&self.ground_program.horizon_color_uniform,
+                 UniformData::Vec4(self.output_color(self.ui_model.background_color()).0)),
+                (&self.ground_program.fade_uniform, UniformData::Float(fade)),
+                (&self.ground_program.fade_distance_uniform, UniformData::Float(far_clip_plane)),
+                (&self.ground_program.gridline_count_uniform, UniformData::Int(gridline_count)),
             ],
             viewport: self.renderer.draw_viewport(),
             options: RenderOptions {
-                depth: Some(DepthState { func: DepthFunc::Less, write: true }),
+                depth: self.options.ground_depth_func.map(|func| {
+                    DepthState { func, write: true }
+                }),
                 clear_ops: ClearOps { color: clear_color, depth: Some(1.0), stencil: Some(0) },
                 ..RenderOptions::default()
             },
@@ -258,14 +929,35 @@ impl<W> DemoApp<W> where W: Window {
 
     #[allow(deprecated)]
     fn render_vector_scene(&mut self) {
-        if self.ui_model.mode == Mode::TwoD {
+        if self.ui_model.mode == Mode::TwoD || self.camera.is_transitioning() {
             self.renderer.disable_depth();
         } else {
             self.renderer.enable_depth();
         }
 
         // Issue render commands!
-        self.scene_proxy.render(&mut self.renderer);
+        match self.options.dump_commands.take() {
+            None => self.scene_proxy.render(&mut self.renderer),
+            Some(path) => {
+                // Only the first frame is dumped.
+                let mut commands = vec![];
+                self.scene_proxy.render_with_callback(&mut self.renderer, |command| {
+                    commands.push(format!("{:?}", command));
+                });
+                if let Err(error) = write_command_transcript(&path, &commands) {
+                    eprintln!("error: couldn't write the --dump-commands transcript \"{}\": {}",
+                              path.display(),
+                              error);
+                }
+            }
+        }
+
+        // The renderer's statistics only cover the scene that it rendered last.
+        let stats = *self.renderer.stats();
+        self.frame_render_stats = Some(match self.frame_render_stats {
+            None => stats,
+            Some(frame_render_stats) => frame_render_stats + stats,
+        });
     }
 
     pub fn take_raster_screenshot(&mut self, path: PathBuf) {
@@ -277,13 +969,203 @@ impl<W> DemoApp<W> where W: Window {
             TextureData::U8(pixels) => pixels,
             _ => panic!("Unexpected pixel format for default framebuffer!"),
         };
+        let (pixels, size) = match self.screenshot_clip_rect(viewport) {
+            None => (pixels, drawable_size),
+            Some(clip_rect) => (crop_pixels(&pixels, drawable_size, clip_rect), clip_rect.size()),
+        };
         image::save_buffer(
             path,
             &pixels,
-            drawable_size.x() as u32,
-            drawable_size.y() as u32,
+            size.x() as u32,
+            size.y() as u32,
             ColorType::Rgba8,
         )
         .unwrap();
     }
+
+    // Renders the scene again offscreen at `scale` times the window's resolution, with the same
+    // transform, and saves it. Images larger than the texture size limit are rendered in tiles
+    // and stitched together. Only 2D scenes can be tiled, so 3D captures are scaled down as
+    // necessary to fit in one texture.
+    //
+    // The background image or gradient is left out, since it's composited separately.
+    pub fn take_scaled_raster_screenshot(&mut self, path: PathBuf, mut scale: i32) {
+        let viewport = RectI::new(Vector2I::zero(), self.window.viewport(View::Mono).size());
+        if self.camera.mode() != Mode::TwoD {
+            let largest_side = i32::max(viewport.width(), viewport.height());
+            let max_scale = i32::max(self.max_texture_size / largest_side, 1);
+            if scale > max_scale {
+                warn!("capturing the screenshot at {}x instead of {}x to fit in a texture",
+                      max_scale,
+                      scale);
+                scale = max_scale;
+            }
+        }
+
+        let image_rect = match self.screenshot_clip_rect(viewport) {
+            None => viewport.scale(scale),
+            Some(clip_rect) => clip_rect.scale(scale),
+        };
+        let tile_size = Vector2I::splat(self.max_texture_size).min(image_rect.size());
+        let format = if self.srgb_enabled() { TextureFormat::SRGBA8 } else { TextureFormat::RGBA8 };
+        let texture = self.renderer.device().create_texture(format, tile_size);
+        let mut framebuffer = self.renderer.device().create_framebuffer(texture);
+
+        let transform = scale_render_transform(self.camera.render_transform(), scale as f32);
+        let clip_rect = self.scene_clip_rect() * scale as f32;
+        let mut pixels = vec![0; image_rect.area() as usize * 4];
+
+        // The frame's own commands are still open, and each tile is rendered in its own.
+        self.renderer.device().end_commands();
+        let (tile_width, tile_height) = (tile_size.x() as usize, tile_size.y() as usize);
+        for tile_y in (image_rect.min_y()..image_rect.max_y()).step_by(tile_height) {
+            for tile_x in (image_rect.min_x()..image_rect.max_x()).step_by(tile_width) {
+                let tile_rect = RectI::new(vec2i(tile_x, tile_y), tile_size);
+                let tile_rect = tile_rect.intersection(image_rect).unwrap();
+
+                // Shift the scene so that the tile lands in the top left of the framebuffer.
+                let tile_origin = tile_rect.origin().to_f32();
+                let tile_transform = match transform {
+                    RenderTransform::Transform2D(transform) => {
+                        RenderTransform::Transform2D(transform.translate(-tile_origin))
+                    }
+                    ref perspective => perspective.clone(),
+                };
+                let framebuffer_rect = RectF::new(Vector2F::zero(), tile_size.to_f32());
+                let view_box = (clip_rect - tile_origin).intersection(framebuffer_rect)
+                                                        .unwrap_or_default();
+                self.scene_proxy.set_view_box(view_box);
+                self.scene_proxy.build(self.build_options(tile_transform));
+                framebuffer = self.render_scene_to_framebuffer(framebuffer);
+
+                // Read back the whole framebuffer, so that the rows come out the same way up as
+                // they do from the window, and keep the part that the tile covers.
+                let render_target = RenderTarget::Framebuffer(&framebuffer);
+                let receiver = self.renderer
+                                   .device()
+                                   .read_pixels(&render_target, RectI::new(Vector2I::zero(),
+                                                                           tile_size));
+                let tile_pixels = match self.renderer.device().recv_texture_data(&receiver) {
+                    TextureData::U8(pixels) => pixels,
+                    _ => panic!("Unexpected pixel format for the screenshot framebuffer!"),
+                };
+                let tile_pixels = crop_pixels(&tile_pixels,
+                                              tile_size,
+                                              RectI::new(Vector2I::zero(), tile_rect.size()));
+                paste_pixels(&mut pixels,
+                             image_rect.size(),
+                             &tile_pixels,
+                             tile_rect.size(),
+                             tile_rect.origin() - image_rect.origin());
+            }
+        }
+        self.renderer.device().begin_commands();
+
+        // Put back the window's view box and render the next frame normally.
+        self.update_scene_clip_rect();
+        self.scene_dirty = true;
+
+        image::save_buffer(path,
+                           &pixels,
+                           image_rect.width() as u32,
+                           image_rect.height() as u32,
+                           ColorType::Rgba8).unwrap();
+    }
+
+    // Returns the part of the window that `--clip-rect` covers, if it's in effect.
+    fn screenshot_clip_rect(&self, viewport: RectI) -> Option<RectI> {
+        let clip_rect = self.options.clip_rect?;
+        match self.camera {
+            Camera::TwoD(transform) => {
+                (transform * clip_rect).round_out().to_i32().intersection(viewport)
+            }
+            _ => None,
+        }
+    }
+}
+
+// Copies the RGBA pixels inside `rect` out of an image of the given size.
+fn crop_pixels(pixels: &[u8], size: Vector2I, rect: RectI) -> Vec<u8> {
+    let mut cropped = Vec::with_capacity(rect.width() as usize * rect.height() as usize * 4);
+    for y in rect.min_y()..rect.max_y() {
+        let row_start = (y * size.x() + rect.min_x()) as usize * 4;
+        cropped.extend_from_slice(&pixels[row_start..(row_start + rect.width() as usize * 4)]);
+    }
+    cropped
+}
+
+// Copies RGBA pixels of the given size into a larger image, with their top left corner at
+// `origin`.
+fn paste_pixels(dest: &mut [u8], dest_size: Vector2I, src: &[u8], src_size: Vector2I,
+                origin: Vector2I) {
+    let row_length = src_size.x() as usize * 4;
+    for y in 0..src_size.y() {
+        let src_start = y as usize * row_length;
+        let dest_start = ((origin.y() + y) * dest_size.x() + origin.x()) as usize * 4;
+        dest[dest_start..(dest_start + row_length)]
+            .copy_from_slice(&src[src_start..(src_start + row_length)]);
+    }
+}
+
+// Creates a texture one texel wide that fades from `top` to `bottom`, to be stretched over the
+// window. Rows are stored bottom to top, as they are in framebuffer textures.
+pub(crate) fn create_background_gradient_texture(device: &DeviceImpl, top: ColorU, bottom: ColorU)
+                                                 -> <DeviceImpl as Device>::Texture {
+    let (top, bottom) = (top.to_f32(), bottom.to_f32());
+    let mut texels = Vec::with_capacity(BACKGROUND_GRADIENT_STEPS as usize * 4);
+    for row in 0..BACKGROUND_GRADIENT_STEPS {
+        let t = row as f32 / (BACKGROUND_GRADIENT_STEPS - 1) as f32;
+        let color = bottom.lerp(top, t).to_u8();
+        texels.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+    }
+    device.create_texture_from_data(TextureFormat::RGBA8,
+                                    vec2i(1, BACKGROUND_GRADIENT_STEPS),
+                                    TextureDataRef::U8(&texels))
+}
+
+// Loads a PNG or JPEG image into a texture to be stretched over the window behind the scene.
+pub(crate) fn create_background_image_texture(device: &DeviceImpl,
+                                              path: &Path,
+                                              max_texture_size: i32)
+                                              -> Result<<DeviceImpl as Device>::Texture, String> {
+    let image = image::open(path).map_err(|err| {
+        format!("Couldn't load the background image \"{}\": {}.", path.display(), err)
+    })?;
+    let mut image = image.into_rgba8();
+    let size = vec2i(image.width() as i32, image.height() as i32);
+    if size.x() > max_texture_size || size.y() > max_texture_size {
+        return Err(format!("The background image \"{}\" is larger than the {}x{} texture limit.",
+                           path.display(),
+                           max_texture_size,
+                           max_texture_size));
+    }
+
+    // Like the gradient texture, the first row ends up at the bottom of the window.
+    image::imageops::flip_vertical_in_place(&mut image);
+    Ok(device.create_texture_from_data(TextureFormat::RGBA8,
+                                       size,
+                                       TextureDataRef::U8(&image.into_raw())))
+}
+
+// Writes one render command per line, followed by a count of each kind of command.
+fn write_command_transcript(path: &Path, commands: &[String]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for command in commands {
+        writeln!(writer, "{}", command)?;
+    }
+
+    // Tally commands by name, ignoring their arguments, in order of first appearance.
+    let mut counts: Vec<(&str, usize)> = vec![];
+    for command in commands {
+        let name = command.split('(').next().unwrap();
+        match counts.iter_mut().find(|count| count.0 == name) {
+            Some(count) => count.1 += 1,
+            None => counts.push((name, 1)),
+        }
+    }
+    writeln!(writer)?;
+    for (name, count) in counts {
+        writeln!(writer, "# {}: {}", name, count)?;
+    }
+    Ok(())
 }
@@ -0,0 +1,144 @@
+// pathfinder/demo/common/src/stats.rs
+//
+// Copyright © 2020 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Recording of per-frame rendering statistics for offline analysis.
+
+use pathfinder_renderer::gpu::perf::{RenderStats, RenderTime};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub struct StatsRecorder {
+    frames: Vec<FrameStats>,
+}
+
+struct FrameStats {
+    stats: RenderStats,
+    // GPU timing information. This is only available if timer queries were issued for the frame.
+    rendering_time: Option<RenderTime>,
+}
+
+impl StatsRecorder {
+    pub fn new() -> StatsRecorder {
+        StatsRecorder { frames: vec![] }
+    }
+
+    pub fn record(&mut self, stats: RenderStats, rendering_time: Option<RenderTime>) {
+        self.frames.push(FrameStats { stats, rendering_time });
+    }
+
+    // Writes out all recorded frames as JSON. All times are in milliseconds.
+    //
+    // The schema is:
+    //
+    //     { "frames": [ { "path_count": ..., ..., "gpu_time": { "dice_ms": ..., ... } | null } ] }
+    pub fn write_json<W>(&self, writer: &mut W) -> io::Result<()> where W: Write {
+        writeln!(writer, "{{")?;
+        writeln!(writer, "  \"frames\": [")?;
+        for (frame_index, frame) in self.frames.iter().enumerate() {
+            let stats = &frame.stats;
+            writeln!(writer, "    {{")?;
+            writeln!(writer, "      \"path_count\": {},", stats.path_count)?;
+            writeln!(writer, "      \"fill_count\": {},", stats.fill_count)?;
+            writeln!(writer, "      \"alpha_tile_count\": {},", stats.alpha_tile_count)?;
+            writeln!(writer, "      \"total_tile_count\": {},", stats.total_tile_count)?;
+            writeln!(writer, "      \"drawcall_count\": {},", stats.drawcall_count)?;
+            writeln!(writer, "      \"gpu_bytes_allocated\": {},", stats.gpu_bytes_allocated)?;
+            writeln!(writer, "      \"gpu_bytes_committed\": {},", stats.gpu_bytes_committed)?;
+            writeln!(writer,
+                     "      \"cpu_build_time_ms\": {},",
+                     duration_to_ms(stats.cpu_build_time))?;
+            match frame.rendering_time {
+                None => writeln!(writer, "      \"gpu_time\": null")?,
+                Some(ref rendering_time) => {
+                    writeln!(writer, "      \"gpu_time\": {{")?;
+                    writeln!(writer,
+                             "        \"dice_ms\": {},",
+                             duration_to_ms(rendering_time.dice_time))?;
+                    writeln!(writer,
+                             "        \"bin_ms\": {},",
+                             duration_to_ms(rendering_time.bin_time))?;
+                    writeln!(writer,
+                             "        \"tile_ms\": {},",
+                             duration_to_ms(rendering_time.tile_time))?;
+                    writeln!(writer,
+                             "        \"fill_ms\": {},",
+                             duration_to_ms(rendering_time.fill_time))?;
+                    writeln!(writer,
+                             "        \"composite_ms\": {},",
+                             duration_to_ms(rendering_time.composite_time))?;
+                    writeln!(writer,
+                             "        \"other_ms\": {},",
+                             duration_to_ms(rendering_time.other_time))?;
+                    writeln!(writer,
+                             "        \"total_ms\": {}",
+                             duration_to_ms(rendering_time.total_time()))?;
+                    writeln!(writer, "      }}")?;
+                }
+            }
+            let separator = if frame_index + 1 < self.frames.len() { "," } else { "" };
+            writeln!(writer, "    }}{}", separator)?;
+        }
+        writeln!(writer, "  ]")?;
+        writeln!(writer, "}}")
+    }
+}
+
+// Times a fixed number of frames and reports the distribution of frame times.
+pub struct Benchmark {
+    frame_count: u32,
+    frame_times: Vec<Duration>,
+    frame_start_time: Option<Instant>,
+}
+
+impl Benchmark {
+    pub fn new(frame_count: u32) -> Benchmark {
+        Benchmark {
+            frame_count,
+            frame_times: Vec::with_capacity(frame_count as usize),
+            frame_start_time: None,
+        }
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.frame_start_time = Some(Instant::now());
+    }
+
+    // Returns true if all frames have been rendered.
+    pub fn end_frame(&mut self) -> bool {
+        if let Some(frame_start_time) = self.frame_start_time.take() {
+            self.frame_times.push(frame_start_time.elapsed());
+        }
+        self.frame_times.len() >= self.frame_count as usize
+    }
+
+    pub fn print_results(&self) {
+        let mut frame_times = self.frame_times.clone();
+        frame_times.sort();
+
+        println!("frames: {}", frame_times.len());
+        if frame_times.is_empty() {
+            return;
+        }
+        println!("min: {:.3} ms", duration_to_ms(frame_times[0]));
+        println!("median: {:.3} ms", duration_to_ms(percentile(&frame_times, 0.5)));
+        println!("p95: {:.3} ms", duration_to_ms(percentile(&frame_times, 0.95)));
+        println!("max: {:.3} ms", duration_to_ms(frame_times[frame_times.len() - 1]));
+    }
+}
+
+// `sorted_times` must be sorted and nonempty.
+fn percentile(sorted_times: &[Duration], fraction: f64) -> Duration {
+    let index = ((sorted_times.len() - 1) as f64 * fraction).round() as usize;
+    sorted_times[index]
+}
+
+pub fn duration_to_ms(time: Duration) -> f64 {
+    time.as_secs() as f64 * 1000.0 + time.subsec_nanos() as f64 / 1000000.0
+}
@@ -10,10 +10,15 @@
 
 use crate::camera::Mode;
 use crate::window::Window;
-use crate::{BackgroundColor, Options};
+use crate::{BackgroundColor, ColorBlindness, DefringingKernelKind, Options};
 use pathfinder_color::ColorU;
-use pathfinder_geometry::rect::RectI;
-use pathfinder_geometry::vector::{Vector2I, vec2i};
+use pathfinder_content::clip;
+use pathfinder_content::outline::{ContourIterFlags, Outline};
+use pathfinder_content::segment::Segment;
+use pathfinder_geometry::line_segment::LineSegment2F;
+use pathfinder_geometry::rect::{RectF, RectI};
+use pathfinder_geometry::transform2d::Transform2F;
+use pathfinder_geometry::vector::{Vector2F, Vector2I, vec2i};
 use pathfinder_gpu::allocator::GPUMemoryAllocator;
 use pathfinder_gpu::{Device, TextureFormat};
 use pathfinder_renderer::gpu::debug::DebugUIPresenter;
@@ -30,7 +35,7 @@ const SLIDER_KNOB_WIDTH: i32 = 12;
 const SLIDER_KNOB_HEIGHT: i32 = 48;
 
 const EFFECTS_PANEL_WIDTH: i32 = 550;
-const EFFECTS_PANEL_HEIGHT: i32 = BUTTON_HEIGHT * 3 + PADDING * 4;
+const EFFECTS_PANEL_HEIGHT: i32 = BUTTON_HEIGHT * 7 + PADDING * 8;
 
 const BACKGROUND_PANEL_WIDTH: i32 = 250;
 const BACKGROUND_PANEL_HEIGHT: i32 = BUTTON_HEIGHT * 3;
@@ -41,6 +46,21 @@ const SCREENSHOT_PANEL_HEIGHT: i32 = BUTTON_HEIGHT * 2;
 const ROTATE_PANEL_WIDTH: i32 = SLIDER_WIDTH + PADDING * 2;
 const ROTATE_PANEL_HEIGHT: i32 = PADDING * 2 + SLIDER_HEIGHT;
 
+const RULER_THICKNESS: i32 = TOOLTIP_HEIGHT;
+const RULER_TICK_LENGTH: i32 = PADDING;
+// The minimum distance between labeled ticks on the ruler, in device pixels.
+const MIN_RULER_TICK_SPACING: f32 = 120.0;
+
+const VIEW_BOX_OUTLINE_COLOR: ColorU = ColorU { r: 0,   g: 144, b: 255, a: 255, };
+const BOUNDS_OUTLINE_COLOR:   ColorU = ColorU { r: 224, g: 32,  b: 144, a: 255, };
+
+const TILE_GRID_COLOR: ColorU = ColorU { r: 255, g: 64, b: 0, a: 112 };
+const WIREFRAME_COLOR: ColorU = ColorU { r: 0, g: 192, b: 96, a: 224 };
+const RUBBER_BAND_FILL_COLOR:    ColorU = ColorU { r: 0, g: 144, b: 255, a: 48,  };
+const RUBBER_BAND_OUTLINE_COLOR: ColorU = ColorU { r: 0, g: 144, b: 255, a: 255, };
+
+const COLOR_SWATCH_SIZE: i32 = FONT_ASCENT;
+
 const LIGHT_BG_COLOR:       ColorU = ColorU { r: 248, g: 248, b: 248, a: 255, };
 const DARK_BG_COLOR:        ColorU = ColorU { r: 32,  g: 32,  b: 32,  a: 255, };
 const TRANSPARENT_BG_COLOR: ColorU = ColorU { r: 0,   g: 0,   b: 0,   a: 0,   };
@@ -60,20 +80,56 @@ pub struct DemoUIModel {
     pub gamma_correction_effect_enabled: bool,
     pub stem_darkening_effect_enabled: bool,
     pub subpixel_aa_effect_enabled: bool,
+    // The kernel that subpixel antialiasing defringes text with.
+    pub defringing_kernel: DefringingKernelKind,
+    pub invert_colors_effect_enabled: bool,
+    pub grayscale_effect_enabled: bool,
+    pub color_blindness: ColorBlindness,
+    // Whether the boundaries of the renderer's tiles are drawn over the scene.
+    pub tile_grid_visible: bool,
     pub rotation: i32,
     pub message: String,
+    // The zoom percentage typed so far, while entering one. This replaces the message.
+    pub zoom_input: Option<String>,
+    // The scale factor of the 2D camera, shown next to the zoom control.
+    pub zoom_scale: f32,
+    // Hides the widgets that move the camera, for kiosk mode.
+    pub camera_locked: bool,
+    // Whether the scene is being built with pinned options rather than the camera's.
+    pub build_transform_pinned: bool,
 }
 
 impl DemoUIModel {
     pub fn new(options: &Options) -> DemoUIModel {
-        DemoUIModel {
+        let mut model = DemoUIModel {
             mode: options.mode,
             background_color: options.background_color,
             gamma_correction_effect_enabled: false,
             stem_darkening_effect_enabled: false,
             subpixel_aa_effect_enabled: false,
+            defringing_kernel: options.defringing_kernel,
+            invert_colors_effect_enabled: false,
+            grayscale_effect_enabled: false,
+            color_blindness: ColorBlindness::None,
+            tile_grid_visible: false,
             rotation: SLIDER_WIDTH / 2,
             message: String::new(),
+            zoom_input: None,
+            zoom_scale: 1.0,
+            camera_locked: options.kiosk,
+            build_transform_pinned: false,
+        };
+        model.apply_forced_effects(options);
+        model
+    }
+
+    // Turns off any effects that were disabled on the command line, undoing the UI toggles.
+    pub fn apply_forced_effects(&mut self, options: &Options) {
+        if options.no_stem_darkening {
+            self.stem_darkening_effect_enabled = false;
+        }
+        if options.no_subpixel_aa {
+            self.subpixel_aa_effect_enabled = false;
         }
     }
 
@@ -81,6 +137,11 @@ impl DemoUIModel {
         (self.rotation as f32 / SLIDER_WIDTH as f32 * 2.0 - 1.0) * PI
     }
 
+    // Moves the rotation slider to reflect the given angle, in radians from -π to π.
+    pub fn set_rotation(&mut self, theta: f32) {
+        self.rotation = ((theta / PI + 1.0) * 0.5 * SLIDER_WIDTH as f32).round() as i32;
+    }
+
     // Only relevant if in monochrome mode.
     pub fn foreground_color(&self) -> ColorU {
         match self.background_color {
@@ -168,12 +229,36 @@ impl<D> DemoUIPresenter<D> where D: Device {
                      window: &mut W,
                      debug_ui_presenter: &mut DebugUIPresenter<D>,
                      action: &mut UIAction,
-                     model: &mut DemoUIModel)
+                     model: &mut DemoUIModel,
+                     file_name: &str)
                      where W: Window {
         // Draw message text.
 
         self.draw_message_text(device, allocator, debug_ui_presenter, model);
 
+        // Draw the name of the file being viewed.
+
+        self.draw_file_name(device, allocator, debug_ui_presenter, file_name);
+
+        // Draw the color blindness simulation, if any, under the file name.
+
+        let mut corner_label_row = 1;
+        if model.color_blindness != ColorBlindness::None {
+            let label = format!("Simulating {}", model.color_blindness.name());
+            self.draw_corner_label(device, allocator, debug_ui_presenter, &label, corner_label_row);
+            corner_label_row += 1;
+        }
+
+        // Draw an indicator while the build transform is pinned, under that.
+
+        if model.build_transform_pinned {
+            self.draw_corner_label(device,
+                                   allocator,
+                                   debug_ui_presenter,
+                                   "PINNED",
+                                   corner_label_row);
+        }
+
         // Draw button strip.
 
         let bottom = debug_ui_presenter.ui_presenter.framebuffer_size().y() - PADDING;
@@ -231,29 +316,31 @@ impl<D> DemoUIPresenter<D> where D: Device {
         position += vec2i(button_size.x() + PADDING, 0);
 
         // Draw mode switch.
-        let new_mode = debug_ui_presenter.ui_presenter.draw_text_switch(device,
-                                                                        allocator,
-                                                                        position,
-                                                                        &["2D", "3D", "VR"],
-                                                                        model.mode as u8);
-        if new_mode != model.mode as u8 {
-            model.mode = match new_mode {
-                0 => Mode::TwoD,
-                1 => Mode::ThreeD,
-                _ => Mode::VR,
-            };
-            *action = UIAction::ModelChanged;
-        }
+        if !model.camera_locked {
+            let new_mode = debug_ui_presenter.ui_presenter.draw_text_switch(device,
+                                                                            allocator,
+                                                                            position,
+                                                                            &["2D", "3D", "VR"],
+                                                                            model.mode as u8);
+            if new_mode != model.mode as u8 {
+                model.mode = match new_mode {
+                    0 => Mode::TwoD,
+                    1 => Mode::ThreeD,
+                    _ => Mode::VR,
+                };
+                *action = UIAction::ModelChanged;
+            }
 
-        let mode_switch_width = debug_ui_presenter.ui_presenter.measure_segmented_control(3);
-        let mode_switch_size = vec2i(mode_switch_width, BUTTON_HEIGHT);
-        debug_ui_presenter.ui_presenter.draw_tooltip(
-            device,
-            allocator,
-            "2D/3D/VR Mode",
-            RectI::new(position, mode_switch_size),
-        );
-        position += vec2i(mode_switch_width + PADDING, 0);
+            let mode_switch_width = debug_ui_presenter.ui_presenter.measure_segmented_control(3);
+            let mode_switch_size = vec2i(mode_switch_width, BUTTON_HEIGHT);
+            debug_ui_presenter.ui_presenter.draw_tooltip(
+                device,
+                allocator,
+                "2D/3D/VR Mode",
+                RectI::new(position, mode_switch_size),
+            );
+            position += vec2i(mode_switch_width + PADDING, 0);
+        }
 
         // Draw background switch.
         if debug_ui_presenter.ui_presenter.draw_button(device,
@@ -284,7 +371,7 @@ impl<D> DemoUIPresenter<D> where D: Device {
         self.draw_effects_panel(device, allocator, debug_ui_presenter, model, action);
 
         // Draw rotate and zoom buttons, if applicable.
-        if model.mode != Mode::TwoD {
+        if model.mode != Mode::TwoD || model.camera_locked {
             return;
         }
 
@@ -304,7 +391,228 @@ impl<D> DemoUIPresenter<D> where D: Device {
         position += vec2i(BUTTON_WIDTH + PADDING, 0);
 
         // Draw zoom control.
-        self.draw_zoom_control(device, allocator, debug_ui_presenter, position, action);
+        self.draw_zoom_control(device, allocator, debug_ui_presenter, position, action, model);
+    }
+
+    // Draws the boundaries of the tiles that the renderer divides the scene into, within
+    // `view_box`. `tile_size` is the size of a tile in device pixels of the window, which is
+    // larger than the renderer's tile size when the scene is rendered scaled down.
+    pub fn draw_tile_grid(&self,
+                          device: &D,
+                          allocator: &mut GPUMemoryAllocator<D>,
+                          debug_ui_presenter: &mut DebugUIPresenter<D>,
+                          view_box: RectI,
+                          tile_size: Vector2F) {
+        let mut lines = vec![];
+        let mut column = (view_box.min_x() as f32 / tile_size.x()).ceil() as i32;
+        loop {
+            let x = (column as f32 * tile_size.x()).round() as i32;
+            if x >= view_box.max_x() {
+                break;
+            }
+            lines.push(RectI::new(vec2i(x, view_box.min_y()), vec2i(1, view_box.height())));
+            column += 1;
+        }
+        let mut row = (view_box.min_y() as f32 / tile_size.y()).ceil() as i32;
+        loop {
+            let y = (row as f32 * tile_size.y()).round() as i32;
+            if y >= view_box.max_y() {
+                break;
+            }
+            lines.push(RectI::new(vec2i(view_box.min_x(), y), vec2i(view_box.width(), 1)));
+            row += 1;
+        }
+
+        debug_ui_presenter.ui_presenter
+                          .draw_solid_rects(device, allocator, &lines, TILE_GRID_COLOR);
+    }
+
+    // Outlines the view box that the scene declares and the bounds of its contents, labeling the
+    // view box above its top left corner and the bounds below their bottom left corner. As with
+    // the rulers, nothing is drawn if the scene is rotated.
+    pub fn draw_bounds_outlines(&self,
+                                device: &D,
+                                allocator: &mut GPUMemoryAllocator<D>,
+                                debug_ui_presenter: &mut DebugUIPresenter<D>,
+                                transform: &Transform2F,
+                                view_box: RectF,
+                                bounds: RectF) {
+        if transform.m12() != 0.0 || transform.m21() != 0.0 {
+            return;
+        }
+
+        let ui_presenter = &debug_ui_presenter.ui_presenter;
+        let outlines = [
+            ("view box", view_box, VIEW_BOX_OUTLINE_COLOR, false),
+            ("bounds", bounds, BOUNDS_OUTLINE_COLOR, true),
+        ];
+        for &(label, rect, color, label_below) in &outlines {
+            let rect = (*transform * rect).round_out().to_i32();
+            ui_presenter.draw_rect_outline(device, allocator, rect, color);
+
+            let label_size = vec2i(ui_presenter.measure_text(label) + PADDING * 2,
+                                   TOOLTIP_HEIGHT);
+            let label_origin = if label_below {
+                rect.lower_left()
+            } else {
+                rect.origin() - vec2i(0, label_size.y())
+            };
+            ui_presenter.draw_solid_rect(device,
+                                         allocator,
+                                         RectI::new(label_origin, label_size),
+                                         color);
+            ui_presenter.draw_text(device,
+                                   allocator,
+                                   label,
+                                   label_origin + vec2i(PADDING, PADDING + FONT_ASCENT),
+                                   false);
+        }
+    }
+
+    // Draws the outlines of the given paths as the lines that the tiler flattens them into, using
+    // the same tolerance in device pixels. Only the CPU tiler flattens this way; the GPU dices
+    // curves with its own fixed tolerance.
+    pub fn draw_wireframe(&self,
+                          device: &D,
+                          allocator: &mut GPUMemoryAllocator<D>,
+                          debug_ui_presenter: &mut DebugUIPresenter<D>,
+                          outlines: &[Outline],
+                          transform: &Transform2F,
+                          tolerance: f32,
+                          clip_rect: RectF) {
+        let mut line_segments = vec![];
+        for outline in outlines {
+            for contour in outline.contours() {
+                for segment in contour.iter(ContourIterFlags::empty()) {
+                    flatten_segment(&segment.transform(transform), tolerance, &mut line_segments);
+                }
+            }
+        }
+
+        // Vertex positions are 16-bit, so lines far offscreen have to be cut down to size.
+        let line_segments: Vec<_> = line_segments.into_iter().filter_map(|line_segment| {
+            clip::clip_line_segment_to_rect(line_segment, clip_rect)
+        }).collect();
+
+        debug_ui_presenter.ui_presenter
+                          .draw_line_segments(device, allocator, &line_segments, WIREFRAME_COLOR);
+    }
+
+    // Shows the rectangle being dragged out to zoom to, in device pixels.
+    pub fn draw_rubber_band(&self,
+                            device: &D,
+                            allocator: &mut GPUMemoryAllocator<D>,
+                            debug_ui_presenter: &mut DebugUIPresenter<D>,
+                            rect: RectI) {
+        let ui_presenter = &debug_ui_presenter.ui_presenter;
+        ui_presenter.draw_solid_rect(device, allocator, rect, RUBBER_BAND_FILL_COLOR);
+        ui_presenter.draw_rect_outline(device, allocator, rect, RUBBER_BAND_OUTLINE_COLOR);
+    }
+
+    // Draws the colors that a monochrome scene is drawn in and drawn over, as swatches labeled
+    // with their hex values, in the lower right corner of the window.
+    pub fn draw_color_legend(&self,
+                             device: &D,
+                             allocator: &mut GPUMemoryAllocator<D>,
+                             debug_ui_presenter: &mut DebugUIPresenter<D>,
+                             foreground_color: ColorU,
+                             background_color: ColorU) {
+        let ui_presenter = &debug_ui_presenter.ui_presenter;
+        let rows = [
+            (format!("Foreground {}", hex_color(foreground_color)), foreground_color),
+            (format!("Background {}", hex_color(background_color)), background_color),
+        ];
+        let text_width = rows.iter().map(|row| ui_presenter.measure_text(&row.0)).max().unwrap();
+
+        let panel_size = vec2i(PADDING * 3 + COLOR_SWATCH_SIZE + text_width,
+                               TOOLTIP_HEIGHT * rows.len() as i32);
+        let panel_origin = ui_presenter.framebuffer_size() - panel_size - vec2i(PADDING, PADDING);
+        ui_presenter.draw_solid_rounded_rect(device,
+                                             allocator,
+                                             RectI::new(panel_origin, panel_size),
+                                             WINDOW_COLOR);
+
+        for (row_index, &(ref label, color)) in rows.iter().enumerate() {
+            let row_origin = panel_origin + vec2i(PADDING, TOOLTIP_HEIGHT * row_index as i32);
+            let swatch_rect = RectI::new(row_origin + vec2i(0, PADDING),
+                                         vec2i(COLOR_SWATCH_SIZE, COLOR_SWATCH_SIZE));
+            ui_presenter.draw_solid_rect(device, allocator, swatch_rect, color);
+            ui_presenter.draw_rect_outline(device, allocator, swatch_rect, TEXT_COLOR);
+            ui_presenter.draw_text(device,
+                                   allocator,
+                                   label,
+                                   row_origin + vec2i(COLOR_SWATCH_SIZE + PADDING,
+                                                      PADDING + FONT_ASCENT),
+                                   false);
+        }
+    }
+
+    // Draws rulers along the top and left edges of the window, labeled in scene units. Rulers are
+    // only meaningful if the scene isn't rotated, so nothing is drawn otherwise.
+    pub fn draw_ruler(&self,
+                      device: &D,
+                      allocator: &mut GPUMemoryAllocator<D>,
+                      debug_ui_presenter: &mut DebugUIPresenter<D>,
+                      transform: &Transform2F) {
+        if transform.m12() != 0.0 || transform.m21() != 0.0 {
+            return;
+        }
+
+        let ui_presenter = &debug_ui_presenter.ui_presenter;
+        let framebuffer_size = ui_presenter.framebuffer_size();
+        ui_presenter.draw_solid_rect(device,
+                                     allocator,
+                                     RectI::new(Vector2I::zero(),
+                                                vec2i(framebuffer_size.x(), RULER_THICKNESS)),
+                                     WINDOW_COLOR);
+        ui_presenter.draw_solid_rect(device,
+                                     allocator,
+                                     RectI::new(vec2i(0, RULER_THICKNESS),
+                                                vec2i(RULER_THICKNESS,
+                                                      framebuffer_size.y() - RULER_THICKNESS)),
+                                     WINDOW_COLOR);
+
+        // Top ruler.
+        for tick in ruler_ticks(transform.m11(), transform.m13(), framebuffer_size.x()) {
+            if tick.position < RULER_THICKNESS {
+                continue;
+            }
+            let tick_length = if tick.label.is_some() { RULER_TICK_LENGTH } else { PADDING / 2 };
+            ui_presenter.draw_solid_rect(device,
+                                         allocator,
+                                         RectI::new(vec2i(tick.position,
+                                                          RULER_THICKNESS - tick_length),
+                                                    vec2i(1, tick_length)),
+                                         TEXT_COLOR);
+            if let Some(ref label) = tick.label {
+                ui_presenter.draw_text(device,
+                                       allocator,
+                                       label,
+                                       vec2i(tick.position + PADDING / 4, FONT_ASCENT),
+                                       false);
+            }
+        }
+
+        // Left ruler.
+        for tick in ruler_ticks(transform.m22(), transform.m23(), framebuffer_size.y()) {
+            if tick.position < RULER_THICKNESS {
+                continue;
+            }
+            let tick_length = if tick.label.is_some() { RULER_TICK_LENGTH } else { PADDING / 2 };
+            ui_presenter.draw_solid_rect(device,
+                                         allocator,
+                                         RectI::new(vec2i(RULER_THICKNESS - tick_length,
+                                                          tick.position),
+                                                    vec2i(tick_length, 1)),
+                                         TEXT_COLOR);
+            if let Some(ref label) = tick.label {
+                ui_presenter.draw_text(device,
+                                       allocator,
+                                       label,
+                                       vec2i(PADDING / 4, tick.position + FONT_ASCENT),
+                                       false);
+            }
+        }
     }
 
     fn draw_zoom_control(&mut self,
@@ -312,7 +620,8 @@ impl<D> DemoUIPresenter<D> where D: Device {
                          allocator: &mut GPUMemoryAllocator<D>,
                          debug_ui_presenter: &mut DebugUIPresenter<D>,
                          position: Vector2I,
-                         action: &mut UIAction) {
+                         action: &mut UIAction,
+                         model: &DemoUIModel) {
         let zoom_segmented_control_width =
             debug_ui_presenter.ui_presenter.measure_segmented_control(3);
         let zoom_segmented_control_rect =
@@ -336,6 +645,23 @@ impl<D> DemoUIPresenter<D> where D: Device {
             Some(2) => *action = UIAction::ZoomOut,
             _ => {}
         }
+
+        // Draw the zoom percentage.
+        let zoom_text = format!("{:.0}%", model.zoom_scale * 100.0);
+        let zoom_text_width = debug_ui_presenter.ui_presenter.measure_text(&zoom_text);
+        let zoom_text_window_origin = position + vec2i(zoom_segmented_control_width + PADDING, 0);
+        debug_ui_presenter.ui_presenter.draw_solid_rounded_rect(
+            device,
+            allocator,
+            RectI::new(zoom_text_window_origin,
+                       vec2i(zoom_text_width + PADDING * 2, BUTTON_HEIGHT)),
+            WINDOW_COLOR);
+        debug_ui_presenter.ui_presenter.draw_text(
+            device,
+            allocator,
+            &zoom_text,
+            zoom_text_window_origin + vec2i(PADDING, BUTTON_TEXT_OFFSET),
+            false);
     }
 
     fn draw_message_text(&mut self,
@@ -343,11 +669,13 @@ impl<D> DemoUIPresenter<D> where D: Device {
                          allocator: &mut GPUMemoryAllocator<D>,
                          debug_ui_presenter: &mut DebugUIPresenter<D>,
                          model: &mut DemoUIModel) {
-        if model.message.is_empty() {
-            return;
-        }
+        let message = match model.zoom_input {
+            Some(ref zoom_input) => format!("Zoom: {}%", zoom_input),
+            None if model.message.is_empty() => return,
+            None => model.message.clone(),
+        };
 
-        let message_size = debug_ui_presenter.ui_presenter.measure_text(&model.message);
+        let message_size = debug_ui_presenter.ui_presenter.measure_text(&message);
         let window_origin = vec2i(PADDING, PADDING);
         let window_size = vec2i(PADDING * 2 + message_size, TOOLTIP_HEIGHT);
         debug_ui_presenter.ui_presenter.draw_solid_rounded_rect(
@@ -359,7 +687,46 @@ impl<D> DemoUIPresenter<D> where D: Device {
         debug_ui_presenter.ui_presenter.draw_text(
             device,
             allocator,
-            &model.message,
+            &message,
+            window_origin + vec2i(PADDING, PADDING + FONT_ASCENT),
+            false,
+        );
+    }
+
+    fn draw_file_name(&mut self,
+                      device: &D,
+                      allocator: &mut GPUMemoryAllocator<D>,
+                      debug_ui_presenter: &mut DebugUIPresenter<D>,
+                      file_name: &str) {
+        if file_name.is_empty() {
+            return;
+        }
+
+        self.draw_corner_label(device, allocator, debug_ui_presenter, file_name, 0);
+    }
+
+    // Draws a line of text in the upper right corner of the window. Row 0 is the top line.
+    fn draw_corner_label(&self,
+                         device: &D,
+                         allocator: &mut GPUMemoryAllocator<D>,
+                         debug_ui_presenter: &mut DebugUIPresenter<D>,
+                         text: &str,
+                         row: i32) {
+        let framebuffer_size = debug_ui_presenter.ui_presenter.framebuffer_size();
+        let text_size = debug_ui_presenter.ui_presenter.measure_text(text);
+        let window_size = vec2i(PADDING * 2 + text_size, TOOLTIP_HEIGHT);
+        let window_origin = vec2i(framebuffer_size.x() - PADDING - window_size.x(),
+                                  PADDING + (TOOLTIP_HEIGHT + PADDING) * row);
+        debug_ui_presenter.ui_presenter.draw_solid_rounded_rect(
+            device,
+            allocator,
+            RectI::new(window_origin, window_size),
+            WINDOW_COLOR,
+        );
+        debug_ui_presenter.ui_presenter.draw_text(
+            device,
+            allocator,
+            text,
             window_origin + vec2i(PADDING, PADDING + FONT_ASCENT),
             false,
         );
@@ -408,6 +775,52 @@ impl<D> DemoUIPresenter<D> where D: Device {
                                  2,
                                  effects_panel_y,
                                  &mut model.subpixel_aa_effect_enabled);
+        self.draw_effects_switch(device,
+                                 allocator,
+                                 action,
+                                 debug_ui_presenter,
+                                 "Invert Colors",
+                                 3,
+                                 effects_panel_y,
+                                 &mut model.invert_colors_effect_enabled);
+        self.draw_effects_switch(device,
+                                 allocator,
+                                 action,
+                                 debug_ui_presenter,
+                                 "Grayscale",
+                                 4,
+                                 effects_panel_y,
+                                 &mut model.grayscale_effect_enabled);
+
+        let mut defringing_kernel = model.defringing_kernel as u8;
+        self.draw_effects_choice(device,
+                                 allocator,
+                                 action,
+                                 debug_ui_presenter,
+                                 "Defringing",
+                                 5,
+                                 effects_panel_y,
+                                 &["CG", "FreeType"],
+                                 &mut defringing_kernel);
+        model.defringing_kernel = match defringing_kernel {
+            0 => DefringingKernelKind::CoreGraphics,
+            _ => DefringingKernelKind::FreeType,
+        };
+
+        // The tile grid is drawn over the scene rather than changing it, so toggling it doesn't
+        // count as changing the effects.
+        let mut tile_grid_visible = model.tile_grid_visible as u8;
+        let mut no_action = UIAction::None;
+        self.draw_effects_choice(device,
+                                 allocator,
+                                 &mut no_action,
+                                 debug_ui_presenter,
+                                 "Tile Grid",
+                                 6,
+                                 effects_panel_y,
+                                 &["Off", "On"],
+                                 &mut tile_grid_visible);
+        model.tile_grid_visible = tile_grid_visible != 0;
     }
 
     fn draw_screenshot_panel<W>(&mut self,
@@ -628,6 +1041,31 @@ impl<D> DemoUIPresenter<D> where D: Device {
                            index: i32,
                            window_y: i32,
                            value: &mut bool) {
+        let mut segment_index = *value as u8;
+        self.draw_effects_choice(device,
+                                 allocator,
+                                 action,
+                                 debug_ui_presenter,
+                                 text,
+                                 index,
+                                 window_y,
+                                 &["Off", "On"],
+                                 &mut segment_index);
+        *value = segment_index != 0;
+    }
+
+    // Draws a labeled row of the effects panel with a two-segment switch choosing between
+    // `segment_labels`.
+    fn draw_effects_choice(&self,
+                           device: &D,
+                           allocator: &mut GPUMemoryAllocator<D>,
+                           action: &mut UIAction,
+                           debug_ui_presenter: &mut DebugUIPresenter<D>,
+                           text: &str,
+                           index: i32,
+                           window_y: i32,
+                           segment_labels: &[&str; 2],
+                           value: &mut u8) {
         let text_x = PADDING * 2;
         let text_y = window_y + PADDING + BUTTON_TEXT_OFFSET + (BUTTON_HEIGHT + PADDING) * index;
         debug_ui_presenter.ui_presenter
@@ -642,8 +1080,8 @@ impl<D> DemoUIPresenter<D> where D: Device {
                                           .draw_text_switch(device,
                                                             allocator,
                                                             switch_position,
-                                                            &["Off", "On"],
-                                                            *value as u8) != 0;
+                                                            segment_labels,
+                                                            *value);
 
         if new_value != *value {
             *action = UIAction::EffectsChanged;
@@ -652,6 +1090,50 @@ impl<D> DemoUIPresenter<D> where D: Device {
     }
 }
 
+struct RulerTick {
+    // In device pixels along the ruler.
+    position: i32,
+    // Only major ticks are labeled.
+    label: Option<String>,
+}
+
+// Returns the ticks for a ruler `length` device pixels long along an axis on which scene
+// coordinate `x` appears at device pixel `scale * x + offset`. Major ticks fall on round numbers
+// at least `MIN_RULER_TICK_SPACING` apart, with four minor ticks between each pair.
+fn ruler_ticks(scale: f32, offset: f32, length: i32) -> Vec<RulerTick> {
+    if !(scale > 0.0) || !scale.is_finite() {
+        return vec![];
+    }
+
+    // Pick the smallest step of the form {1, 2, 5} × 10^n that's far enough apart.
+    let min_step = MIN_RULER_TICK_SPACING / scale;
+    let magnitude = 10.0f32.powf(min_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0].iter()
+                                    .map(|&multiple| multiple * magnitude)
+                                    .find(|&step| step >= min_step)
+                                    .unwrap_or(magnitude * 10.0);
+    let minor_step = step / 5.0;
+    let decimal_places = f32::max(-step.log10().floor(), 0.0) as usize;
+
+    let mut ticks = vec![];
+    let start = (-offset / scale / minor_step).floor() as i64;
+    let end = ((length as f32 - offset) / scale / minor_step).ceil() as i64;
+    for index in start..=end {
+        let value = index as f32 * minor_step;
+        let position = (value * scale + offset).round() as i32;
+        if position < 0 || position >= length {
+            continue;
+        }
+        let label = if index % 5 == 0 {
+            Some(format!("{:.*}", decimal_places, value))
+        } else {
+            None
+        };
+        ticks.push(RulerTick { position, label });
+    }
+    ticks
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum UIAction {
     None,
@@ -691,3 +1173,24 @@ impl ScreenshotType {
         }
     }
 }
+
+// Splits a segment in half until each piece is within the tolerance of a line, as the tiler does.
+fn flatten_segment(segment: &Segment, tolerance: f32, line_segments: &mut Vec<LineSegment2F>) {
+    if segment.is_quadratic() {
+        return flatten_segment(&segment.to_cubic(), tolerance, line_segments);
+    }
+
+    if segment.is_line() ||
+            (segment.is_cubic() && segment.as_cubic_segment().is_flat(tolerance)) {
+        line_segments.push(segment.baseline);
+        return;
+    }
+
+    let (prev, next) = segment.split(0.5);
+    flatten_segment(&prev, tolerance, line_segments);
+    flatten_segment(&next, tolerance, line_segments);
+}
+
+fn hex_color(color: ColorU) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
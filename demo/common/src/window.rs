@@ -16,6 +16,9 @@ use pathfinder_geometry::vector::Vector2I;
 use pathfinder_resources::ResourceLoader;
 use rayon::ThreadPoolBuilder;
 use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[cfg(all(target_os = "macos", not(feature = "pf-gl")))]
 use io_surface::IOSurfaceRef;
@@ -55,14 +58,84 @@ pub trait Window {
     fn adjust_thread_pool_settings(&self, builder: ThreadPoolBuilder) -> ThreadPoolBuilder {
         builder
     }
+
+    // Windows without title bars can ignore this.
+    fn set_title(&mut self, _title: &str) {}
+
+    // The radial distortion coefficients (k1, k2) that correct for the lens in front of the given
+    // view, with the radius measured in units of the viewport's half-height. Headsets whose
+    // runtime corrects lens distortion itself should leave these at zero, which skips the pass.
+    fn lens_distortion_coefficients(&self, _view: View) -> [f32; 2] {
+        [0.0, 0.0]
+    }
+
+    // Creates the timer that hides messages after they've been shown for `timeout`. The default
+    // runs it on a background thread; ports that can't spawn threads should supply their own,
+    // built on whatever timer callbacks the platform has.
+    fn create_message_timer(&self, expire_message_event_id: u32, timeout: Duration)
+                            -> Box<dyn MessageTimer> where Self: Sized {
+        Box::new(ThreadMessageTimer::new::<Self>(expire_message_event_id, timeout))
+    }
+}
+
+// Schedules the expiry of on-screen messages.
+pub trait MessageTimer {
+    // Arranges for a user event with the timer's event ID and the given epoch to be pushed once
+    // the timeout elapses. This cancels the pending expiry, if any, so only the latest message
+    // expires.
+    fn schedule(&self, epoch: u32);
+}
+
+// Expires messages from a single background thread.
+pub struct ThreadMessageTimer {
+    sender: Sender<u32>,
+}
+
+impl ThreadMessageTimer {
+    pub fn new<W>(expire_message_event_id: u32, timeout: Duration) -> ThreadMessageTimer
+                  where W: Window {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut pending_expiry: Option<(u32, Instant)> = None;
+            loop {
+                let result = match pending_expiry {
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                    Some((_, deadline)) => {
+                        let now = Instant::now();
+                        let delay =
+                            if deadline > now { deadline - now } else { Duration::default() };
+                        receiver.recv_timeout(delay)
+                    }
+                };
+                match result {
+                    Ok(epoch) => pending_expiry = Some((epoch, Instant::now() + timeout)),
+                    Err(RecvTimeoutError::Timeout) => {
+                        let (epoch, _) = pending_expiry.take().unwrap();
+                        W::push_user_event(expire_message_event_id, epoch);
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+        ThreadMessageTimer { sender }
+    }
+}
+
+impl MessageTimer for ThreadMessageTimer {
+    fn schedule(&self, epoch: u32) {
+        drop(self.sender.send(epoch));
+    }
 }
 
 pub enum Event {
     Quit,
     WindowResized(WindowSize),
+    FocusGained,
+    FocusLost,
     KeyDown(Keycode),
     KeyUp(Keycode),
     MouseDown(Vector2I),
+    MouseUp(Vector2I),
     MouseMoved(Vector2I),
     MouseDragged(Vector2I),
     Zoom(f32, Vector2I),
@@ -72,17 +145,26 @@ pub enum Event {
     },
     SetEyeTransforms(Vec<OcularTransform>),
     OpenData(DataPath),
+    DroppedFile(DataPath),
     User {
         message_type: u32,
         message_data: u32,
     },
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum Keycode {
     Alphanumeric(u8),
     Escape,
     Tab,
+    Shift,
+    Left,
+    Right,
+    Up,
+    Down,
+    F5,
+    Enter,
+    Backspace,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -113,9 +195,11 @@ pub struct OcularTransform {
     pub modelview_to_eye: Transform4F,
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum DataPath {
     Default,
     Resource(String),
     Path(PathBuf),
+    // Read once from standard input at startup.
+    Stdin,
 }
@@ -18,6 +18,7 @@ extern crate objc;
 
 use euclid::default::Size2D;
 use nfd::Response;
+use notify::{DebouncedEvent, RecursiveMode, Watcher};
 use pathfinder_demo::window::{Event, Keycode, DataPath, View, Window, WindowSize};
 use pathfinder_demo::{DemoApp, Options};
 use pathfinder_geometry::rect::RectI;
@@ -28,6 +29,9 @@ use std::cell::Cell;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Mutex;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 use surfman::{SurfaceAccess, SurfaceType, declare_surfman};
 use winit::{ControlFlow, ElementState, Event as WinitEvent, EventsLoop, EventsLoopProxy};
 use winit::{MouseButton, VirtualKeyCode, Window as WinitWindow, WindowBuilder, WindowEvent};
@@ -63,6 +67,9 @@ static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 const DEFAULT_WINDOW_WIDTH: u32 = 1067;
 const DEFAULT_WINDOW_HEIGHT: u32 = 800;
 
+// How long to wait for a burst of file changes to settle before reloading.
+const WATCH_DEBOUNCE_DELAY_MS: u64 = 200;
+
 lazy_static! {
     static ref EVENT_QUEUE: Mutex<Option<EventQueue>> = Mutex::new(None);
 }
@@ -75,14 +82,41 @@ fn main() {
     let mut options = Options::default();
     options.command_line_overrides();
 
+    // Inspecting the scene doesn't need a window.
+    if options.print_metadata {
+        let resource_loader = FilesystemResourceLoader::locate();
+        let viewport_size = initial_window_size(&options);
+        pathfinder_demo::print_scene_metadata(&resource_loader, &options, viewport_size);
+        return;
+    }
+    if options.stats_only {
+        let resource_loader = FilesystemResourceLoader::locate();
+        let viewport_size = initial_window_size(&options);
+        pathfinder_demo::print_scene_complexity(&resource_loader, &options, viewport_size);
+        return;
+    }
+    if let Some(ref export_path) = options.export_svg {
+        let resource_loader = FilesystemResourceLoader::locate();
+        let viewport_size = initial_window_size(&options);
+        pathfinder_demo::export_scene_svg(&resource_loader, &options, export_path, viewport_size);
+        return;
+    }
+
     let window = WindowImpl::new(&options);
     let window_size = window.size();
 
+    if options.watch {
+        match options.input_path {
+            DataPath::Path(ref path) => spawn_file_watcher(path.clone()),
+            _ => eprintln!("warning: `--watch` requires an input file; ignoring"),
+        }
+    }
+
     let mut app = DemoApp::new(window, window_size, options);
 
     while !app.should_exit {
         let mut events = vec![];
-        if !app.dirty {
+        if !app.dirty() {
             events.push(app.window.get_event());
         }
         while let Some(event) = app.window.try_get_event() {
@@ -90,6 +124,9 @@ fn main() {
         }
 
         let scene_count = app.prepare_frame(events);
+        if app.rendering_paused() {
+            continue;
+        }
 
         app.draw_scene();
         app.begin_compositing();
@@ -100,6 +137,70 @@ fn main() {
     }
 }
 
+fn initial_window_size(options: &Options) -> Vector2I {
+    options.window_size.unwrap_or(vec2i(DEFAULT_WINDOW_WIDTH as i32, DEFAULT_WINDOW_HEIGHT as i32))
+}
+
+// With `--dpr`, resizes the window so that it has as many device pixels as it would on a display
+// with that backing scale factor, whatever the real display's is. The demo then draws it as that
+// display would, so captures come out the same size everywhere.
+fn fit_window_to_dpr(window: &WinitWindow, options: &Options) {
+    let dpr = match options.dpr {
+        None => return,
+        Some(dpr) => dpr as f64,
+    };
+    let hidpi_factor = window.get_current_monitor().get_hidpi_factor();
+    let window_size = initial_window_size(options);
+    window.set_inner_size(LogicalSize::new(window_size.x() as f64 * dpr / hidpi_factor,
+                                           window_size.y() as f64 * dpr / hidpi_factor));
+}
+
+// Watches the given file on a background thread and asks the app to reload it whenever it
+// changes.
+fn spawn_file_watcher(path: PathBuf) {
+    thread::spawn(move || {
+        let (sender, receiver) = mpsc::channel();
+        let debounce_delay = Duration::from_millis(WATCH_DEBOUNCE_DELAY_MS);
+
+        // Watch the containing directory rather than the file itself, since many editors save by
+        // replacing the file.
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        // Nothing would report a panic on this thread, so say why `--watch` isn't working.
+        let result = notify::watcher(sender, debounce_delay).and_then(|mut watcher| {
+            watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+        let _watcher = match result {
+            Ok(watcher) => watcher,
+            Err(error) => {
+                eprintln!("warning: couldn't watch {}: {}", directory.display(), error);
+                return;
+            }
+        };
+
+        while let Ok(event) = receiver.recv() {
+            let changed_path = match event {
+                DebouncedEvent::Create(changed_path) |
+                DebouncedEvent::Write(changed_path) |
+                DebouncedEvent::Rename(_, changed_path) => changed_path,
+                _ => continue,
+            };
+            if changed_path.file_name() != path.file_name() {
+                continue;
+            }
+
+            let mut event_queue = EVENT_QUEUE.lock().unwrap();
+            let event_queue = event_queue.as_mut().unwrap();
+            event_queue.pending_custom_events.push_back(CustomEvent::OpenData(path.clone()));
+            drop(event_queue.event_loop_proxy.wakeup());
+        }
+    });
+}
+
 struct WindowImpl {
     window: WinitWindow,
 
@@ -110,6 +211,8 @@ struct WindowImpl {
     connection: Connection,
     #[cfg(any(not(target_os = "macos"), feature = "pf-gl"))]
     device: Device,
+    #[cfg(any(not(target_os = "macos"), feature = "pf-gl"))]
+    gl_version: GLVersion,
 
     #[cfg(all(target_os = "macos", not(feature = "pf-gl")))]
     #[allow(dead_code)]
@@ -142,15 +245,16 @@ enum CustomEvent {
     OpenData(PathBuf),
 }
 
-impl Window for WindowImpl {
-    #[cfg(not(target_os = "macos"))]
-    fn gl_version(&self) -> GLVersion {
-        GLVersion::GL4
-    }
+// The GL version to use unless `--backend` chooses one.
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_GL_VERSION: GLVersion = GLVersion::GL4;
+#[cfg(all(target_os = "macos", feature = "pf-gl"))]
+const DEFAULT_GL_VERSION: GLVersion = GLVersion::GL3;
 
-    #[cfg(all(target_os = "macos", feature = "pf-gl"))]
+impl Window for WindowImpl {
+    #[cfg(any(not(target_os = "macos"), feature = "pf-gl"))]
     fn gl_version(&self) -> GLVersion {
-        GLVersion::GL3
+        self.gl_version
     }
 
     #[cfg(any(not(target_os = "macos"), feature = "pf-gl"))]
@@ -211,7 +315,7 @@ impl Window for WindowImpl {
     }
 
     fn present_open_svg_dialog(&mut self) {
-        if let Ok(Response::Okay(path)) = nfd::open_file_dialog(Some("svg,pdf"), None) {
+        if let Ok(Response::Okay(path)) = nfd::open_file_dialog(Some("svg,svgz,pdf"), None) {
             let mut event_queue = EVENT_QUEUE.lock().unwrap();
             let event_queue = event_queue.as_mut().unwrap();
             event_queue.pending_custom_events.push_back(CustomEvent::OpenData(PathBuf::from(path)));
@@ -226,6 +330,10 @@ impl Window for WindowImpl {
         }
     }
 
+    fn set_title(&mut self, title: &str) {
+        self.window.set_title(title);
+    }
+
     fn create_user_event_id(&self) -> u32 {
         let id = self.next_user_event_id.get();
         self.next_user_event_id.set(id + 1);
@@ -247,12 +355,14 @@ impl WindowImpl {
     #[cfg(any(not(target_os = "macos"), feature = "pf-gl"))]
     fn new(options: &Options) -> WindowImpl {
         let event_loop = EventsLoop::new();
-        let window_size = Size2D::new(DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT);
+        let window_size = initial_window_size(options);
+        let window_size = Size2D::new(window_size.x() as u32, window_size.y() as u32);
         let logical_size = LogicalSize::new(window_size.width as f64, window_size.height as f64);
         let window = WindowBuilder::new().with_title("Pathfinder Demo")
                                          .with_dimensions(logical_size)
                                          .build(&event_loop)
                                          .unwrap();
+        fit_window_to_dpr(&window, options);
         window.show();
 
         let connection = Connection::from_winit_window(&window).unwrap();
@@ -294,6 +404,9 @@ impl WindowImpl {
             connection,
             context,
             device,
+            gl_version: options.backend
+                               .and_then(|backend| backend.gl_version())
+                               .unwrap_or(DEFAULT_GL_VERSION),
             next_user_event_id: Cell::new(0),
             pending_events: VecDeque::new(),
             mouse_position: vec2i(0, 0),
@@ -305,12 +418,14 @@ impl WindowImpl {
     #[cfg(all(target_os = "macos", not(feature = "pf-gl")))]
     fn new(options: &Options) -> WindowImpl {
         let event_loop = EventsLoop::new();
-        let window_size = Size2D::new(DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT);
+        let window_size = initial_window_size(options);
+        let window_size = Size2D::new(window_size.x() as u32, window_size.y() as u32);
         let logical_size = LogicalSize::new(window_size.width as f64, window_size.height as f64);
         let window = WindowBuilder::new().with_title("Pathfinder Demo")
                                          .with_dimensions(logical_size)
                                          .build(&event_loop)
                                          .unwrap();
+        fit_window_to_dpr(&window, options);
         window.show();
 
         let connection = SystemConnection::from_winit_window(&window).unwrap();
@@ -444,7 +559,7 @@ fn convert_winit_event(winit_event: WinitEvent,
                     ..
                 } => {
                     *mouse_down = false;
-                    None
+                    Some(Event::MouseUp(*mouse_position))
                 }
                 WindowEvent::CursorMoved { position, .. } => {
                     *mouse_position = vec2i(position.x as i32, position.y as i32);
@@ -459,12 +574,41 @@ fn convert_winit_event(winit_event: WinitEvent,
                         match virtual_keycode {
                             VirtualKeyCode::Escape => Some(Keycode::Escape),
                             VirtualKeyCode::Tab => Some(Keycode::Tab),
+                            VirtualKeyCode::LShift |
+                            VirtualKeyCode::RShift => Some(Keycode::Shift),
+                            VirtualKeyCode::Left => Some(Keycode::Left),
+                            VirtualKeyCode::Right => Some(Keycode::Right),
+                            VirtualKeyCode::Up => Some(Keycode::Up),
+                            VirtualKeyCode::Down => Some(Keycode::Down),
+                            VirtualKeyCode::F5 => Some(Keycode::F5),
+                            VirtualKeyCode::Return |
+                            VirtualKeyCode::NumpadEnter => Some(Keycode::Enter),
+                            VirtualKeyCode::Back => Some(Keycode::Backspace),
+                            VirtualKeyCode::Space => Some(Keycode::Alphanumeric(b' ')),
+                            VirtualKeyCode::Key0 => Some(Keycode::Alphanumeric(b'0')),
+                            VirtualKeyCode::Key1 => Some(Keycode::Alphanumeric(b'1')),
+                            VirtualKeyCode::Key2 => Some(Keycode::Alphanumeric(b'2')),
+                            VirtualKeyCode::Key3 => Some(Keycode::Alphanumeric(b'3')),
+                            VirtualKeyCode::Key4 => Some(Keycode::Alphanumeric(b'4')),
+                            VirtualKeyCode::Key5 => Some(Keycode::Alphanumeric(b'5')),
+                            VirtualKeyCode::Key6 => Some(Keycode::Alphanumeric(b'6')),
+                            VirtualKeyCode::Key7 => Some(Keycode::Alphanumeric(b'7')),
+                            VirtualKeyCode::Key8 => Some(Keycode::Alphanumeric(b'8')),
+                            VirtualKeyCode::Key9 => Some(Keycode::Alphanumeric(b'9')),
+                            VirtualKeyCode::Comma => Some(Keycode::Alphanumeric(b',')),
+                            VirtualKeyCode::Period => Some(Keycode::Alphanumeric(b'.')),
+                            VirtualKeyCode::LBracket => Some(Keycode::Alphanumeric(b'[')),
+                            VirtualKeyCode::RBracket => Some(Keycode::Alphanumeric(b']')),
+                            VirtualKeyCode::Equals => Some(Keycode::Alphanumeric(b'=')),
+                            VirtualKeyCode::Add => Some(Keycode::Alphanumeric(b'+')),
+                            VirtualKeyCode::Minus |
+                            VirtualKeyCode::Subtract => Some(Keycode::Alphanumeric(b'-')),
                             virtual_keycode => {
                                 let vk = virtual_keycode as u32;
                                 let vk_a = VirtualKeyCode::A as u32;
                                 let vk_z = VirtualKeyCode::Z as u32;
                                 if vk >= vk_a && vk <= vk_z {
-                                    let character = ((vk - vk_a) + 'A' as u32) as u8;
+                                    let character = ((vk - vk_a) + 'a' as u32) as u8;
                                     Some(Keycode::Alphanumeric(character))
                                 } else {
                                     None
@@ -478,7 +622,10 @@ fn convert_winit_event(winit_event: WinitEvent,
                         }
                     })
                 }
+                WindowEvent::DroppedFile(path) => Some(Event::DroppedFile(DataPath::Path(path))),
                 WindowEvent::CloseRequested => Some(Event::Quit),
+                WindowEvent::Focused(true) => Some(Event::FocusGained),
+                WindowEvent::Focused(false) => Some(Event::FocusLost),
                 WindowEvent::Resized(new_size) => {
                     let logical_size = vec2i(new_size.width as i32, new_size.height as i32);
                     let backing_scale_factor =
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use pathfinder_content::fill::FillRule;
 use pathfinder_content::outline::ContourIterFlags;
 use pathfinder_content::segment::SegmentKind;
 use pathfinder_geometry::vector::{Vector2F, vec2f};
@@ -57,12 +58,19 @@ fn export_svg<W: Write>(scene: &Scene, writer: &mut W) -> io::Result<()> {
     for draw_path_index in 0..scene.draw_path_count() {
         let draw_path_id = DrawPathId(draw_path_index);
         let draw_path = scene.get_draw_path(draw_path_id);
+        if draw_path.outline.is_empty() {
+            continue;
+        }
+
         let paint = scene.get_paint(draw_path.paint);
 
         write!(writer, "    <path")?;
         if !draw_path.name.is_empty() {
             write!(writer, " id=\"{}\"", draw_path.name)?;
         }
+        if draw_path.fill_rule == FillRule::EvenOdd {
+            write!(writer, " fill-rule=\"evenodd\"")?;
+        }
         writeln!(writer, " fill=\"{:?}\" d=\"{:?}\" />", paint.base_color(), draw_path.outline)?;
     }
     writeln!(writer, "</svg>")?;
@@ -38,10 +38,17 @@ use std::time::Duration;
 
 const DUMMY_TEXTURE_LENGTH: i32 = 16;
 
+// From `GL_EXT_texture_sRGB_decode`, which the generated bindings don't include.
+const GL_TEXTURE_SRGB_DECODE_EXT: GLenum = 0x8a48;
+const GL_DECODE_EXT: GLenum = 0x8a49;
+const GL_SKIP_DECODE_EXT: GLenum = 0x8a4a;
+
 pub struct GLDevice {
     version: GLVersion,
     default_framebuffer: GLuint,
     dummy_texture: GLTexture,
+    // Whether `GL_EXT_texture_sRGB_decode` is available.
+    srgb_decode_supported: bool,
 }
 
 impl GLDevice {
@@ -53,7 +60,13 @@ impl GLDevice {
             format: TextureFormat::RGBA8,
         };
 
-        let mut device = GLDevice { version, default_framebuffer, dummy_texture };
+        let srgb_decode_supported = extension_supported("GL_EXT_texture_sRGB_decode");
+        let mut device = GLDevice {
+            version,
+            default_framebuffer,
+            dummy_texture,
+            srgb_decode_supported,
+        };
         let dummy_texture_data =
             [0; DUMMY_TEXTURE_LENGTH as usize * DUMMY_TEXTURE_LENGTH as usize * 4];
         device.dummy_texture =
@@ -356,6 +369,18 @@ impl Device for GLDevice {
         }
     }
 
+    fn max_texture_size(&self) -> i32 {
+        unsafe {
+            let mut max_texture_size = 0;
+            gl::GetIntegerv(gl::MAX_TEXTURE_SIZE, &mut max_texture_size); ck();
+            max_texture_size
+        }
+    }
+
+    fn supports_skipping_srgb_decode(&self) -> bool {
+        self.srgb_decode_supported
+    }
+
     fn create_texture(&self, format: TextureFormat, size: Vector2I) -> GLTexture {
         let mut texture = GLTexture { gl_texture: 0, size, format };
         unsafe {
@@ -600,7 +625,88 @@ impl Device for GLDevice {
             assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
         }
 
-        GLFramebuffer { gl_framebuffer, texture }
+        GLFramebuffer { gl_framebuffer, texture, multisample: None }
+    }
+
+    fn create_multisampled_framebuffer(&self, texture: GLTexture, sample_count: u32)
+                                       -> Option<GLFramebuffer> {
+        // Asking for more samples than the driver supports is an error, not just an incomplete
+        // framebuffer, so check first.
+        let mut max_sample_count = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_SAMPLES, &mut max_sample_count); ck();
+        }
+        if sample_count > max_sample_count as u32 {
+            return None;
+        }
+
+        let size = texture.size;
+        let mut gl_framebuffer = 0;
+        let mut gl_renderbuffers = [0; 2];
+        unsafe {
+            gl::GenFramebuffers(1, &mut gl_framebuffer); ck();
+            gl::BindFramebuffer(gl::FRAMEBUFFER, gl_framebuffer); ck();
+            gl::GenRenderbuffers(2, gl_renderbuffers.as_mut_ptr()); ck();
+
+            let storage = [
+                (gl_renderbuffers[0],
+                 texture.format.gl_internal_format() as GLenum,
+                 gl::COLOR_ATTACHMENT0),
+                (gl_renderbuffers[1], gl::DEPTH24_STENCIL8, gl::DEPTH_STENCIL_ATTACHMENT),
+            ];
+            for &(gl_renderbuffer, internal_format, attachment) in &storage {
+                gl::BindRenderbuffer(gl::RENDERBUFFER, gl_renderbuffer); ck();
+                gl::RenderbufferStorageMultisample(gl::RENDERBUFFER,
+                                                   sample_count as GLsizei,
+                                                   internal_format,
+                                                   size.x(),
+                                                   size.y()); ck();
+                gl::FramebufferRenderbuffer(gl::FRAMEBUFFER,
+                                            attachment,
+                                            gl::RENDERBUFFER,
+                                            gl_renderbuffer); ck();
+            }
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0); ck();
+
+            // The driver may still reject the combination of formats and sample count.
+            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                gl::DeleteFramebuffers(1, &mut gl_framebuffer); ck();
+                gl::DeleteRenderbuffers(2, gl_renderbuffers.as_ptr()); ck();
+                return None;
+            }
+        }
+
+        let mut gl_resolve_framebuffer = 0;
+        unsafe {
+            gl::GenFramebuffers(1, &mut gl_resolve_framebuffer); ck();
+            gl::BindFramebuffer(gl::FRAMEBUFFER, gl_resolve_framebuffer); ck();
+            self.bind_texture(&texture, 0);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER,
+                                     gl::COLOR_ATTACHMENT0,
+                                     gl::TEXTURE_2D,
+                                     texture.gl_texture,
+                                     0); ck();
+            assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
+        }
+
+        let multisample = GLMultisampleStorage { gl_resolve_framebuffer, gl_renderbuffers };
+        Some(GLFramebuffer { gl_framebuffer, texture, multisample: Some(multisample) })
+    }
+
+    fn resolve_framebuffer(&self, framebuffer: &GLFramebuffer) {
+        let multisample = match framebuffer.multisample {
+            None => return,
+            Some(ref multisample) => multisample,
+        };
+        let size = framebuffer.texture.size;
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer.gl_framebuffer); ck();
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, multisample.gl_resolve_framebuffer); ck();
+            gl::BlitFramebuffer(0, 0, size.x(), size.y(),
+                                0, 0, size.x(), size.y(),
+                                gl::COLOR_BUFFER_BIT,
+                                gl::NEAREST); ck();
+        }
     }
 
     fn create_buffer(&self, mode: BufferUploadMode) -> GLBuffer {
@@ -657,6 +763,7 @@ impl Device for GLDevice {
         unsafe {
             gl::DeleteFramebuffers(1, &mut framebuffer.gl_framebuffer); ck();
         }
+        drop(framebuffer.multisample.take());
         mem::forget(framebuffer);
         texture
     }
@@ -702,6 +809,15 @@ impl Device for GLDevice {
                               } else {
                                   gl::CLAMP_TO_EDGE as GLint
                               }); ck();
+            if texture.format == TextureFormat::SRGBA8 && self.srgb_decode_supported {
+                gl::TexParameteri(gl::TEXTURE_2D,
+                                  GL_TEXTURE_SRGB_DECODE_EXT,
+                                  if flags.contains(TextureSamplingFlags::NO_SRGB_DECODE) {
+                                      GL_SKIP_DECODE_EXT as GLint
+                                  } else {
+                                      GL_DECODE_EXT as GLint
+                                  }); ck();
+            }
         }
     }
 
@@ -976,6 +1092,19 @@ impl GLDevice {
             RenderTarget::Default => self.bind_default_framebuffer(),
             RenderTarget::Framebuffer(framebuffer) => self.bind_framebuffer(framebuffer),
         }
+
+        // OpenGL ES always encodes to sRGB when writing to sRGB textures, but desktop OpenGL
+        // only does so when asked.
+        match self.version {
+            GLVersion::GLES3 => {}
+            GLVersion::GL3 | GLVersion::GL4 => unsafe {
+                if self.render_target_format(attachment) == TextureFormat::SRGBA8 {
+                    gl::Enable(gl::FRAMEBUFFER_SRGB); ck();
+                } else {
+                    gl::Disable(gl::FRAMEBUFFER_SRGB); ck();
+                }
+            },
+        }
     }
 
     fn bind_vertex_array(&self, vertex_array: &GLVertexArray) {
@@ -1107,7 +1236,7 @@ impl GLDevice {
             let channels = format.channels();
             let (mut texture_data, texture_data_ptr, texture_data_len);
             match format {
-                TextureFormat::R8 | TextureFormat::RGBA8 => {
+                TextureFormat::R8 | TextureFormat::RGBA8 | TextureFormat::SRGBA8 => {
                     let mut pixels: Vec<u8> =
                         vec![0; size.x() as usize * size.y() as usize * channels];
                     texture_data_ptr = pixels.as_mut_ptr();
@@ -1231,6 +1360,24 @@ impl Drop for GLFence {
 pub struct GLFramebuffer {
     pub gl_framebuffer: GLuint,
     pub texture: GLTexture,
+    // If present, `gl_framebuffer` renders to multisampled storage, and `texture` is attached to
+    // a separate framebuffer that the storage is resolved into.
+    pub multisample: Option<GLMultisampleStorage>,
+}
+
+pub struct GLMultisampleStorage {
+    gl_resolve_framebuffer: GLuint,
+    // The color and depth/stencil renderbuffers, in that order.
+    gl_renderbuffers: [GLuint; 2],
+}
+
+impl Drop for GLMultisampleStorage {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &mut self.gl_resolve_framebuffer); ck();
+            gl::DeleteRenderbuffers(2, self.gl_renderbuffers.as_ptr()); ck();
+        }
+    }
 }
 
 impl Drop for GLFramebuffer {
@@ -1469,6 +1616,7 @@ impl TextureFormatExt for TextureFormat {
             TextureFormat::R8 => gl::R8 as GLint,
             TextureFormat::R16F => gl::R16F as GLint,
             TextureFormat::RGBA8 => gl::RGBA8 as GLint,
+            TextureFormat::SRGBA8 => gl::SRGB8_ALPHA8 as GLint,
             TextureFormat::RGBA16F => gl::RGBA16F as GLint,
             TextureFormat::RGBA32F => gl::RGBA32F as GLint,
         }
@@ -1477,13 +1625,16 @@ impl TextureFormatExt for TextureFormat {
     fn gl_format(self) -> GLuint {
         match self {
             TextureFormat::R8 | TextureFormat::R16F => gl::RED,
-            TextureFormat::RGBA8 | TextureFormat::RGBA16F | TextureFormat::RGBA32F => gl::RGBA,
+            TextureFormat::RGBA8 |
+            TextureFormat::SRGBA8 |
+            TextureFormat::RGBA16F |
+            TextureFormat::RGBA32F => gl::RGBA,
         }
     }
 
     fn gl_type(self) -> GLuint {
         match self {
-            TextureFormat::R8 | TextureFormat::RGBA8 => gl::UNSIGNED_BYTE,
+            TextureFormat::R8 | TextureFormat::RGBA8 | TextureFormat::SRGBA8 => gl::UNSIGNED_BYTE,
             TextureFormat::R16F | TextureFormat::RGBA16F => gl::HALF_FLOAT,
             TextureFormat::RGBA32F => gl::FLOAT,
         }
@@ -1560,6 +1711,19 @@ impl GLVersion {
     }
 }
 
+// Returns true if the current context supports the extension with the given name.
+fn extension_supported(name: &str) -> bool {
+    unsafe {
+        let mut extension_count = 0;
+        gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut extension_count); ck();
+        (0..extension_count as GLuint).any(|index| {
+            let extension = gl::GetStringi(gl::EXTENSIONS, index); ck();
+            !extension.is_null() &&
+                CStr::from_ptr(extension as *const c_char).to_bytes() == name.as_bytes()
+        })
+    }
+}
+
 // Error checking
 
 #[cfg(debug_assertions)]
@@ -49,6 +49,12 @@ pub trait Device: Sized {
     fn backend_name(&self) -> &'static str;
     fn device_name(&self) -> String;
     fn feature_level(&self) -> FeatureLevel;
+    /// The largest width or height that textures and framebuffers can have, in pixels.
+    ///
+    /// Devices that don't override this report 4096, which every supported GPU allows.
+    fn max_texture_size(&self) -> i32 {
+        4096
+    }
     fn create_texture(&self, format: TextureFormat, size: Vector2I) -> Self::Texture;
     fn create_texture_from_data(&self, format: TextureFormat, size: Vector2I, data: TextureDataRef)
                                 -> Self::Texture;
@@ -121,6 +127,25 @@ pub trait Device: Sized {
     fn try_recv_buffer(&self, receiver: &Self::BufferDataReceiver) -> Option<Vec<u8>>;
     fn recv_buffer(&self, receiver: &Self::BufferDataReceiver) -> Vec<u8>;
 
+    /// Creates a framebuffer that renders to multisampled color and depth/stencil storage with the
+    /// given number of samples per pixel. Its texture only receives the rendering once
+    /// `resolve_framebuffer()` is called. Backends that don't support multisampling, or this many
+    /// samples, return `None`.
+    fn create_multisampled_framebuffer(&self, _texture: Self::Texture, _sample_count: u32)
+                                       -> Option<Self::Framebuffer> {
+        None
+    }
+
+    /// Downsamples a multisampled framebuffer into its texture. Does nothing for other
+    /// framebuffers.
+    fn resolve_framebuffer(&self, _framebuffer: &Self::Framebuffer) {}
+
+    /// Returns true if `TextureSamplingFlags::NO_SRGB_DECODE` is honored. Backends that always
+    /// decode `SRGBA8` textures when sampling them return false.
+    fn supports_skipping_srgb_decode(&self) -> bool {
+        false
+    }
+
     fn create_texture_from_png(&self,
                                resources: &dyn ResourceLoader,
                                name: &str,
@@ -211,6 +236,12 @@ pub enum TextureFormat {
     R8,
     R16F,
     RGBA8,
+    /// 8-bit RGBA with sRGB-encoded color channels.
+    ///
+    /// Rendering to a texture of this format blends in linear space and encodes the result to
+    /// sRGB on write. Sampling decodes to linear unless `TextureSamplingFlags::NO_SRGB_DECODE` is
+    /// set.
+    SRGBA8,
     RGBA16F,
     RGBA32F,
 }
@@ -483,7 +514,10 @@ impl TextureFormat {
     pub fn channels(self) -> usize {
         match self {
             TextureFormat::R8 | TextureFormat::R16F => 1,
-            TextureFormat::RGBA8 | TextureFormat::RGBA16F | TextureFormat::RGBA32F => 4,
+            TextureFormat::RGBA8 |
+            TextureFormat::SRGBA8 |
+            TextureFormat::RGBA16F |
+            TextureFormat::RGBA32F => 4,
         }
     }
 
@@ -492,7 +526,7 @@ impl TextureFormat {
         match self {
             TextureFormat::R8 => 1,
             TextureFormat::R16F => 2,
-            TextureFormat::RGBA8 => 4,
+            TextureFormat::RGBA8 | TextureFormat::SRGBA8 => 4,
             TextureFormat::RGBA16F => 8,
             TextureFormat::RGBA32F => 16,
         }
@@ -525,6 +559,9 @@ bitflags! {
         const REPEAT_V    = 0x02;
         const NEAREST_MIN = 0x04;
         const NEAREST_MAG = 0x08;
+        /// Return the stored sRGB-encoded values of an `SRGBA8` texture when sampling it, rather
+        /// than converting them to linear. Backends that can't do this ignore it.
+        const NO_SRGB_DECODE = 0x10;
     }
 }
 
@@ -541,7 +578,8 @@ impl<'a> TextureDataRef<'a> {
                                       -> *const c_void {
         let channels = match (format, self) {
             (TextureFormat::R8, TextureDataRef::U8(_)) => 1,
-            (TextureFormat::RGBA8, TextureDataRef::U8(_)) => 4,
+            (TextureFormat::RGBA8, TextureDataRef::U8(_)) |
+            (TextureFormat::SRGBA8, TextureDataRef::U8(_)) => 4,
             (TextureFormat::RGBA16F, TextureDataRef::F16(_)) => 4,
             (TextureFormat::RGBA32F, TextureDataRef::F32(_)) => 4,
             _ => panic!("Unimplemented texture format!"),
@@ -383,6 +383,12 @@ impl Device for MetalDevice {
         FeatureLevel::D3D11
     }
 
+    // Metal doesn't report this, but every Mac GPU family supports this size.
+    #[inline]
+    fn max_texture_size(&self) -> i32 {
+        16384
+    }
+
     // TODO: Add texture usage hint.
     fn create_texture(&self, format: TextureFormat, size: Vector2I) -> MetalTexture {
         let descriptor = create_texture_descriptor(format, size);
@@ -732,6 +738,7 @@ impl Device for MetalDevice {
             MTLPixelFormat::R8Unorm => TextureFormat::R8,
             MTLPixelFormat::R16Float => TextureFormat::R16F,
             MTLPixelFormat::RGBA8Unorm => TextureFormat::RGBA8,
+            MTLPixelFormat::RGBA8Unorm_sRGB => TextureFormat::SRGBA8,
             MTLPixelFormat::RGBA16Float => TextureFormat::RGBA16F,
             MTLPixelFormat::RGBA32Float => TextureFormat::RGBA32F,
             _ => panic!("Unexpected Metal texture format!"),
@@ -2177,6 +2184,7 @@ impl TextureFormatExt for TextureFormat {
             MTLPixelFormat::R8Unorm => Some(TextureFormat::R8),
             MTLPixelFormat::R16Float => Some(TextureFormat::R16F),
             MTLPixelFormat::RGBA8Unorm => Some(TextureFormat::RGBA8),
+            MTLPixelFormat::RGBA8Unorm_sRGB => Some(TextureFormat::SRGBA8),
             MTLPixelFormat::BGRA8Unorm => {
                 // FIXME(pcwalton): This is wrong! But it prevents a crash for now.
                 Some(TextureFormat::RGBA8)
@@ -2209,7 +2217,7 @@ impl MetalTextureDataReceiver {
         let format = format.expect("Unexpected framebuffer texture format!");
 
         let texture_data = match format {
-            TextureFormat::R8 | TextureFormat::RGBA8 => {
+            TextureFormat::R8 | TextureFormat::RGBA8 | TextureFormat::SRGBA8 => {
                 let channels = format.channels();
                 let stride = size.x() as usize * channels;
                 let mut pixels = vec![0; stride * size.y() as usize];
@@ -2726,6 +2734,7 @@ fn create_texture_descriptor(format: TextureFormat, size: Vector2I) -> TextureDe
         TextureFormat::R8 => descriptor.set_pixel_format(MTLPixelFormat::R8Unorm),
         TextureFormat::R16F => descriptor.set_pixel_format(MTLPixelFormat::R16Float),
         TextureFormat::RGBA8 => descriptor.set_pixel_format(MTLPixelFormat::RGBA8Unorm),
+        TextureFormat::SRGBA8 => descriptor.set_pixel_format(MTLPixelFormat::RGBA8Unorm_sRGB),
         TextureFormat::RGBA16F => descriptor.set_pixel_format(MTLPixelFormat::RGBA16Float),
         TextureFormat::RGBA32F => descriptor.set_pixel_format(MTLPixelFormat::RGBA32Float),
     }
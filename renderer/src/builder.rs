@@ -18,10 +18,11 @@ use crate::gpu_data::{DrawTileBatch, DrawTileBatchD3D9, DrawTileBatchD3D11, Fill
 use crate::gpu_data::{PathBatchIndex, PathSource, PrepareTilesInfoD3D11, PropagateMetadataD3D11};
 use crate::gpu_data::{RenderCommand, SegmentIndicesD3D11, SegmentsD3D11, TileBatchDataD3D11};
 use crate::gpu_data::{TileBatchId, TileBatchTexture, TileObjectPrimitive, TilePathInfoD3D11};
-use crate::options::{PrepareMode, PreparedBuildOptions, PreparedRenderTransform};
+use crate::options::{AntialiasingQuality, PrepareMode, PreparedBuildOptions};
+use crate::options::PreparedRenderTransform;
 use crate::paint::{PaintId, PaintInfo, PaintMetadata};
 use crate::scene::{ClipPathId, DisplayItem, DrawPath, DrawPathId, LastSceneInfo, PathId};
-use crate::scene::{Scene, SceneSink};
+use crate::scene::{Scene, SceneDiff, SceneId, SceneSink};
 use crate::tile_map::DenseTileMap;
 use crate::tiler::Tiler;
 use crate::tiles::{self, DrawTilingPathInfo, TILE_HEIGHT, TILE_WIDTH, TilingPathInfo};
@@ -33,10 +34,11 @@ use pathfinder_content::outline::{Outline, PointFlags};
 use pathfinder_geometry::line_segment::{LineSegment2F, LineSegmentU16};
 use pathfinder_geometry::rect::{RectF, RectI};
 use pathfinder_geometry::transform2d::Transform2F;
-use pathfinder_geometry::vector::{Vector2I, vec2i};
+use pathfinder_geometry::vector::{Vector2F, Vector2I, vec2i};
 use pathfinder_gpu::TextureSamplingFlags;
 use pathfinder_simd::default::F32x4;
 use std::borrow::Cow;
+use std::mem;
 use std::ops::Range;
 use std::sync::atomic::AtomicUsize;
 use std::u32;
@@ -51,7 +53,7 @@ const MAX_CLIP_BATCHES: u32 = 32;
 
 pub(crate) struct SceneBuilder<'a, 'b, 'c, 'd> {
     pub(crate) scene: &'a mut Scene,
-    built_options: &'b PreparedBuildOptions,
+    pub(crate) built_options: &'b PreparedBuildOptions,
     next_alpha_tile_indices: [AtomicUsize; ALPHA_TILE_LEVEL_COUNT],
     pub(crate) sink: &'c mut SceneSink<'d>,
 }
@@ -127,6 +129,45 @@ pub(crate) struct BuiltPathTransformCPUBinGPUData {
     pub outline: Outline,
 }
 
+/// The tiles that were built on CPU for each draw path in the previous frame, kept so that paths
+/// that haven't changed don't need to be tiled again.
+pub(crate) struct RetainedTiles {
+    scene_id: SceneId,
+    key: RetainedTilesKey,
+    draw_paths: Vec<Option<RetainedDrawPath>>,
+}
+
+// Everything besides the path itself that tiling depends on.
+#[derive(Clone, Copy, PartialEq)]
+struct RetainedTilesKey {
+    transform: Transform2F,
+    view_box: RectF,
+    dilation: Vector2F,
+    subpixel_aa_enabled: bool,
+    antialiasing_disabled: bool,
+    antialiasing_quality: AntialiasingQuality,
+}
+
+#[derive(Clone)]
+struct RetainedDrawPath {
+    path: BuiltPath,
+    fills: Vec<Fill>,
+}
+
+impl RetainedTiles {
+    pub(crate) fn retain_unchanged_paths(&mut self, diff: &SceneDiff, new_scene_id: SceneId) {
+        let mut old_draw_paths = mem::replace(&mut self.draw_paths, vec![]);
+        self.draw_paths = diff.unchanged_draw_paths.iter().enumerate().map(|(index, &unchanged)| {
+            if unchanged {
+                old_draw_paths.get_mut(index).and_then(|draw_path| draw_path.take())
+            } else {
+                None
+            }
+        }).collect();
+        self.scene_id = new_scene_id;
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct Occluder {
     pub(crate) coords: Vector2I,
@@ -237,7 +278,19 @@ impl<'a, 'b, 'c, 'd> SceneBuilder<'a, 'b, 'c, 'd> {
             })
         });
 
+        let retained_tiles_key = self.retained_tiles_key(prepare_mode, effective_view_box);
+        let retained_tiles = match (retained_tiles_key, self.sink.retained_tiles.take()) {
+            (Some(key), Some(retained_tiles)) if retained_tiles.scene_id == self.scene.id() &&
+                    retained_tiles.key == key &&
+                    retained_tiles.draw_paths.len() == draw_path_count => Some(retained_tiles),
+            _ => None,
+        };
+        let retain = self.sink.retain_tiles && retained_tiles_key.is_some();
+
         let built_draw_paths = executor.build_vector(draw_path_count, |path_index| {
+            let retained_draw_path = retained_tiles.as_ref().and_then(|retained_tiles| {
+                retained_tiles.draw_paths[path_index].as_ref()
+            });
             self.build_draw_path_on_cpu(DrawPathBuildParams {
                 path_build_params: PathBuildParams {
                     path_id: PathId(path_index as u32),
@@ -248,12 +301,43 @@ impl<'a, 'b, 'c, 'd> SceneBuilder<'a, 'b, 'c, 'd> {
                 },
                 paint_metadata: &paint_metadata,
                 built_clip_paths: &built_clip_paths,
+                retained_draw_path,
+                retain,
             })
         });
 
+        let (built_draw_paths, retained_draw_paths): (Vec<_>, Vec<_>) =
+            built_draw_paths.into_iter().unzip();
+        if let (true, Some(key)) = (retain, retained_tiles_key) {
+            self.sink.retained_tiles = Some(RetainedTiles {
+                scene_id: self.scene.id(),
+                key,
+                draw_paths: retained_draw_paths,
+            });
+        }
+
         BuiltPaths { draw: built_draw_paths }
     }
 
+    // Tiles can only be carried over from frame to frame if they're built entirely on CPU with a
+    // 2D transform.
+    fn retained_tiles_key(&self, prepare_mode: &PrepareMode, effective_view_box: RectF)
+                          -> Option<RetainedTilesKey> {
+        let transform = match (prepare_mode, &self.built_options.transform) {
+            (&PrepareMode::CPU, &PreparedRenderTransform::None) => Transform2F::default(),
+            (&PrepareMode::CPU, &PreparedRenderTransform::Transform2D(transform)) => transform,
+            _ => return None,
+        };
+        Some(RetainedTilesKey {
+            transform,
+            view_box: effective_view_box,
+            dilation: self.built_options.dilation,
+            subpixel_aa_enabled: self.built_options.subpixel_aa_enabled,
+            antialiasing_disabled: self.built_options.antialiasing_disabled,
+            antialiasing_quality: self.built_options.antialiasing_quality,
+        })
+    }
+
     fn build_clip_path_on_cpu(&self, params: PathBuildParams) -> BuiltPath {
         let PathBuildParams { path_id, view_box, built_options, scene, prepare_mode } = params;
         let path_object = &scene.get_clip_path(path_id.to_clip_path_id());
@@ -274,7 +358,8 @@ impl<'a, 'b, 'c, 'd> SceneBuilder<'a, 'b, 'c, 'd> {
         tiler.object_builder.built_path
     }
 
-    fn build_draw_path_on_cpu(&self, params: DrawPathBuildParams) -> BuiltDrawPath {
+    fn build_draw_path_on_cpu(&self, params: DrawPathBuildParams)
+                              -> (BuiltDrawPath, Option<RetainedDrawPath>) {
         let DrawPathBuildParams {
             path_build_params: PathBuildParams {
                 path_id,
@@ -285,14 +370,24 @@ impl<'a, 'b, 'c, 'd> SceneBuilder<'a, 'b, 'c, 'd> {
             },
             paint_metadata,
             built_clip_paths,
+            retained_draw_path,
+            retain,
         } = params;
 
         let path_object = scene.get_draw_path(path_id.to_draw_path_id());
-        let outline = scene.apply_render_options(path_object.outline(), built_options);
 
         let paint_id = path_object.paint();
         let paint_metadata = &paint_metadata[paint_id.0 as usize];
 
+        if let Some(retained_draw_path) = retained_draw_path {
+            let built_path = self.reuse_retained_draw_path(retained_draw_path);
+            let retained_draw_path = if retain { Some((*retained_draw_path).clone()) } else { None };
+            return (BuiltDrawPath::new(built_path, path_object, paint_metadata),
+                    retained_draw_path);
+        }
+
+        let outline = scene.apply_render_options(path_object.outline(), built_options);
+
         let mut tiler = Tiler::new(self,
                                    path_id,
                                    &outline,
@@ -305,12 +400,49 @@ impl<'a, 'b, 'c, 'd> SceneBuilder<'a, 'b, 'c, 'd> {
             paint_id,
             blend_mode: path_object.blend_mode(),
             fill_rule: path_object.fill_rule(),
+            aliased: built_options.antialiasing_disabled,
         }));
 
         tiler.generate_tiles();
+
+        // Clipped paths share alpha tiles with their clip paths, so they can't be carried over.
+        let retained_draw_path = if retain && path_object.clip_path().is_none() {
+            Some(RetainedDrawPath {
+                path: tiler.object_builder.built_path.clone(),
+                fills: tiler.object_builder.fills.clone(),
+            })
+        } else {
+            None
+        };
+
         self.send_fills(tiler.object_builder.fills);
 
-        BuiltDrawPath::new(tiler.object_builder.built_path, path_object, paint_metadata)
+        (BuiltDrawPath::new(tiler.object_builder.built_path, path_object, paint_metadata),
+         retained_draw_path)
+    }
+
+    // Alpha tiles are allocated anew each frame, so the retained tiles are renumbered and their
+    // fills relinked to match.
+    fn reuse_retained_draw_path(&self, retained_draw_path: &RetainedDrawPath) -> BuiltPath {
+        let mut built_path = retained_draw_path.path.clone();
+        let mut fills = retained_draw_path.fills.clone();
+
+        let mut alpha_tile_mapping = FxHashMap::default();
+        if let BuiltPathData::CPU(ref mut cpu_data) = built_path.data {
+            for tile in &mut cpu_data.tiles.data {
+                if tile.alpha_tile_id.is_valid() {
+                    let alpha_tile_id = AlphaTileId::new(&self.next_alpha_tile_indices, 0);
+                    alpha_tile_mapping.insert(tile.alpha_tile_id.0, alpha_tile_id.0);
+                    tile.alpha_tile_id = alpha_tile_id;
+                }
+            }
+        }
+        for fill in &mut fills {
+            fill.link = alpha_tile_mapping[&fill.link];
+        }
+
+        self.send_fills(fills);
+        built_path
     }
 
     fn send_fills(&self, fills: Vec<Fill>) {
@@ -403,6 +535,9 @@ struct DrawPathBuildParams<'a> {
     path_build_params: PathBuildParams<'a>,
     paint_metadata: &'a [PaintMetadata],
     built_clip_paths: &'a [BuiltPath],
+    retained_draw_path: Option<&'a RetainedDrawPath>,
+    // Whether to return a copy of the tiles so that they can be reused next frame.
+    retain: bool,
 }
 
 impl BuiltPath {
@@ -1061,6 +1196,7 @@ impl TileBatchBuilder {
                                             paint_id,
                                             blend_mode: draw_path.blend_mode(),
                                             fill_rule: draw_path.fill_rule(),
+                                            aliased: built_options.antialiasing_disabled,
                                         }));
         Some(BuiltDrawPath::new(built_path, draw_path, paint_metadata))
     }
@@ -77,6 +77,19 @@ impl SceneProxy {
         self.sender.send(MainToWorkerMsg::ReplaceScene(new_scene)).unwrap();
     }
 
+    /// Replaces the wrapped scene with a newer version of it, keeping the tiling work for every
+    /// path that hasn't changed.
+    ///
+    /// Paths are matched up with those of the old scene by ID, so this is most effective when a
+    /// small edit is made to a large scene. See `Scene::diff()`. Once this method has been called,
+    /// the proxy holds on to the tiles of each frame so that they can be reused by the next one.
+    /// Tiles are only reused when scenes are built on CPU; that is, when the renderer level is
+    /// D3D9.
+    #[inline]
+    pub fn update_scene(&self, new_scene: Scene) {
+        self.sender.send(MainToWorkerMsg::UpdateScene(new_scene)).unwrap();
+    }
+
     /// Sets the view box of the scene, which defines the visible rectangle.
     #[inline]
     pub fn set_view_box(&self, new_view_box: RectF) {
@@ -92,8 +105,17 @@ impl SceneProxy {
     /// Sends all queued commands to the given renderer to render the wrapped scene.
     #[inline]
     pub fn render<D>(&mut self, renderer: &mut Renderer<D>) where D: Device {
+        self.render_with_callback(renderer, |_| {})
+    }
+
+    /// Like `render()`, but also passes each command to the given callback before rendering it.
+    ///
+    /// This is useful to inspect the commands that a scene produces, for debugging.
+    pub fn render_with_callback<D, F>(&mut self, renderer: &mut Renderer<D>, mut callback: F)
+                                      where D: Device, F: FnMut(&RenderCommand) {
         renderer.begin_scene();
         while let Ok(command) = self.receiver.recv() {
+            callback(&command);
             renderer.render_command(&command);
             match command {
                 RenderCommand::Finish { .. } => break,
@@ -135,7 +157,14 @@ fn scene_thread<E>(mut scene: Scene,
                    where E: Executor {
     while let Ok(msg) = main_to_worker_receiver.recv() {
         match msg {
-            MainToWorkerMsg::ReplaceScene(new_scene) => scene = new_scene,
+            MainToWorkerMsg::ReplaceScene(new_scene) => {
+                sink.retained_tiles = None;
+                scene = new_scene;
+            }
+            MainToWorkerMsg::UpdateScene(new_scene) => {
+                sink.update_scene(&scene.diff(&new_scene), new_scene.id());
+                scene = new_scene;
+            }
             MainToWorkerMsg::CopyScene(sender) => sender.send(scene.clone()).unwrap(),
             MainToWorkerMsg::SetViewBox(new_view_box) => scene.set_view_box(new_view_box),
             MainToWorkerMsg::Build(options) => scene.build(options, &mut sink, &executor),
@@ -145,6 +174,7 @@ fn scene_thread<E>(mut scene: Scene,
 
 enum MainToWorkerMsg {
     ReplaceScene(Scene),
+    UpdateScene(Scene),
     CopyScene(Sender<Scene>),
     SetViewBox(RectF),
     Build(BuildOptions),
@@ -108,7 +108,7 @@ impl<D> RendererD3D11<D> where D: Device {
 
         core.stats.drawcall_count += 1;
         core.finish_timing_draw_call(&timer_query);
-        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Other, timer_query);
+        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Tile, timer_query);
 
         core.allocator.free_general_buffer(path_info_buffer_id);
     }
@@ -629,7 +629,7 @@ impl<D> RendererD3D11<D> where D: Device {
 
         core.stats.drawcall_count += 1;
         core.finish_timing_draw_call(&timer_query);
-        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Other, timer_query);
+        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Tile, timer_query);
 
         let fill_indirect_draw_params_receiver =
             core.device.read_buffer(&z_buffer, BufferTarget::Storage, 0..32);
@@ -683,7 +683,7 @@ impl<D> RendererD3D11<D> where D: Device {
 
         core.stats.drawcall_count += 1;
         core.finish_timing_draw_call(&timer_query);
-        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Other, timer_query);
+        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Tile, timer_query);
     }
 
     fn allocate_first_tile_map(&mut self, core: &mut RendererCore<D>) -> GeneralBufferID {
@@ -331,7 +331,7 @@ impl<D> RendererD3D9<D> where D: Device {
 
         core.stats.drawcall_count += 1;
         core.finish_timing_draw_call(&timer_query);
-        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Other, timer_query);
+        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Tile, timer_query);
         let timer_query = core.timer_query_cache.start_timing_draw_call(&core.device,
                                                                         &core.options);
 
@@ -357,7 +357,7 @@ impl<D> RendererD3D9<D> where D: Device {
 
         core.stats.drawcall_count += 1;
         core.finish_timing_draw_call(&timer_query);
-        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Other, timer_query);
+        core.current_timer.as_mut().unwrap().push_query(TimeCategory::Tile, timer_query);
 
         core.allocator.free_framebuffer(mask_temp_framebuffer_id);
     }
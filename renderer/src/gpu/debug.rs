@@ -22,7 +22,8 @@ use pathfinder_geometry::vector::{Vector2I, vec2i};
 use pathfinder_gpu::Device;
 use pathfinder_gpu::allocator::GPUMemoryAllocator;
 use pathfinder_resources::ResourceLoader;
-use pathfinder_ui::{FONT_ASCENT, LINE_HEIGHT, PADDING, UIPresenter, WINDOW_COLOR};
+use pathfinder_ui::{FONT_ASCENT, LINE_HEIGHT, PADDING, TEXT_COLOR, UIPresenter, WINDOW_COLOR};
+use std::cmp;
 use std::collections::VecDeque;
 use std::ops::{Add, Div};
 use std::time::Duration;
@@ -33,12 +34,24 @@ const STATS_WINDOW_WIDTH: i32 = 275;
 const STATS_WINDOW_HEIGHT: i32 = LINE_HEIGHT * 4 + PADDING + 2;
 
 const PERFORMANCE_WINDOW_WIDTH: i32 = 400;
-const PERFORMANCE_WINDOW_HEIGHT_D3D9: i32 = LINE_HEIGHT * 8 + PADDING + 2;
-const PERFORMANCE_WINDOW_HEIGHT_D3D11: i32 = LINE_HEIGHT * 10 + PADDING + 2;
+const PERFORMANCE_WINDOW_HEIGHT_D3D9: i32 = LINE_HEIGHT * 9 + PADDING + 2;
+const PERFORMANCE_WINDOW_HEIGHT_D3D11: i32 = LINE_HEIGHT * 11 + PADDING + 2;
 
 const INFO_WINDOW_WIDTH: i32 = 425;
 const INFO_WINDOW_HEIGHT: i32 = LINE_HEIGHT * 2 + PADDING + 2;
 
+// The number of frames shown in the frame time graph.
+const FRAME_TIME_HISTORY_LENGTH: usize = 120;
+const FRAME_TIME_GRAPH_SAMPLE_WIDTH: i32 = 2;
+const FRAME_TIME_GRAPH_HEIGHT: i32 = 80;
+// The frame time graph is never scaled to less than this, so that steady frame times don't look
+// like noise.
+const MIN_FRAME_TIME_GRAPH_SCALE_MS: f64 = 1000.0 / 60.0;
+
+const FRAME_TIME_WINDOW_WIDTH: i32 =
+    FRAME_TIME_HISTORY_LENGTH as i32 * FRAME_TIME_GRAPH_SAMPLE_WIDTH + PADDING * 2;
+const FRAME_TIME_WINDOW_HEIGHT: i32 = LINE_HEIGHT + FRAME_TIME_GRAPH_HEIGHT + PADDING * 2;
+
 /// Manages the debug UI.
 pub struct DebugUIPresenter<D> where D: Device {
     /// The general UI presenter object.
@@ -48,6 +61,9 @@ pub struct DebugUIPresenter<D> where D: Device {
 
     cpu_samples: SampleBuffer<RenderStats>,
     gpu_samples: SampleBuffer<RenderTime>,
+    // The CPU build time plus the GPU rendering time of recent frames, in milliseconds, oldest
+    // first.
+    frame_times: VecDeque<f64>,
     backend_name: &'static str,
     device_name: String,
     renderer_level: RendererLevel,
@@ -64,6 +80,7 @@ impl<D> DebugUIPresenter<D> where D: Device {
             ui_presenter,
             cpu_samples: SampleBuffer::new(),
             gpu_samples: SampleBuffer::new(),
+            frame_times: VecDeque::with_capacity(FRAME_TIME_HISTORY_LENGTH),
             backend_name: device.backend_name(),
             device_name: device.device_name(),
             renderer_level,
@@ -71,11 +88,18 @@ impl<D> DebugUIPresenter<D> where D: Device {
     }
 
     pub(crate) fn add_sample(&mut self, stats: RenderStats, rendering_time: RenderTime) {
+        let frame_time = duration_to_ms(stats.cpu_build_time + rendering_time.total_time());
+        if self.frame_times.len() == FRAME_TIME_HISTORY_LENGTH {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+
         self.cpu_samples.push(stats);
         self.gpu_samples.push(rendering_time);
     }
 
     pub(crate) fn draw(&self, device: &D, allocator: &mut GPUMemoryAllocator<D>) {
+        self.draw_frame_time_window(device, allocator);
         self.draw_stats_window(device, allocator);
         self.draw_performance_window(device, allocator);
         self.draw_info_window(device, allocator);
@@ -124,6 +148,61 @@ impl<D> DebugUIPresenter<D> where D: Device {
         }
     }
 
+    fn draw_frame_time_window(&self, device: &D, allocator: &mut GPUMemoryAllocator<D>) {
+        let performance_window_height = self.performance_window_size().y();
+
+        let framebuffer_size = self.ui_presenter.framebuffer_size();
+        let bottom = framebuffer_size.y() - PADDING;
+        let window_rect = RectI::new(
+            vec2i(framebuffer_size.x() - PADDING - FRAME_TIME_WINDOW_WIDTH,
+                  bottom -
+                    PADDING -
+                    INFO_WINDOW_HEIGHT -
+                    performance_window_height -
+                    PADDING -
+                    STATS_WINDOW_HEIGHT -
+                    PADDING -
+                    FRAME_TIME_WINDOW_HEIGHT),
+            vec2i(FRAME_TIME_WINDOW_WIDTH, FRAME_TIME_WINDOW_HEIGHT));
+
+        self.ui_presenter.draw_solid_rounded_rect(device, allocator, window_rect, WINDOW_COLOR);
+
+        let max_frame_time = self.frame_times
+                                 .iter()
+                                 .cloned()
+                                 .fold(MIN_FRAME_TIME_GRAPH_SCALE_MS, f64::max);
+        let origin = window_rect.origin() + vec2i(PADDING, PADDING + FONT_ASCENT);
+        self.ui_presenter.draw_text(
+            device,
+            allocator,
+            &format!("Frame: {:.1} ms max", max_frame_time),
+            origin,
+            false,
+        );
+
+        // Draw the graph as one vertical span per frame, reaching from the previous frame's time
+        // to this one's so that the spans join up into a line. The newest frame is on the right.
+        let graph_origin = window_rect.origin() + vec2i(PADDING, PADDING + LINE_HEIGHT);
+        let first_x = graph_origin.x() + (FRAME_TIME_HISTORY_LENGTH -
+                                          self.frame_times.len()) as i32 *
+                                         FRAME_TIME_GRAPH_SAMPLE_WIDTH;
+        let mut spans = Vec::with_capacity(self.frame_times.len());
+        let mut last_y = None;
+        for (frame_index, &frame_time) in self.frame_times.iter().enumerate() {
+            let height = (frame_time / max_frame_time * FRAME_TIME_GRAPH_HEIGHT as f64) as i32;
+            let y = graph_origin.y() + FRAME_TIME_GRAPH_HEIGHT - height;
+            let (top, bottom) = match last_y {
+                None => (y, y),
+                Some(last_y) => (cmp::min(last_y, y), cmp::max(last_y, y)),
+            };
+            let x = first_x + frame_index as i32 * FRAME_TIME_GRAPH_SAMPLE_WIDTH;
+            spans.push(RectI::from_points(vec2i(x, top),
+                                          vec2i(x + FRAME_TIME_GRAPH_SAMPLE_WIDTH, bottom + 1)));
+            last_y = Some(y);
+        }
+        self.ui_presenter.draw_solid_rects(device, allocator, &spans, TEXT_COLOR);
+    }
+
     fn draw_stats_window(&self, device: &D, allocator: &mut GPUMemoryAllocator<D>) {
         let performance_window_height = self.performance_window_size().y();
 
@@ -247,6 +326,14 @@ impl<D> DebugUIPresenter<D> where D: Device {
             }
             RendererLevel::D3D9 => {}
         }
+        self.ui_presenter.draw_text(
+            device,
+            allocator,
+            &format!("GPU Tile: {:.3} ms", duration_to_ms(mean_gpu_sample.tile_time)),
+            origin + vec2i(0, current_y),
+            false,
+        );
+        current_y += LINE_HEIGHT;
         self.ui_presenter.draw_text(
             device,
             allocator,
@@ -285,6 +372,7 @@ impl<D> DebugUIPresenter<D> where D: Device {
         wallclock_time += duration_to_ms(mean_gpu_sample.composite_time) +
                           duration_to_ms(mean_gpu_sample.dice_time) +
                           duration_to_ms(mean_gpu_sample.bin_time) +
+                          duration_to_ms(mean_gpu_sample.tile_time) +
                           duration_to_ms(mean_gpu_sample.other_time);
         self.ui_presenter.draw_text(
             device,
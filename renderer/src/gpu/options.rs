@@ -30,6 +30,12 @@ pub struct RendererOptions<D> where D: Device {
     pub background_color: Option<ColorF>,
     /// Whether to display the debug UI.
     pub show_debug_ui: bool,
+    /// The exponent that the coverage of each pixel is raised to before it's used as alpha.
+    ///
+    /// The default of 1.0 maps coverage to alpha linearly. Other values can help to match the
+    /// antialiasing of other rasterizers; values above 1.0 make edges lighter and thinner, and
+    /// values below 1.0 make them heavier. This must be positive.
+    pub coverage_gamma: f32,
 }
 
 /// The GPU API level that Pathfinder will use.
@@ -60,6 +66,7 @@ impl<D> Default for RendererOptions<D> where D: Device {
             dest: DestFramebuffer::default(),
             background_color: None,
             show_debug_ui: false,
+            coverage_gamma: 1.0,
         }
     }
 }
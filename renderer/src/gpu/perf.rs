@@ -82,6 +82,7 @@ pub(crate) struct TimerQueryCache<D> where D: Device {
 pub(crate) struct PendingTimer<D> where D: Device {
     pub(crate) dice_times: Vec<TimerFuture<D>>,
     pub(crate) bin_times: Vec<TimerFuture<D>>,
+    pub(crate) tile_times: Vec<TimerFuture<D>>,
     pub(crate) fill_times: Vec<TimerFuture<D>>,
     pub(crate) composite_times: Vec<TimerFuture<D>>,
     pub(crate) other_times: Vec<TimerFuture<D>>,
@@ -96,6 +97,7 @@ pub(crate) enum TimerFuture<D> where D: Device {
 pub(crate) enum TimeCategory {
     Dice,
     Bin,
+    Tile,
     Fill,
     Composite,
     Other,
@@ -131,6 +133,7 @@ impl<D> PendingTimer<D> where D: Device {
         PendingTimer {
             dice_times: vec![],
             bin_times: vec![],
+            tile_times: vec![],
             fill_times: vec![],
             composite_times: vec![],
             other_times: vec![],
@@ -140,6 +143,7 @@ impl<D> PendingTimer<D> where D: Device {
     pub(crate) fn poll(&mut self, device: &D) -> Vec<D::TimerQuery> {
         let mut old_queries = vec![];
         for future in self.dice_times.iter_mut().chain(self.bin_times.iter_mut())
+                                                .chain(self.tile_times.iter_mut())
                                                 .chain(self.fill_times.iter_mut())
                                                 .chain(self.composite_times.iter_mut())
                                                 .chain(self.other_times.iter_mut()) {
@@ -153,16 +157,25 @@ impl<D> PendingTimer<D> where D: Device {
     pub(crate) fn total_time(&self) -> Option<RenderTime> {
         let dice_time = total_time_of_timer_futures(&self.dice_times);
         let bin_time = total_time_of_timer_futures(&self.bin_times);
+        let tile_time = total_time_of_timer_futures(&self.tile_times);
         let fill_time = total_time_of_timer_futures(&self.fill_times);
         let composite_time = total_time_of_timer_futures(&self.composite_times);
         let other_time = total_time_of_timer_futures(&self.other_times);
-        match (dice_time, bin_time, fill_time, composite_time, other_time) {
+        match (dice_time, bin_time, tile_time, fill_time, composite_time, other_time) {
             (Some(dice_time),
              Some(bin_time),
+             Some(tile_time),
              Some(fill_time),
              Some(composite_time),
              Some(other_time)) => {
-                Some(RenderTime { dice_time, bin_time, fill_time, composite_time, other_time })
+                Some(RenderTime {
+                    dice_time,
+                    bin_time,
+                    tile_time,
+                    fill_time,
+                    composite_time,
+                    other_time,
+                })
             }
             _ => None,
         }
@@ -178,6 +191,7 @@ impl<D> PendingTimer<D> where D: Device {
         match time_category {
             TimeCategory::Dice => self.dice_times.push(timer_future),
             TimeCategory::Bin => self.bin_times.push(timer_future),
+            TimeCategory::Tile => self.tile_times.push(timer_future),
             TimeCategory::Fill => self.fill_times.push(timer_future),
             TimeCategory::Composite => self.composite_times.push(timer_future),
             TimeCategory::Other => self.other_times.push(timer_future),
@@ -229,12 +243,18 @@ pub struct RenderTime {
     /// 
     /// This will be zero in the D3D9-level backend, since in that backend binning is done on CPU.
     pub bin_time: Duration,
+    /// How much GPU time it took to prepare tiles for filling and compositing.
+    ///
+    /// In the D3D11-level backend, this is computing path bounds, propagating backdrops, and
+    /// sorting tiles. In the D3D9-level backend, it's the passes that copy and combine clip tiles
+    /// in the mask framebuffer.
+    pub tile_time: Duration,
     /// How much GPU time it took to draw fills (i.e. render edges) to masks.
     pub fill_time: Duration,
     /// How much GPU time it took to draw the contents of the tiles to the output.
     pub composite_time: Duration,
     /// How much GPU time it took to execute miscellaneous tasks other than dicing, binning,
-    /// filling, and compositing.
+    /// tile preparation, filling, and compositing.
     pub other_time: Duration,
 }
 
@@ -242,7 +262,8 @@ impl RenderTime {
     /// The total GPU time it took to render the scene.
     #[inline]
     pub fn total_time(&self) -> Duration {
-        self.dice_time + self.bin_time + self.fill_time + self.composite_time + self.other_time
+        self.dice_time + self.bin_time + self.tile_time + self.fill_time + self.composite_time +
+            self.other_time
     }
 }
 
@@ -252,6 +273,7 @@ impl Default for RenderTime {
         RenderTime {
             dice_time: Duration::new(0, 0),
             bin_time: Duration::new(0, 0),
+            tile_time: Duration::new(0, 0),
             fill_time: Duration::new(0, 0),
             composite_time: Duration::new(0, 0),
             other_time: Duration::new(0, 0),
@@ -267,6 +289,7 @@ impl Add<RenderTime> for RenderTime {
         RenderTime {
             dice_time: self.dice_time + other.dice_time,
             bin_time: self.bin_time + other.bin_time,
+            tile_time: self.tile_time + other.tile_time,
             fill_time: self.fill_time + other.fill_time,
             composite_time: self.composite_time + other.composite_time,
             other_time: self.other_time + other.other_time,
@@ -283,6 +306,7 @@ impl Div<usize> for RenderTime {
         RenderTime {
             dice_time: self.dice_time / divisor,
             bin_time: self.bin_time / divisor,
+            tile_time: self.tile_time / divisor,
             fill_time: self.fill_time / divisor,
             composite_time: self.composite_time / divisor,
             other_time: self.other_time / divisor,
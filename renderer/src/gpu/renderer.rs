@@ -808,6 +808,45 @@ impl<D> Renderer<D> where D: Device {
         self.core.preserve_draw_framebuffer();
     }
 
+    /// Copies a texture to the destination framebuffer, stretching it to fill the output
+    /// viewport.
+    ///
+    /// This can be used to redisplay a scene that was previously rendered to an offscreen
+    /// framebuffer without building or rendering it again. If `blend` is true, the texture, which
+    /// must have premultiplied alpha, is composited over the existing contents of the destination
+    /// instead of replacing them. This must not be called between `begin_scene()` and
+    /// `end_scene()`.
+    pub fn blit_texture_to_dest(&mut self, texture: &D::Texture, blend: bool) {
+        let main_viewport = self.core.main_viewport();
+        let target = match self.core.options.dest {
+            DestFramebuffer::Default { .. } => RenderTarget::Default,
+            DestFramebuffer::Other(ref framebuffer) => RenderTarget::Framebuffer(framebuffer),
+        };
+
+        self.core.device.draw_elements(6, &RenderState {
+            target: &target,
+            program: &self.blit_program.program,
+            vertex_array: &self.frame.blit_vertex_array.vertex_array,
+            primitive: Primitive::Triangles,
+            textures: &[(&self.blit_program.src_texture, texture)],
+            images: &[],
+            storage_buffers: &[],
+            uniforms: &[
+                (&self.blit_program.framebuffer_size_uniform,
+                 UniformData::Vec2(main_viewport.size().to_f32().0)),
+                (&self.blit_program.dest_rect_uniform,
+                 UniformData::Vec4(RectF::new(Vector2F::zero(), main_viewport.size().to_f32()).0)),
+            ],
+            viewport: main_viewport,
+            options: RenderOptions {
+                blend: if blend { BlendMode::SrcOver.to_blend_state() } else { None },
+                ..RenderOptions::default()
+            },
+        });
+
+        self.core.stats.drawcall_count += 1;
+    }
+
     fn push_render_target(&mut self, render_target_id: RenderTargetId) {
         self.core.render_target_stack.push(render_target_id);
     }
@@ -1094,6 +1133,8 @@ impl<D> RendererCore<D> where D: Device {
                        UniformData::Vec2(F32x2::new(TILE_WIDTH as f32, TILE_HEIGHT as f32))));
         uniforms.push((&tile_program.framebuffer_size_uniform,
                        UniformData::Vec2(draw_viewport.size().to_f32().0)));
+        uniforms.push((&tile_program.coverage_gamma_uniform,
+                       UniformData::Float(self.options.coverage_gamma)));
         uniforms.push((&tile_program.texture_metadata_size_uniform,
                        UniformData::IVec2(I32x2::new(TEXTURE_METADATA_TEXTURE_WIDTH,
                                                      TEXTURE_METADATA_TEXTURE_HEIGHT))));
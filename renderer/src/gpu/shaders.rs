@@ -151,6 +151,7 @@ pub(crate) struct TileProgramCommon<D> where D: Device {
     pub(crate) mask_texture_size_0_uniform: D::Uniform,
     pub(crate) gamma_lut_texture: D::TextureParameter,
     pub(crate) framebuffer_size_uniform: D::Uniform,
+    pub(crate) coverage_gamma_uniform: D::Uniform,
 }
 
 impl<D> TileProgramCommon<D> where D: Device {
@@ -166,6 +167,7 @@ impl<D> TileProgramCommon<D> where D: Device {
         let mask_texture_size_0_uniform = device.get_uniform(&program, "MaskTextureSize0");
         let gamma_lut_texture = device.get_texture_parameter(&program, "GammaLUT");
         let framebuffer_size_uniform = device.get_uniform(&program, "FramebufferSize");
+        let coverage_gamma_uniform = device.get_uniform(&program, "CoverageGamma");
 
         TileProgramCommon {
             program,
@@ -180,6 +182,7 @@ impl<D> TileProgramCommon<D> where D: Device {
             mask_texture_size_0_uniform,
             gamma_lut_texture,
             framebuffer_size_uniform,
+            coverage_gamma_uniform,
         }
     }
 }
@@ -31,6 +31,7 @@ use std::u32;
 
 pub const TILE_CTRL_MASK_WINDING:  i32 = 0x1;
 pub const TILE_CTRL_MASK_EVEN_ODD: i32 = 0x2;
+pub const TILE_CTRL_MASK_ALIASED:  i32 = 0x4;
 
 pub const TILE_CTRL_MASK_0_SHIFT:  i32 = 0;
 
@@ -29,3 +29,5 @@ mod gpu_data;
 mod tile_map;
 mod tiler;
 mod tiles;
+
+pub use crate::tiles::{TILE_HEIGHT, TILE_WIDTH};
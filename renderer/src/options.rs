@@ -58,6 +58,14 @@ pub struct BuildOptions {
     pub dilation: Vector2F,
     /// True if subpixel antialiasing for LCD screens is to be performed.
     pub subpixel_aa_enabled: bool,
+    /// True if path edges should be hard rather than antialiased.
+    ///
+    /// The fractional coverage computed by the fill stage is snapped to 0 or 1 when the mask is
+    /// sampled during tile compositing, so each pixel is either fully inside or fully outside the
+    /// path. Clip paths are still antialiased.
+    pub antialiasing_disabled: bool,
+    /// How accurately the coverage of path edges is computed. Lower levels build faster.
+    pub antialiasing_quality: AntialiasingQuality,
 }
 
 impl BuildOptions {
@@ -66,6 +74,48 @@ impl BuildOptions {
             transform: self.transform.prepare(bounds),
             dilation: self.dilation,
             subpixel_aa_enabled: self.subpixel_aa_enabled,
+            antialiasing_disabled: self.antialiasing_disabled,
+            antialiasing_quality: self.antialiasing_quality,
+        }
+    }
+}
+
+/// How accurately path coverage is computed, trading accuracy for speed.
+///
+/// Curves are flattened into line segments before their coverage is computed, and each level
+/// allows those segments to stray further from the true curve. The number of segments, and with
+/// it the number of fills that the GPU has to rasterize, roughly halves with each step down.
+///
+/// This only affects the D3D9 level, which flattens curves on the CPU. The D3D11 level dices
+/// curves on the GPU at a fixed accuracy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AntialiasingQuality {
+    /// Segments stray by up to 1/4 of a device pixel, which is indistinguishable from the exact
+    /// curve. This is the default.
+    High,
+    /// Segments stray by up to 1 device pixel. Large curves look the same, but tight ones, such as
+    /// those of small text, can look slightly faceted.
+    Medium,
+    /// Segments stray by up to 4 device pixels. Curves visibly become polygons, but scenes dense
+    /// with curves build and render fastest.
+    Low,
+}
+
+impl Default for AntialiasingQuality {
+    #[inline]
+    fn default() -> AntialiasingQuality {
+        AntialiasingQuality::High
+    }
+}
+
+impl AntialiasingQuality {
+    /// The greatest distance, in device pixels, that a flattened curve may stray from the curve.
+    #[inline]
+    pub fn flattening_tolerance(self) -> f32 {
+        match self {
+            AntialiasingQuality::High => 0.25,
+            AntialiasingQuality::Medium => 1.0,
+            AntialiasingQuality::Low => 4.0,
         }
     }
 }
@@ -143,6 +193,8 @@ pub(crate) struct PreparedBuildOptions {
     pub(crate) transform: PreparedRenderTransform,
     pub(crate) dilation: Vector2F,
     pub(crate) subpixel_aa_enabled: bool,
+    pub(crate) antialiasing_disabled: bool,
+    pub(crate) antialiasing_quality: AntialiasingQuality,
 }
 
 #[derive(Clone, Copy)]
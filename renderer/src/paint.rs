@@ -389,6 +389,26 @@ impl Palette {
         paint_id
     }
 
+    // Replaces every color in the palette, both base colors and gradient stops, with `f(color)`.
+    pub(crate) fn map_colors<F>(&mut self, mut f: F) where F: FnMut(ColorU) -> ColorU {
+        for paint in &mut self.paints {
+            paint.base_color = f(paint.base_color);
+            if let Some(ref mut overlay) = paint.overlay {
+                if let PaintContents::Gradient(ref mut gradient) = overlay.contents {
+                    for stop in gradient.stops_mut() {
+                        stop.color = f(stop.color);
+                    }
+                }
+            }
+        }
+
+        self.cache = self.paints
+                         .iter()
+                         .enumerate()
+                         .map(|(paint_index, paint)| (paint.clone(), PaintId(paint_index as u16)))
+                         .collect();
+    }
+
     pub(crate) fn push_render_target(&mut self, render_target: RenderTarget) -> RenderTargetId {
         let id = self.render_targets.len() as u32;
         self.render_targets.push(render_target);
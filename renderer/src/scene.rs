@@ -10,7 +10,7 @@
 
 //! The vector scene to be rendered.
 
-use crate::builder::SceneBuilder;
+use crate::builder::{RetainedTiles, SceneBuilder};
 use crate::concurrent::executor::Executor;
 use crate::gpu::options::RendererLevel;
 use crate::gpu::renderer::Renderer;
@@ -18,9 +18,12 @@ use crate::gpu_data::RenderCommand;
 use crate::options::{BuildOptions, PreparedBuildOptions};
 use crate::options::{PreparedRenderTransform, RenderCommandListener};
 use crate::paint::{MergedPaletteInfo, Paint, PaintId, PaintInfo, PaintTextureManager, Palette};
+use crate::tiles::{TILE_HEIGHT, TILE_WIDTH};
+use pathfinder_color::ColorU;
 use pathfinder_content::effects::BlendMode;
 use pathfinder_content::fill::FillRule;
-use pathfinder_content::outline::Outline;
+use pathfinder_content::outline::{ContourIterFlags, Outline};
+use pathfinder_content::pattern::PatternSource;
 use pathfinder_content::render_target::RenderTargetId;
 use pathfinder_geometry::rect::RectF;
 use pathfinder_geometry::transform2d::Transform2F;
@@ -224,6 +227,33 @@ impl Scene {
         self.epoch.next();
     }
 
+    /// Returns the total number of line and curve segments in the outlines of the draw paths and
+    /// clip paths in this scene, including the segments that close contours.
+    pub fn segment_count(&self) -> usize {
+        let outlines = self.draw_paths
+                           .iter()
+                           .map(|draw_path| &draw_path.outline)
+                           .chain(self.clip_paths.iter().map(|clip_path| &clip_path.outline));
+        outlines.flat_map(|outline| outline.contours())
+                .map(|contour| contour.iter(ContourIterFlags::empty()).count())
+                .sum()
+    }
+
+    /// Estimates how many tiles rendering this scene untransformed would touch, by adding up the
+    /// tiles that the bounding box of each draw path overlaps within the view box.
+    ///
+    /// Tiles under several paths are counted once per path, and paths that would be culled or
+    /// occluded are counted too, so this overestimates the tiles that are actually rasterized.
+    pub fn approximate_tile_count(&self) -> u64 {
+        let tile_size = vec2f(TILE_WIDTH as f32, TILE_HEIGHT as f32);
+        self.draw_paths.iter().filter_map(|draw_path| {
+            let bounds = draw_path.outline.bounds().intersection(self.view_box)?;
+            let tile_count = ((bounds.lower_right() / tile_size).ceil() -
+                              (bounds.origin() / tile_size).floor()).to_i32();
+            Some(tile_count.x() as u64 * tile_count.y() as u64)
+        }).sum()
+    }
+
     #[allow(deprecated)]
     pub(crate) fn apply_render_options(&self,
                                        original_outline: &Outline,
@@ -335,6 +365,117 @@ impl Scene {
         self.palette.paints.get(paint_id.0 as usize).expect("No paint with that ID!")
     }
 
+    /// Returns the color that all paths in this scene are filled with, if they are all filled with
+    /// the same solid color.
+    pub fn monochrome_color(&self) -> Option<ColorU> {
+        let mut monochrome_color = None;
+        for draw_path in &self.draw_paths {
+            let paint = self.get_paint(draw_path.paint);
+            if !paint.is_color() {
+                return None;
+            }
+            match monochrome_color {
+                None => monochrome_color = Some(paint.base_color()),
+                Some(color) if color == paint.base_color() => {}
+                Some(_) => return None,
+            }
+        }
+        monochrome_color
+    }
+
+    /// Fills every path in this scene with the given paint, except for paths that composite
+    /// render targets, since those carry the contents of other paths.
+    pub fn replace_paints(&mut self, paint: &Paint) {
+        let paint_id = self.palette.push_paint(paint);
+        for draw_path in &mut self.draw_paths {
+            let old_paint = &self.palette.paints[draw_path.paint.0 as usize];
+            let composites_render_target = match old_paint.pattern() {
+                Some(pattern) => match *pattern.source() {
+                    PatternSource::RenderTarget { .. } => true,
+                    PatternSource::Image(_) => false,
+                },
+                None => false,
+            };
+            if !composites_render_target {
+                draw_path.paint = paint_id;
+            }
+        }
+        self.epoch.next();
+    }
+
+    /// Replaces every color that paths are filled with, including the stops of gradients, with
+    /// `f(color)`. Image patterns are left alone.
+    pub fn map_colors<F>(&mut self, f: F) where F: FnMut(ColorU) -> ColorU {
+        self.palette.map_colors(f);
+        self.epoch.next();
+    }
+
+    /// Compares this scene with a newer version of it and reports which paths changed.
+    ///
+    /// Paths are matched up by ID. Bounding boxes are compared first, so that paths that moved are
+    /// rejected cheaply; outlines are only compared point by point if their bounds are equal.
+    pub fn diff(&self, new_scene: &Scene) -> SceneDiff {
+        let mut dirty_rect = None;
+
+        let mut unchanged_draw_paths = Vec::with_capacity(new_scene.draw_paths.len());
+        for (path_index, new_draw_path) in new_scene.draw_paths.iter().enumerate() {
+            let unchanged = match self.draw_paths.get(path_index) {
+                Some(old_draw_path) if self.draw_path_matches(old_draw_path,
+                                                              new_scene,
+                                                              new_draw_path) => true,
+                Some(old_draw_path) => {
+                    union_dirty_rect(&mut dirty_rect, old_draw_path.outline.bounds());
+                    union_dirty_rect(&mut dirty_rect, new_draw_path.outline.bounds());
+                    false
+                }
+                None => {
+                    union_dirty_rect(&mut dirty_rect, new_draw_path.outline.bounds());
+                    false
+                }
+            };
+            unchanged_draw_paths.push(unchanged);
+        }
+        for old_draw_path in self.draw_paths.iter().skip(new_scene.draw_paths.len()) {
+            union_dirty_rect(&mut dirty_rect, old_draw_path.outline.bounds());
+        }
+
+        let clip_path_count = self.clip_paths.len().max(new_scene.clip_paths.len());
+        for path_index in 0..clip_path_count {
+            match (self.clip_paths.get(path_index), new_scene.clip_paths.get(path_index)) {
+                (Some(old_clip_path), Some(new_clip_path)) if
+                        old_clip_path.clip_path == new_clip_path.clip_path &&
+                        old_clip_path.fill_rule == new_clip_path.fill_rule &&
+                        outlines_match(&old_clip_path.outline, &new_clip_path.outline) => {}
+                (old_clip_path, new_clip_path) => {
+                    for clip_path in old_clip_path.into_iter().chain(new_clip_path) {
+                        union_dirty_rect(&mut dirty_rect, clip_path.outline.bounds());
+                    }
+                }
+            }
+        }
+
+        // Moving paths into or out of render targets can change anything.
+        if self.display_list != new_scene.display_list {
+            union_dirty_rect(&mut dirty_rect, self.bounds);
+            union_dirty_rect(&mut dirty_rect, new_scene.bounds);
+        }
+
+        SceneDiff { dirty_rect, unchanged_draw_paths }
+    }
+
+    fn draw_path_matches(&self,
+                         old_draw_path: &DrawPath,
+                         new_scene: &Scene,
+                         new_draw_path: &DrawPath)
+                         -> bool {
+        old_draw_path.paint == new_draw_path.paint &&
+            self.get_paint(old_draw_path.paint) == new_scene.get_paint(new_draw_path.paint) &&
+            old_draw_path.clip_path == new_draw_path.clip_path &&
+            old_draw_path.fill_rule == new_draw_path.fill_rule &&
+            old_draw_path.blend_mode == new_draw_path.blend_mode &&
+            outlines_match(&old_draw_path.outline, &new_draw_path.outline)
+    }
+
     /// Returns the globally-unique ID of the scene.
     #[inline]
     pub fn id(&self) -> SceneId {
@@ -353,12 +494,27 @@ impl Scene {
                                    executor: E)
                                    -> Vec<RenderCommand>
                                    where D: Device, E: Executor {
+        self.build_commands(renderer.mode().level, build_options, executor)
+    }
+
+    /// Builds the scene for a renderer of the given level and returns the resulting commands.
+    ///
+    /// Unlike `build_into_vector()`, this needs no renderer, and therefore no GPU device, so it's
+    /// useful for examining the output of the CPU stages of the pipeline on their own, for example
+    /// in tests. The scene is built synchronously on the calling thread; to build it in the
+    /// background for rendering, use `SceneProxy` instead.
+    pub fn build_commands<E>(&mut self,
+                             level: RendererLevel,
+                             build_options: BuildOptions,
+                             executor: E)
+                             -> Vec<RenderCommand>
+                             where E: Executor {
         let commands = Arc::new(Mutex::new(vec![]));
         let commands_for_listener = commands.clone();
         let listener = RenderCommandListener::new(Box::new(move |command| {
             commands_for_listener.lock().unwrap().push(command)
         }));
-        let mut sink = SceneSink::new(listener, renderer.mode().level);
+        let mut sink = SceneSink::new(listener, level);
         self.build(build_options, &mut sink, &executor);
         let mut commands = commands.lock().unwrap();
         mem::replace(&mut *commands, vec![])
@@ -378,6 +534,40 @@ impl Scene {
     }
 }
 
+fn outlines_match(old_outline: &Outline, new_outline: &Outline) -> bool {
+    old_outline.bounds() == new_outline.bounds() && old_outline == new_outline
+}
+
+fn union_dirty_rect(dirty_rect: &mut Option<RectF>, rect: RectF) {
+    *dirty_rect = Some(match *dirty_rect {
+        None => rect,
+        Some(dirty_rect) => dirty_rect.union_rect(rect),
+    });
+}
+
+/// The differences between two versions of a scene, as computed by `Scene::diff()`.
+#[derive(Clone, Debug)]
+pub struct SceneDiff {
+    dirty_rect: Option<RectF>,
+    pub(crate) unchanged_draw_paths: Vec<bool>,
+}
+
+impl SceneDiff {
+    /// Returns the union of the bounds of every path that was added, removed, or changed, in
+    /// scene coordinates, or `None` if the two scenes draw the same thing.
+    #[inline]
+    pub fn dirty_rect(&self) -> Option<RectF> {
+        self.dirty_rect
+    }
+
+    /// Returns true if the draw path with the given ID in the new scene is identical to the one
+    /// with the same ID in the old scene.
+    #[inline]
+    pub fn draw_path_is_unchanged(&self, draw_path_id: DrawPathId) -> bool {
+        self.unchanged_draw_paths.get(draw_path_id.0 as usize).cloned().unwrap_or(false)
+    }
+}
+
 /// Receives render commands and delivers them to a `RenderCommandListener`.
 ///
 /// Scene sinks wrap render command listeners with cached information about the previous scene.
@@ -386,6 +576,8 @@ pub struct SceneSink<'a> {
     pub(crate) renderer_level: RendererLevel,
     pub(crate) last_scene: Option<LastSceneInfo>,
     pub(crate) paint_texture_manager: PaintTextureManager,
+    pub(crate) retain_tiles: bool,
+    pub(crate) retained_tiles: Option<RetainedTiles>,
 }
 
 pub(crate) struct LastSceneInfo {
@@ -432,6 +624,19 @@ impl<'a> SceneSink<'a> {
             renderer_level,
             last_scene: None,
             paint_texture_manager: PaintTextureManager::new(),
+            retain_tiles: false,
+            retained_tiles: None,
+        }
+    }
+
+    /// Prepares to build a new version of the last scene that was built, keeping the CPU tiling
+    /// work for every path that `diff` reports as unchanged.
+    ///
+    /// After the first call, the sink keeps the tiles of each frame around for the next one.
+    pub(crate) fn update_scene(&mut self, diff: &SceneDiff, new_scene_id: SceneId) {
+        self.retain_tiles = true;
+        if let Some(ref mut retained_tiles) = self.retained_tiles {
+            retained_tiles.retain_unchanged_paths(diff, new_scene_id);
         }
     }
 }
@@ -496,7 +701,7 @@ pub struct RenderTarget {
 }
 
 /// High-level drawing commands.
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum DisplayItem {
     /// Draws paths to the render target on top of the stack.
     DrawPaths(Range<DrawPathId>),
@@ -670,3 +875,161 @@ impl PathId {
         DrawPathId(self.0)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::concurrent::executor::SequentialExecutor;
+    use crate::gpu::options::RendererLevel;
+    use crate::gpu_data::RenderCommand;
+    use crate::options::{BuildOptions, RenderCommandListener};
+    use crate::paint::Paint;
+    use pathfinder_color::rgbu;
+    use pathfinder_content::outline::Outline;
+    use pathfinder_geometry::rect::RectF;
+    use pathfinder_geometry::vector::{Vector2F, vec2f};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use super::{DrawPath, DrawPathId, Scene, SceneSink};
+
+    // A drawn tile: its path, position, backdrop, control byte, and paint, followed by the fills
+    // of its alpha tile. Alpha tile IDs are left out, since they depend on allocation order.
+    type TileSignature = (u32, i16, i16, i8, u8, u16, Vec<(u16, u16, u16, u16)>);
+
+    #[test]
+    fn test_complexity_counts() {
+        let scene = build_scene(&[
+            RectF::new(vec2f(0.0, 0.0), vec2f(32.0, 32.0)),
+            RectF::new(vec2f(8.0, 8.0), vec2f(24.0, 24.0)),
+            RectF::new(vec2f(240.0, 240.0), vec2f(32.0, 32.0)),
+        ]);
+
+        // Each rounded rectangle has four sides and four corners.
+        assert_eq!(scene.segment_count(), 3 * 8);
+
+        // The last rectangle hangs off the view box, so only its first tile counts.
+        assert_eq!(scene.approximate_tile_count(), 4 + 4 + 1);
+    }
+
+    #[test]
+    fn test_build_commands_matches_sink() {
+        let rects = [
+            RectF::new(vec2f(10.5, 10.5), vec2f(100.0, 50.25)),
+            RectF::new(vec2f(120.25, 30.75), vec2f(60.0, 90.0)),
+        ];
+        let commands =
+            build_scene(&rects).build_commands(RendererLevel::D3D9,
+                                               BuildOptions::default(),
+                                               SequentialExecutor);
+        match commands.first() {
+            Some(&RenderCommand::Start { path_count, .. }) => assert_eq!(path_count, 2),
+            _ => panic!("Expected the commands to begin with `Start`!"),
+        }
+        match commands.last() {
+            Some(&RenderCommand::Finish { .. }) => {}
+            _ => panic!("Expected the commands to end with `Finish`!"),
+        }
+
+        let (mut sink, sink_commands) = new_sink();
+        build_scene(&rects).build(BuildOptions::default(), &mut sink, &SequentialExecutor);
+
+        let tiles = tile_signatures(&commands);
+        assert!(!tiles.is_empty());
+        assert_eq!(tiles, tile_signatures(&sink_commands.lock().unwrap()));
+    }
+
+    #[test]
+    fn test_incremental_build_matches_full_build() {
+        let old_rects = [
+            RectF::new(vec2f(10.5, 10.5), vec2f(100.0, 50.25)),
+            RectF::new(vec2f(120.25, 30.75), vec2f(60.0, 90.0)),
+            RectF::new(vec2f(40.0, 150.5), vec2f(170.5, 80.0)),
+        ];
+        let mut new_rects = old_rects;
+        new_rects[1] = RectF::new(vec2f(140.75, 60.5), vec2f(60.0, 90.0));
+
+        let old_scene = build_scene(&old_rects);
+        let mut new_scene = build_scene(&new_rects);
+
+        let diff = old_scene.diff(&new_scene);
+        assert!(diff.draw_path_is_unchanged(DrawPathId(0)));
+        assert!(!diff.draw_path_is_unchanged(DrawPathId(1)));
+        assert!(diff.draw_path_is_unchanged(DrawPathId(2)));
+        assert_eq!(diff.dirty_rect(), Some(old_rects[1].union_rect(new_rects[1])));
+
+        // Build the old scene with tile retention on, as `SceneProxy::update_scene()` does, and
+        // then build the new scene on top of it.
+        let (mut incremental_sink, incremental_commands) = new_sink();
+        let mut old_scene_copy = old_scene.clone();
+        incremental_sink.update_scene(&old_scene.diff(&old_scene_copy), old_scene_copy.id());
+        old_scene_copy.build(BuildOptions::default(), &mut incremental_sink, &SequentialExecutor);
+        incremental_commands.lock().unwrap().clear();
+        incremental_sink.update_scene(&diff, new_scene.id());
+        new_scene.build(BuildOptions::default(), &mut incremental_sink, &SequentialExecutor);
+
+        let (mut full_sink, full_commands) = new_sink();
+        new_scene.build(BuildOptions::default(), &mut full_sink, &SequentialExecutor);
+
+        let incremental_tiles = tile_signatures(&incremental_commands.lock().unwrap());
+        let full_tiles = tile_signatures(&full_commands.lock().unwrap());
+        assert!(!full_tiles.is_empty());
+        assert_eq!(incremental_tiles, full_tiles);
+    }
+
+    fn build_scene(rects: &[RectF]) -> Scene {
+        let mut scene = Scene::new();
+        scene.set_view_box(RectF::new(Vector2F::zero(), vec2f(256.0, 256.0)));
+        let paint = scene.push_paint(&Paint::from_color(rgbu(0, 0, 0)));
+        for &rect in rects {
+            let outline = Outline::from_rect_rounded(rect, vec2f(8.0, 8.0));
+            scene.push_draw_path(DrawPath::new(outline, paint));
+        }
+        scene
+    }
+
+    fn new_sink() -> (SceneSink<'static>, Arc<Mutex<Vec<RenderCommand>>>) {
+        let commands = Arc::new(Mutex::new(vec![]));
+        let commands_for_listener = commands.clone();
+        let listener = RenderCommandListener::new(Box::new(move |command| {
+            commands_for_listener.lock().unwrap().push(command)
+        }));
+        (SceneSink::new(listener, RendererLevel::D3D9), commands)
+    }
+
+    fn tile_signatures(commands: &[RenderCommand]) -> Vec<TileSignature> {
+        let mut fills: HashMap<u32, Vec<(u16, u16, u16, u16)>> = HashMap::new();
+        for command in commands {
+            if let RenderCommand::AddFillsD3D9(ref batch) = *command {
+                for fill in batch {
+                    let segment = fill.line_segment;
+                    fills.entry(fill.link)
+                         .or_insert_with(Vec::new)
+                         .push((segment.from_x, segment.from_y, segment.to_x, segment.to_y));
+                }
+            }
+        }
+
+        let mut signatures = vec![];
+        for command in commands {
+            if let RenderCommand::DrawTilesD3D9(ref batch) = *command {
+                for tile in &batch.tiles {
+                    let mut tile_fills = if tile.alpha_tile_id.is_valid() {
+                        fills.get(&tile.alpha_tile_id.0).cloned().unwrap_or_else(Vec::new)
+                    } else {
+                        vec![]
+                    };
+                    tile_fills.sort();
+                    signatures.push((tile.path_id.0,
+                                     tile.tile_x,
+                                     tile.tile_y,
+                                     tile.backdrop,
+                                     tile.ctrl,
+                                     tile.color,
+                                     tile_fills));
+                }
+            }
+        }
+        signatures.sort();
+        signatures
+    }
+}
@@ -27,8 +27,6 @@ use pathfinder_geometry::vector::{Vector2F, Vector2I, vec2f, vec2i};
 use pathfinder_simd::default::{F32x2, U32x2};
 use std::f32::NEG_INFINITY;
 
-const FLATTENING_TOLERANCE: f32 = 0.25;
-
 pub(crate) struct Tiler<'a, 'b, 'c, 'd> {
     scene_builder: &'a SceneBuilder<'b, 'a, 'c, 'd>,
     pub(crate) object_builder: ObjectBuilder,
@@ -83,9 +81,14 @@ impl<'a, 'b, 'c, 'd> Tiler<'a, 'b, 'c, 'd> {
     fn generate_fills(&mut self) {
         debug_assert_eq!(self.scene_builder.sink.renderer_level, RendererLevel::D3D9);
 
+        let quality = self.scene_builder.built_options.antialiasing_quality;
+        let tolerance = quality.flattening_tolerance();
         for contour in self.outline.contours() {
             for segment in contour.iter(ContourIterFlags::empty()) {
-                process_segment(&segment, self.scene_builder, &mut self.object_builder);
+                process_segment(&segment,
+                                tolerance,
+                                self.scene_builder,
+                                &mut self.object_builder);
             }
         }
     }
@@ -164,23 +167,24 @@ impl<'a, 'b, 'c, 'd> Tiler<'a, 'b, 'c, 'd> {
 }
 
 fn process_segment(segment: &Segment,
+                   tolerance: f32,
                    scene_builder: &SceneBuilder,
                    object_builder: &mut ObjectBuilder) {
     // TODO(pcwalton): Stop degree elevating.
     if segment.is_quadratic() {
         let cubic = segment.to_cubic();
-        return process_segment(&cubic, scene_builder, object_builder);
+        return process_segment(&cubic, tolerance, scene_builder, object_builder);
     }
 
     if segment.is_line() ||
-            (segment.is_cubic() && segment.as_cubic_segment().is_flat(FLATTENING_TOLERANCE)) {
+            (segment.is_cubic() && segment.as_cubic_segment().is_flat(tolerance)) {
         return process_line_segment(segment.baseline, scene_builder, object_builder);
     }
 
     // TODO(pcwalton): Use a smarter flattening algorithm.
     let (prev, next) = segment.split(0.5);
-    process_segment(&prev, scene_builder, object_builder);
-    process_segment(&next, scene_builder, object_builder);
+    process_segment(&prev, tolerance, scene_builder, object_builder);
+    process_segment(&next, tolerance, scene_builder, object_builder);
 }
 
 // This is the meat of the technique. It implements the fast lattice-clipping algorithm from
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::gpu_data::{TILE_CTRL_MASK_0_SHIFT, TILE_CTRL_MASK_EVEN_ODD};
+use crate::gpu_data::{TILE_CTRL_MASK_0_SHIFT, TILE_CTRL_MASK_ALIASED, TILE_CTRL_MASK_EVEN_ODD};
 use crate::gpu_data::{TILE_CTRL_MASK_WINDING, TileObjectPrimitive};
 use crate::paint::PaintId;
 use pathfinder_content::effects::BlendMode;
@@ -16,7 +16,9 @@ use pathfinder_content::fill::FillRule;
 use pathfinder_geometry::rect::{RectF, RectI};
 use pathfinder_geometry::vector::vec2f;
 
+/// The width of the tiles that scenes are divided into for rendering, in device pixels.
 pub const TILE_WIDTH: u32 = 16;
+/// The height of the tiles that scenes are divided into for rendering, in device pixels.
 pub const TILE_HEIGHT: u32 = 16;
 
 #[derive(Clone, Copy)]
@@ -30,6 +32,7 @@ pub(crate) struct DrawTilingPathInfo {
     pub(crate) paint_id: PaintId,
     pub(crate) blend_mode: BlendMode,
     pub(crate) fill_rule: FillRule,
+    pub(crate) aliased: bool,
 }
 
 impl TilingPathInfo {
@@ -54,6 +57,9 @@ impl TilingPathInfo {
                         ctrl |= (TILE_CTRL_MASK_WINDING << TILE_CTRL_MASK_0_SHIFT) as u8
                     }
                 }
+                if draw_tiling_path_info.aliased {
+                    ctrl |= (TILE_CTRL_MASK_ALIASED << TILE_CTRL_MASK_0_SHIFT) as u8
+                }
             }
             TilingPathInfo::Clip => {}
         }
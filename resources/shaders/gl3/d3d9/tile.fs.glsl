@@ -563,7 +563,8 @@ float sampleMask(float maskAlpha,
                  sampler2D maskTexture,
                  vec2 maskTextureSize,
                  vec3 maskTexCoord,
-                 int maskCtrl){
+                 int maskCtrl,
+                 float coverageGamma){
     if(maskCtrl == 0)
         return maskAlpha;
 
@@ -575,6 +576,15 @@ float sampleMask(float maskAlpha,
         coverage = abs(coverage);
     else
         coverage = 1.0 - abs(1.0 - mod(coverage, 2.0));
+
+
+
+    if((maskCtrl & 0x4)!= 0)
+        coverage = step(0.5, coverage);
+
+
+
+    coverage = pow(min(coverage, 1.0), coverageGamma);
     return min(maskAlpha, coverage);
 }
 
@@ -597,11 +607,17 @@ vec4 calculateColor(vec2 fragCoord,
                     vec3 maskTexCoord0,
                     vec2 colorTexCoord0,
                     vec4 baseColor,
-                    int tileCtrl){
+                    int tileCtrl,
+                    float coverageGamma){
 
-    int maskCtrl0 =(tileCtrl >> 0)& 0x3;
+    int maskCtrl0 =(tileCtrl >> 0)& 0x7;
     float maskAlpha = 1.0;
-    maskAlpha = sampleMask(maskAlpha, maskTexture0, maskTextureSize0, maskTexCoord0, maskCtrl0);
+    maskAlpha = sampleMask(maskAlpha,
+                           maskTexture0,
+                           maskTextureSize0,
+                           maskTexCoord0,
+                           maskCtrl0,
+                           coverageGamma);
 
 
     vec4 color = baseColor;
@@ -644,6 +660,7 @@ uniform sampler2D uGammaLUT;
 uniform vec2 uColorTextureSize0;
 uniform vec2 uMaskTextureSize0;
 uniform vec2 uFramebufferSize;
+uniform float uCoverageGamma;
 
 in vec3 vMaskTexCoord0;
 in vec2 vColorTexCoord0;
@@ -680,6 +697,7 @@ void main(){
                                 vMaskTexCoord0,
                                 vColorTexCoord0,
                                 vBaseColor,
-                                int(vTileCtrl));
+                                int(vTileCtrl),
+                                uCoverageGamma);
 }
 
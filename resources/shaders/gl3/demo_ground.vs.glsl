@@ -24,9 +24,11 @@ uniform int uGridlineCount;
 in ivec2 aPosition;
 
 out vec2 vTexCoord;
+out float vDepth;
 
 void main(){
     vTexCoord = vec2(aPosition * uGridlineCount);
     gl_Position = uTransform * vec4(ivec4(aPosition . x, 0, aPosition . y, 1));
+    vDepth = gl_Position . w;
 }
 
@@ -565,7 +565,8 @@ float sampleMask(float maskAlpha,
                  sampler2D maskTexture,
                  vec2 maskTextureSize,
                  vec3 maskTexCoord,
-                 int maskCtrl){
+                 int maskCtrl,
+                 float coverageGamma){
     if(maskCtrl == 0)
         return maskAlpha;
 
@@ -577,6 +578,15 @@ float sampleMask(float maskAlpha,
         coverage = abs(coverage);
     else
         coverage = 1.0 - abs(1.0 - mod(coverage, 2.0));
+
+
+
+    if((maskCtrl & 0x4)!= 0)
+        coverage = step(0.5, coverage);
+
+
+
+    coverage = pow(min(coverage, 1.0), coverageGamma);
     return min(maskAlpha, coverage);
 }
 
@@ -599,11 +609,17 @@ vec4 calculateColor(vec2 fragCoord,
                     vec3 maskTexCoord0,
                     vec2 colorTexCoord0,
                     vec4 baseColor,
-                    int tileCtrl){
+                    int tileCtrl,
+                    float coverageGamma){
 
-    int maskCtrl0 =(tileCtrl >> 0)& 0x3;
+    int maskCtrl0 =(tileCtrl >> 0)& 0x7;
     float maskAlpha = 1.0;
-    maskAlpha = sampleMask(maskAlpha, maskTexture0, maskTextureSize0, maskTexCoord0, maskCtrl0);
+    maskAlpha = sampleMask(maskAlpha,
+                           maskTexture0,
+                           maskTextureSize0,
+                           maskTexCoord0,
+                           maskCtrl0,
+                           coverageGamma);
 
 
     vec4 color = baseColor;
@@ -709,6 +725,7 @@ uniform vec2 uColorTextureSize0;
 uniform vec2 uMaskTextureSize0;
 uniform vec2 uFramebufferSize;
 uniform ivec2 uFramebufferTileSize;
+uniform float uCoverageGamma;
 layout(rgba8)uniform image2D uDestImage;
 
 layout(std430, binding = 0)buffer bTiles {
@@ -773,7 +790,7 @@ void main(){
 
                 backdrop = int(tileControlWord)>> 24;
                 maskTileCoord = uvec2(0u);
-                tileCtrl &= ~(0x3 << 0);
+                tileCtrl &= ~(0x7 << 0);
             }
 
             vec3 maskTexCoord0 = vec3(vec2(ivec2(maskTileCoord)+ tileSubCoord), backdrop);
@@ -814,7 +831,8 @@ void main(){
                                            maskTexCoord0,
                                            colorTexCoord0,
                                            baseColor,
-                                           tileCtrl);
+                                           tileCtrl,
+                                           uCoverageGamma);
 
             destColors[subY]= destColors[subY]*(1.0 - srcColor . a)+ srcColor;
         }
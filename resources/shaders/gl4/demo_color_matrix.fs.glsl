@@ -0,0 +1,34 @@
+#version {{version}}
+// Automatically generated from files in pathfinder/shaders/. Do not edit!
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+precision highp float;
+
+
+
+
+
+uniform sampler2D uSource;
+uniform mat4 uColorMatrix;
+
+in vec2 vTexCoord;
+
+out vec4 oFragColor;
+
+void main(){
+    oFragColor = uColorMatrix * texture(uSource, vTexCoord);
+}
+
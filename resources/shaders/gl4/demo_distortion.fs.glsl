@@ -0,0 +1,45 @@
+#version {{version}}
+// Automatically generated from files in pathfinder/shaders/. Do not edit!
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+precision highp float;
+
+
+
+
+
+uniform sampler2D uSource;
+uniform vec2 uCoefficients;
+uniform float uAspectRatio;
+
+in vec2 vTexCoord;
+
+out vec4 oFragColor;
+
+void main(){
+
+
+    vec2 aspect = vec2(uAspectRatio, 1.0);
+    vec2 position =(vTexCoord * 2.0 - 1.0)* aspect;
+    float radiusSquared = dot(position, position);
+    float scale = 1.0 + radiusSquared *(uCoefficients . x + radiusSquared * uCoefficients . y);
+    vec2 texCoord =(position * scale / aspect + 1.0)* 0.5;
+
+
+    vec2 inBounds = step(vec2(0.0), texCoord)* step(texCoord, vec2(1.0));
+    oFragColor = texture(uSource, texCoord)* inBounds . x * inBounds . y;
+}
+
@@ -20,13 +20,30 @@ precision highp float;
 
 uniform vec4 uGroundColor;
 uniform vec4 uGridlineColor;
+uniform vec4 uHorizonColor;
+
+uniform float uFade;
+
+uniform float uFadeDistance;
 
 in vec2 vTexCoord;
+in float vDepth;
 
 out vec4 oFragColor;
 
 void main(){
-    vec2 texCoordPx = fract(vTexCoord)/ fwidth(vTexCoord);
-    oFragColor = any(lessThanEqual(texCoordPx, vec2(1.0)))? uGridlineColor : uGroundColor;
+    vec2 cellsPerPx = fwidth(vTexCoord);
+    vec2 texCoordPx = fract(vTexCoord)/ cellsPerPx;
+    vec4 color = any(lessThanEqual(texCoordPx, vec2(1.0)))? uGridlineColor : uGroundColor;
+
+
+
+
+    float density = max(cellsPerPx . x, cellsPerPx . y);
+    float horizon = max(smoothstep(0.25, 1.0, density),
+                        smoothstep(0.5, 1.0, vDepth / uFadeDistance));
+    vec4 fadedColor = mix(color, uGroundColor, smoothstep(0.05, 0.25, density));
+    fadedColor = mix(fadedColor, uHorizonColor, horizon);
+    oFragColor = mix(color, fadedColor, uFade);
 }
 
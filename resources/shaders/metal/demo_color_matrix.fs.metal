@@ -0,0 +1,23 @@
+// Automatically generated from files in pathfinder/shaders/. Do not edit!
+#include <metal_stdlib>
+#include <simd/simd.h>
+
+using namespace metal;
+
+struct main0_out
+{
+    float4 oFragColor [[color(0)]];
+};
+
+struct main0_in
+{
+    float2 vTexCoord [[user(locn0)]];
+};
+
+fragment main0_out main0(main0_in in [[stage_in]], constant float4x4& uColorMatrix [[buffer(0)]], texture2d<float> uSource [[texture(0)]], sampler uSourceSmplr [[sampler(0)]])
+{
+    main0_out out = {};
+    out.oFragColor = uColorMatrix * uSource.sample(uSourceSmplr, in.vTexCoord);
+    return out;
+}
+
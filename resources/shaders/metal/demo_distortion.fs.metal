@@ -0,0 +1,29 @@
+// Automatically generated from files in pathfinder/shaders/. Do not edit!
+#include <metal_stdlib>
+#include <simd/simd.h>
+
+using namespace metal;
+
+struct main0_out
+{
+    float4 oFragColor [[color(0)]];
+};
+
+struct main0_in
+{
+    float2 vTexCoord [[user(locn0)]];
+};
+
+fragment main0_out main0(main0_in in [[stage_in]], constant float& uAspectRatio [[buffer(0)]], constant float2& uCoefficients [[buffer(1)]], texture2d<float> uSource [[texture(0)]], sampler uSourceSmplr [[sampler(0)]])
+{
+    main0_out out = {};
+    float2 aspect = float2(uAspectRatio, 1.0);
+    float2 position = ((in.vTexCoord * 2.0) - float2(1.0)) * aspect;
+    float radiusSquared = dot(position, position);
+    float scale = 1.0 + (radiusSquared * (uCoefficients.x + (radiusSquared * uCoefficients.y)));
+    float2 texCoord = (((position * scale) / aspect) + float2(1.0)) * 0.5;
+    float2 inBounds = step(float2(0.0), texCoord) * step(texCoord, float2(1.0));
+    out.oFragColor = (uSource.sample(uSourceSmplr, texCoord) * inBounds.x) * inBounds.y;
+    return out;
+}
+
@@ -12,13 +12,20 @@ struct main0_out
 struct main0_in
 {
     float2 vTexCoord [[user(locn0)]];
+    float vDepth [[user(locn1)]];
 };
 
-fragment main0_out main0(main0_in in [[stage_in]], constant float4& uGridlineColor [[buffer(0)]], constant float4& uGroundColor [[buffer(1)]])
+fragment main0_out main0(main0_in in [[stage_in]], constant float& uFade [[buffer(0)]], constant float& uFadeDistance [[buffer(1)]], constant float4& uGridlineColor [[buffer(2)]], constant float4& uGroundColor [[buffer(3)]], constant float4& uHorizonColor [[buffer(4)]])
 {
     main0_out out = {};
-    float2 texCoordPx = fract(in.vTexCoord) / fwidth(in.vTexCoord);
-    out.oFragColor = select(uGroundColor, uGridlineColor, bool4(any(texCoordPx <= float2(1.0))));
+    float2 cellsPerPx = fwidth(in.vTexCoord);
+    float2 texCoordPx = fract(in.vTexCoord) / cellsPerPx;
+    float4 color = select(uGroundColor, uGridlineColor, bool4(any(texCoordPx <= float2(1.0))));
+    float density = fast::max(cellsPerPx.x, cellsPerPx.y);
+    float horizon = fast::max(smoothstep(0.25, 1.0, density), smoothstep(0.5, 1.0, in.vDepth / uFadeDistance));
+    float4 fadedColor = mix(color, uGroundColor, float4(smoothstep(0.0500000007450580596923828125, 0.25, density)));
+    fadedColor = mix(fadedColor, uHorizonColor, float4(horizon));
+    out.oFragColor = mix(color, fadedColor, float4(uFade));
     return out;
 }
 
@@ -7,6 +7,7 @@ using namespace metal;
 struct main0_out
 {
     float2 vTexCoord [[user(locn0)]];
+    float vDepth [[user(locn1)]];
     float4 gl_Position [[position]];
 };
 
@@ -20,6 +21,7 @@ vertex main0_out main0(main0_in in [[stage_in]], constant int& uGridlineCount [[
     main0_out out = {};
     out.vTexCoord = float2(in.aPosition * int2(uGridlineCount));
     out.gl_Position = uTransform * float4(int4(in.aPosition.x, 0, in.aPosition.y, 1));
+    out.vDepth = out.gl_Position.w;
     return out;
 }
 
@@ -31,4 +31,17 @@ impl ResourceLoader for EmbeddedResourceLoader {
             None => Err(IOError::from(ErrorKind::NotFound)),
         }
     }
+
+    fn list_directory(&self, virtual_directory: &str) -> Option<Vec<String>> {
+        let prefix = format!("{}/", virtual_directory);
+        let mut virtual_paths: Vec<String> = RESOURCES.iter().filter_map(|&(path, _)| {
+            if path.starts_with(&prefix) && !path[prefix.len()..].contains('/') {
+                Some(path.to_owned())
+            } else {
+                None
+            }
+        }).collect();
+        virtual_paths.sort();
+        Some(virtual_paths)
+    }
 }
@@ -12,7 +12,7 @@
 
 use crate::ResourceLoader;
 use std::env;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{Error as IOError, Read};
 use std::path::PathBuf;
 
@@ -59,4 +59,23 @@ impl ResourceLoader for FilesystemResourceLoader {
         File::open(&path)?.read_to_end(&mut data)?;
         Ok(data)
     }
+
+    fn list_directory(&self, virtual_directory: &str) -> Option<Vec<String>> {
+        let mut path = self.directory.clone();
+        virtual_directory
+            .split('/')
+            .for_each(|segment| path.push(segment));
+
+        let mut virtual_paths: Vec<String> = fs::read_dir(&path)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().into_string().ok()?;
+                Some(format!("{}/{}", virtual_directory, file_name))
+            })
+            .collect();
+        virtual_paths.sort();
+        Some(virtual_paths)
+    }
 }
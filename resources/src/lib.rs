@@ -26,4 +26,10 @@ pub trait ResourceLoader {
     /// This is deliberately not a `Path`, because these are virtual paths
     /// that do not necessarily correspond to real paths on a filesystem.
     fn slurp(&self, path: &str) -> Result<Vec<u8>, IOError>;
+
+    /// Returns the virtual paths of the resources directly inside the given virtual directory, in
+    /// sorted order, or `None` if this loader can't enumerate its resources.
+    fn list_directory(&self, _directory: &str) -> Option<Vec<String>> {
+        None
+    }
 }
@@ -45,6 +45,7 @@ uniform vec2 uColorTextureSize0;
 uniform vec2 uMaskTextureSize0;
 uniform vec2 uFramebufferSize;
 uniform ivec2 uFramebufferTileSize;
+uniform float uCoverageGamma;
 layout(rgba8) uniform image2D uDestImage;
 
 layout(std430, binding = 0) buffer bTiles {
@@ -150,7 +151,8 @@ void main() {
                                            maskTexCoord0,
                                            colorTexCoord0,
                                            baseColor,
-                                           tileCtrl);
+                                           tileCtrl,
+                                           uCoverageGamma);
 
             destColors[subY] = destColors[subY] * (1.0 - srcColor.a) + srcColor;
         }
@@ -27,6 +27,7 @@ uniform sampler2D uGammaLUT;
 uniform vec2 uColorTextureSize0;
 uniform vec2 uMaskTextureSize0;
 uniform vec2 uFramebufferSize;
+uniform float uCoverageGamma;
 
 in vec3 vMaskTexCoord0;
 in vec2 vColorTexCoord0;
@@ -63,5 +64,6 @@ void main() {
                                 vMaskTexCoord0,
                                 vColorTexCoord0,
                                 vBaseColor,
-                                int(vTileCtrl));
+                                int(vTileCtrl),
+                                uCoverageGamma);
 }
@@ -0,0 +1,30 @@
+#version 330
+
+// pathfinder/shaders/demo_color_matrix.fs.glsl
+//
+// Copyright © 2020 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Transforms the colors of a finished frame, such as to simulate a color vision deficiency.
+
+precision highp float;
+
+#ifdef GL_ES
+precision highp sampler2D;
+#endif
+
+uniform sampler2D uSource;
+uniform mat4 uColorMatrix;
+
+in vec2 vTexCoord;
+
+out vec4 oFragColor;
+
+void main() {
+    oFragColor = uColorMatrix * texture(uSource, vTexCoord);
+}
@@ -0,0 +1,41 @@
+#version 330
+
+// pathfinder/shaders/demo_distortion.fs.glsl
+//
+// Copyright © 2020 The Pathfinder Project Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Applies radial barrel distortion to cancel out the pincushion distortion of an HMD lens.
+
+precision highp float;
+
+#ifdef GL_ES
+precision highp sampler2D;
+#endif
+
+uniform sampler2D uSource;
+uniform vec2 uCoefficients;
+uniform float uAspectRatio;
+
+in vec2 vTexCoord;
+
+out vec4 oFragColor;
+
+void main() {
+    // Measure the distance from the lens center in units of the viewport's half-height, so that
+    // the distortion stays circular on non-square viewports.
+    vec2 aspect = vec2(uAspectRatio, 1.0);
+    vec2 position = (vTexCoord * 2.0 - 1.0) * aspect;
+    float radiusSquared = dot(position, position);
+    float scale = 1.0 + radiusSquared * (uCoefficients.x + radiusSquared * uCoefficients.y);
+    vec2 texCoord = (position * scale / aspect + 1.0) * 0.5;
+
+    // Anything pulled in from outside the source image is black.
+    vec2 inBounds = step(vec2(0.0), texCoord) * step(texCoord, vec2(1.0));
+    oFragColor = texture(uSource, texCoord) * inBounds.x * inBounds.y;
+}
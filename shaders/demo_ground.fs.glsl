@@ -18,12 +18,29 @@ precision highp sampler2D;
 
 uniform vec4 uGroundColor;
 uniform vec4 uGridlineColor;
+uniform vec4 uHorizonColor;
+// 1.0 to fade the grid out with distance, for an unbounded ground plane; 0.0 otherwise.
+uniform float uFade;
+// The eye-space distance by which the ground has fully faded into the horizon.
+uniform float uFadeDistance;
 
 in vec2 vTexCoord;
+in float vDepth;
 
 out vec4 oFragColor;
 
 void main() {
-    vec2 texCoordPx = fract(vTexCoord) / fwidth(vTexCoord);
-    oFragColor = any(lessThanEqual(texCoordPx, vec2(1.0))) ? uGridlineColor : uGroundColor;
+    vec2 cellsPerPx = fwidth(vTexCoord);
+    vec2 texCoordPx = fract(vTexCoord) / cellsPerPx;
+    vec4 color = any(lessThanEqual(texCoordPx, vec2(1.0))) ? uGridlineColor : uGroundColor;
+
+    // Far away, grid cells shrink to a few pixels and the lines alias, so blend them into the
+    // ground, and then blend the ground into the horizon so that neither the far clip plane nor
+    // the edge of the plane is visible.
+    float density = max(cellsPerPx.x, cellsPerPx.y);
+    float horizon = max(smoothstep(0.25, 1.0, density),
+                        smoothstep(0.5, 1.0, vDepth / uFadeDistance));
+    vec4 fadedColor = mix(color, uGroundColor, smoothstep(0.05, 0.25, density));
+    fadedColor = mix(fadedColor, uHorizonColor, horizon);
+    oFragColor = mix(color, fadedColor, uFade);
 }
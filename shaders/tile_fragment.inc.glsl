@@ -32,9 +32,10 @@
 #define FRAC_6_PI   1.9098593171027443
 #define FRAC_PI_3   1.0471975511965976
 
-#define TILE_CTRL_MASK_MASK                     0x3
+#define TILE_CTRL_MASK_MASK                     0x7
 #define TILE_CTRL_MASK_WINDING                  0x1
 #define TILE_CTRL_MASK_EVEN_ODD                 0x2
+#define TILE_CTRL_MASK_ALIASED                  0x4
 
 #define TILE_CTRL_MASK_0_SHIFT                  0
 
@@ -540,7 +541,8 @@ float sampleMask(float maskAlpha,
                  sampler2D maskTexture,
                  vec2 maskTextureSize,
                  vec3 maskTexCoord,
-                 int maskCtrl) {
+                 int maskCtrl,
+                 float coverageGamma) {
     if (maskCtrl == 0)
         return maskAlpha;
 
@@ -552,6 +554,15 @@ float sampleMask(float maskAlpha,
         coverage = abs(coverage);
     else
         coverage = 1.0 - abs(1.0 - mod(coverage, 2.0));
+
+    // Discard the fractional coverage computed by the fill stage, so that each pixel is either
+    // fully inside or fully outside the path.
+    if ((maskCtrl & TILE_CTRL_MASK_ALIASED) != 0)
+        coverage = step(0.5, coverage);
+
+    // Adjust how coverage maps to alpha, to match other rasterizers. An exponent of 1 leaves it
+    // linear.
+    coverage = pow(min(coverage, 1.0), coverageGamma);
     return min(maskAlpha, coverage);
 }
 
@@ -574,11 +585,17 @@ vec4 calculateColor(vec2 fragCoord,
                     vec3 maskTexCoord0,
                     vec2 colorTexCoord0,
                     vec4 baseColor,
-                    int tileCtrl) {
+                    int tileCtrl,
+                    float coverageGamma) {
     // Sample mask.
     int maskCtrl0 = (tileCtrl >> TILE_CTRL_MASK_0_SHIFT) & TILE_CTRL_MASK_MASK;
     float maskAlpha = 1.0;
-    maskAlpha = sampleMask(maskAlpha, maskTexture0, maskTextureSize0, maskTexCoord0, maskCtrl0);
+    maskAlpha = sampleMask(maskAlpha,
+                           maskTexture0,
+                           maskTextureSize0,
+                           maskTexCoord0,
+                           maskCtrl0,
+                           coverageGamma);
 
     // Sample color.
     vec4 color = baseColor;
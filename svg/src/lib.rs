@@ -287,41 +287,35 @@ impl SVGScene {
     }
 }
 
+impl BuildResultFlags {
+    /// Returns human-readable names of the unsupported features that were encountered.
+    pub fn feature_names(&self) -> Vec<&'static str> {
+        BUILD_RESULT_FLAG_NAMES.iter()
+                               .enumerate()
+                               .filter(|&(bit, _)| (self.bits() >> bit) & 1 != 0)
+                               .map(|(_, &name)| name)
+                               .collect()
+    }
+}
+
 impl Display for BuildResultFlags {
     fn fmt(&self, formatter: &mut Formatter) -> FormatResult {
-        if self.is_empty() {
-            return Ok(());
-        }
-
-        let mut first = true;
-        for (bit, name) in NAMES.iter().enumerate() {
-            if (self.bits() >> bit) & 1 == 0 {
-                continue;
-            }
-            if !first {
-                formatter.write_str(", ")?;
-            } else {
-                first = false;
-            }
-            formatter.write_str(name)?;
-        }
-
-        return Ok(());
-
-        // Must match the order in `BuildResultFlags`.
-        static NAMES: &'static [&'static str] = &[
-            "<filter>",
-            "<image>",
-            "<mask>",
-            "<pattern>",
-            "multiple clip paths",
-            "non-color paint",
-            "filter attribute",
-            "mask attribute",
-        ];
+        formatter.write_str(&self.feature_names().join(", "))
     }
 }
 
+// Must match the order in `BuildResultFlags`.
+static BUILD_RESULT_FLAG_NAMES: &'static [&'static str] = &[
+    "<filter>",
+    "<image>",
+    "<mask>",
+    "<pattern>",
+    "multiple clip paths",
+    "non-color paint",
+    "filter attribute",
+    "mask attribute",
+];
+
 trait PaintExt {
     fn from_svg_paint(svg_paint: &UsvgPaint,
                       transform: &Transform2F,
@@ -18,6 +18,7 @@ extern crate serde_derive;
 
 use hashbrown::HashMap;
 use pathfinder_color::ColorU;
+use pathfinder_geometry::line_segment::LineSegment2F;
 use pathfinder_geometry::rect::RectI;
 use pathfinder_geometry::vector::{Vector2F, Vector2I, vec2i};
 use pathfinder_gpu::allocator::{BufferTag, GPUMemoryAllocator};
@@ -131,6 +132,65 @@ impl<D> UIPresenter<D> where D: Device {
         self.draw_rect(device, allocator, rect, color, true);
     }
 
+    // Draws many rectangles of the same color in a single draw call.
+    pub fn draw_solid_rects(&self,
+                            device: &D,
+                            allocator: &mut GPUMemoryAllocator<D>,
+                            rects: &[RectI],
+                            color: ColorU) {
+        if rects.is_empty() {
+            return;
+        }
+
+        let mut vertex_data = Vec::with_capacity(rects.len() * 4);
+        let mut index_data = Vec::with_capacity(rects.len() * 6);
+        for rect in rects {
+            let first_index = vertex_data.len() as u32;
+            vertex_data.extend_from_slice(&[
+                DebugSolidVertex::new(rect.origin()),
+                DebugSolidVertex::new(rect.upper_right()),
+                DebugSolidVertex::new(rect.lower_right()),
+                DebugSolidVertex::new(rect.lower_left()),
+            ]);
+            index_data.extend(QUAD_INDICES.iter().map(|index| first_index + index));
+        }
+
+        self.draw_solid_rects_with_vertex_data(device,
+                                               allocator,
+                                               &vertex_data,
+                                               &index_data,
+                                               color,
+                                               true);
+    }
+
+    // Draws many lines of the same color in a single draw call. Endpoints are rounded to the
+    // nearest device pixel.
+    pub fn draw_line_segments(&self,
+                              device: &D,
+                              allocator: &mut GPUMemoryAllocator<D>,
+                              line_segments: &[LineSegment2F],
+                              color: ColorU) {
+        if line_segments.is_empty() {
+            return;
+        }
+
+        let mut vertex_data = Vec::with_capacity(line_segments.len() * 2);
+        let mut index_data = Vec::with_capacity(line_segments.len() * 2);
+        for line_segment in line_segments {
+            let first_index = vertex_data.len() as u32;
+            vertex_data.push(DebugSolidVertex::new(line_segment.from().round().to_i32()));
+            vertex_data.push(DebugSolidVertex::new(line_segment.to().round().to_i32()));
+            index_data.extend_from_slice(&[first_index, first_index + 1]);
+        }
+
+        self.draw_solid_rects_with_vertex_data(device,
+                                               allocator,
+                                               &vertex_data,
+                                               &index_data,
+                                               color,
+                                               false);
+    }
+
     pub fn draw_rect_outline(&self,
                              device: &D,
                              allocator: &mut GPUMemoryAllocator<D>,
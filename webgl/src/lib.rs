@@ -396,7 +396,8 @@ unsafe fn check_and_extract_data(
 ) -> Object {
     let channels = match (format, data_ref) {
         (TextureFormat::R8, TextureDataRef::U8(_)) => 1,
-        (TextureFormat::RGBA8, TextureDataRef::U8(_)) => 4,
+        (TextureFormat::RGBA8, TextureDataRef::U8(_)) |
+        (TextureFormat::SRGBA8, TextureDataRef::U8(_)) => 4,
         (TextureFormat::RGBA16F, TextureDataRef::F16(_)) => 4,
         (TextureFormat::RGBA32F, TextureDataRef::F32(_)) => 4,
         _ => panic!("Unimplemented texture format!"),
@@ -453,6 +454,16 @@ impl Device for WebGlDevice {
         FeatureLevel::D3D10
     }
 
+    fn max_texture_size(&self) -> i32 {
+        // WebGL 2 guarantees at least this much.
+        const MIN_MAX_TEXTURE_SIZE: i32 = 2048;
+        self.context
+            .get_parameter(WebGl::MAX_TEXTURE_SIZE)
+            .ok()
+            .and_then(|value| value.as_f64())
+            .map_or(MIN_MAX_TEXTURE_SIZE, |value| value as i32)
+    }
+
     fn create_texture(&self, format: TextureFormat, size: Vector2I) -> WebGlTexture {
         let texture = self.context.create_texture().unwrap();
         let texture = WebGlTexture {
@@ -1168,6 +1179,7 @@ impl TextureFormatExt for TextureFormat {
             TextureFormat::R8 => WebGl::R8,
             TextureFormat::R16F => WebGl::R16F,
             TextureFormat::RGBA8 => WebGl::RGBA,
+            TextureFormat::SRGBA8 => WebGl::SRGB8_ALPHA8,
             TextureFormat::RGBA16F => WebGl::RGBA16F,
             TextureFormat::RGBA32F => WebGl::RGBA32F,
         }
@@ -1176,13 +1188,18 @@ impl TextureFormatExt for TextureFormat {
     fn gl_format(self) -> u32 {
         match self {
             TextureFormat::R8 | TextureFormat::R16F => WebGl::RED,
-            TextureFormat::RGBA8 | TextureFormat::RGBA16F | TextureFormat::RGBA32F => WebGl::RGBA,
+            TextureFormat::RGBA8 |
+            TextureFormat::SRGBA8 |
+            TextureFormat::RGBA16F |
+            TextureFormat::RGBA32F => WebGl::RGBA,
         }
     }
 
     fn gl_type(self) -> u32 {
         match self {
-            TextureFormat::R8 | TextureFormat::RGBA8 => WebGl::UNSIGNED_BYTE,
+            TextureFormat::R8 | TextureFormat::RGBA8 | TextureFormat::SRGBA8 => {
+                WebGl::UNSIGNED_BYTE
+            }
             TextureFormat::R16F | TextureFormat::RGBA16F => WebGl::HALF_FLOAT,
             TextureFormat::RGBA32F => WebGl::FLOAT,
         }